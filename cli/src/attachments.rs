@@ -0,0 +1,388 @@
+//! Attachment chunking and encryption
+//!
+//! Each attachment gets a fresh XChaCha20-Poly1305 key. The file is split
+//! into fixed-size chunks, every chunk is encrypted under that key and
+//! stored as its own CID-addressed blob. The resulting [`AttachmentRef`]
+//! (filename, MIME type, size, content hash, key and chunk CIDs) travels
+//! inside the encrypted message body, so none of that metadata is
+//! visible on the DHT.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use crate::crypto::{self, ContentKey};
+use crate::storage;
+
+/// Plaintext bytes per chunk (keeps each blob well under the entry size limit)
+pub const CHUNK_SIZE: usize = 512 * 1024;
+
+/// Largest attachment accepted by `send --attach`
+pub const MAX_ATTACHMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Reference to an encrypted, chunked attachment
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AttachmentRef {
+    /// Original file name (no directory components)
+    pub filename: String,
+    /// MIME type guessed from the file extension
+    pub mime_type: String,
+    /// Plaintext size in bytes
+    pub size: u64,
+    /// Hex sha2-256 of the plaintext, checked after reassembly
+    pub sha256: String,
+    /// Hex XChaCha20-Poly1305 key the chunks are encrypted with
+    pub key: String,
+    /// CIDs of the encrypted chunks, in order
+    pub chunks: Vec<String>,
+}
+
+/// An encrypted chunk ready to be stored as a blob
+#[derive(Debug, Clone)]
+pub struct EncryptedChunk {
+    pub cid: String,
+    pub ciphertext: Vec<u8>,
+}
+
+/// Split and encrypt a file's contents
+pub fn encrypt_attachment(
+    filename: &str,
+    data: &[u8],
+) -> Result<(AttachmentRef, Vec<EncryptedChunk>)> {
+    if data.len() as u64 > MAX_ATTACHMENT_SIZE {
+        bail!(
+            "Attachment {} is {} bytes (limit {} bytes)",
+            filename,
+            data.len(),
+            MAX_ATTACHMENT_SIZE
+        );
+    }
+
+    let key = crypto::generate_content_key();
+
+    let mut chunks = Vec::new();
+    // An empty file still gets one (empty) chunk so it round-trips
+    let pieces: Vec<&[u8]> = if data.is_empty() {
+        vec![data]
+    } else {
+        data.chunks(CHUNK_SIZE).collect()
+    };
+    for piece in pieces {
+        let ciphertext = crypto::encrypt_with_key(&key, piece)
+            .context("Failed to encrypt attachment chunk")?;
+        chunks.push(EncryptedChunk {
+            cid: storage::compute_cid(&ciphertext),
+            ciphertext,
+        });
+    }
+
+    let reference = AttachmentRef {
+        filename: sanitize_filename(filename),
+        mime_type: guess_mime_type(filename).to_string(),
+        size: data.len() as u64,
+        sha256: hex::encode(Sha256::digest(data)),
+        key: hex::encode(key),
+        chunks: chunks.iter().map(|c| c.cid.clone()).collect(),
+    };
+
+    Ok((reference, chunks))
+}
+
+/// Check a received reference's size and chunk count before fetching it
+///
+/// The reference comes from the sender, so a size over
+/// [`MAX_ATTACHMENT_SIZE`] or a chunk list that doesn't match the size is
+/// rejected rather than trusted for allocation or fetching.
+pub fn check_reference(reference: &AttachmentRef) -> Result<()> {
+    if reference.size > MAX_ATTACHMENT_SIZE {
+        bail!(
+            "Attachment {} claims {} bytes (limit {} bytes)",
+            reference.filename,
+            reference.size,
+            MAX_ATTACHMENT_SIZE
+        );
+    }
+
+    let expected_chunks = (reference.size as usize).div_ceil(CHUNK_SIZE).max(1);
+    if reference.chunks.len() != expected_chunks {
+        bail!(
+            "Attachment {} lists {} chunk(s) for {} bytes, expected {}",
+            reference.filename,
+            reference.chunks.len(),
+            reference.size,
+            expected_chunks
+        );
+    }
+
+    Ok(())
+}
+
+/// Decrypt and reassemble an attachment from its fetched chunks
+///
+/// `chunks` must be in the same order as `reference.chunks`; `None` marks a
+/// chunk that could not be fetched. Missing, tampered or truncated chunks
+/// are reported instead of producing a corrupt file.
+pub fn reassemble_attachment(
+    reference: &AttachmentRef,
+    chunks: &[Option<Vec<u8>>],
+) -> Result<Vec<u8>> {
+    check_reference(reference)?;
+
+    if chunks.len() != reference.chunks.len() {
+        bail!(
+            "Attachment {} expects {} chunk(s), got {}",
+            reference.filename,
+            reference.chunks.len(),
+            chunks.len()
+        );
+    }
+
+    let missing: Vec<usize> = chunks
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_none())
+        .map(|(i, _)| i + 1)
+        .collect();
+    if !missing.is_empty() {
+        bail!(
+            "Attachment {} is incomplete: chunk(s) {:?} of {} have not replicated yet",
+            reference.filename,
+            missing,
+            reference.chunks.len()
+        );
+    }
+
+    let key_bytes = hex::decode(&reference.key).context("Attachment key is not valid hex")?;
+    if key_bytes.len() != 32 {
+        bail!("Attachment key must be 32 bytes");
+    }
+    let key = ContentKey::clone_from_slice(&key_bytes);
+
+    let mut data = Vec::with_capacity(reference.size as usize);
+    for (index, (cid, chunk)) in reference.chunks.iter().zip(chunks).enumerate() {
+        let ciphertext = chunk.as_deref().unwrap_or_default();
+        storage::verify_cid(cid, ciphertext)
+            .with_context(|| format!("Chunk {} of {} is corrupt", index + 1, reference.filename))?;
+        let plaintext = crypto::decrypt_with_key(&key, ciphertext).with_context(|| {
+            format!("Chunk {} of {} failed to decrypt", index + 1, reference.filename)
+        })?;
+        data.extend(plaintext);
+    }
+
+    if data.len() as u64 != reference.size {
+        bail!(
+            "Attachment {} is {} bytes, expected {}",
+            reference.filename,
+            data.len(),
+            reference.size
+        );
+    }
+    if hex::encode(Sha256::digest(&data)) != reference.sha256 {
+        bail!("Attachment {} failed its content hash check", reference.filename);
+    }
+
+    Ok(data)
+}
+
+/// Strip directory components so a sender cannot write outside the target dir
+pub fn sanitize_filename(filename: &str) -> String {
+    Path::new(filename)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.is_empty() && *name != "." && *name != "..")
+        .unwrap_or("attachment")
+        .to_string()
+}
+
+/// Write an attachment into `dir` without replacing any existing file
+///
+/// The sanitized filename is tried first, then `name (1).ext`, `name (2).ext`
+/// and so on, so attachments sharing a filename are all kept. Returns the
+/// path written.
+pub fn save_to_dir(dir: &Path, filename: &str, data: &[u8]) -> Result<PathBuf> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+    let filename = sanitize_filename(filename);
+    for copy in 0..1000 {
+        let path = dir.join(numbered_filename(&filename, copy));
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to create attachment: {}", path.display()));
+            }
+        };
+        file.write_all(data)
+            .with_context(|| format!("Failed to write attachment: {}", path.display()))?;
+        return Ok(path);
+    }
+
+    bail!("Too many files named {} in {}", filename, dir.display())
+}
+
+/// `report.pdf` -> `report (2).pdf`; copy 0 is the name itself
+fn numbered_filename(filename: &str, copy: usize) -> String {
+    if copy == 0 {
+        return filename.to_string();
+    }
+    match filename.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => {
+            format!("{} ({}).{}", stem, copy, extension)
+        }
+        _ => format!("{} ({})", filename, copy),
+    }
+}
+
+/// Guess a MIME type from the file extension
+pub fn guess_mime_type(filename: &str) -> &'static str {
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "ics" => "text/calendar",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Format a byte count for display
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KiB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fetched(chunks: &[EncryptedChunk]) -> Vec<Option<Vec<u8>>> {
+        chunks.iter().map(|c| Some(c.ciphertext.clone())).collect()
+    }
+
+    #[test]
+    fn test_attachment_roundtrip_multiple_chunks() {
+        let data: Vec<u8> = (0..(CHUNK_SIZE * 2 + 123)).map(|i| (i % 251) as u8).collect();
+        let (reference, chunks) = encrypt_attachment("report.pdf", &data).unwrap();
+
+        assert_eq!(reference.chunks.len(), 3);
+        assert_eq!(reference.mime_type, "application/pdf");
+        assert_eq!(reference.size, data.len() as u64);
+
+        let restored = reassemble_attachment(&reference, &fetched(&chunks)).unwrap();
+        assert_eq!(restored, data);
+    }
+
+    #[test]
+    fn test_empty_attachment_roundtrip() {
+        let (reference, chunks) = encrypt_attachment("empty.txt", b"").unwrap();
+        assert_eq!(reference.chunks.len(), 1);
+        assert!(reassemble_attachment(&reference, &fetched(&chunks)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missing_chunk_detected() {
+        let data = vec![1u8; CHUNK_SIZE + 10];
+        let (reference, chunks) = encrypt_attachment("data.bin", &data).unwrap();
+
+        let mut partial = fetched(&chunks);
+        partial[1] = None;
+
+        let err = reassemble_attachment(&reference, &partial).unwrap_err();
+        assert!(err.to_string().contains("incomplete"));
+    }
+
+    #[test]
+    fn test_tampered_chunk_detected() {
+        let (reference, chunks) = encrypt_attachment("notes.txt", b"hello").unwrap();
+        let mut tampered = fetched(&chunks);
+        tampered[0].as_mut().unwrap()[30] ^= 0x01;
+
+        assert!(reassemble_attachment(&reference, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_oversized_reference_rejected() {
+        let (mut reference, chunks) = encrypt_attachment("notes.txt", b"hello").unwrap();
+
+        reference.size = u64::MAX;
+        let err = reassemble_attachment(&reference, &fetched(&chunks)).unwrap_err();
+        assert!(err.to_string().contains("limit"));
+
+        // A size within the limit must still match the chunk count
+        reference.size = CHUNK_SIZE as u64 * 4;
+        assert!(check_reference(&reference).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("../../etc/passwd"), "passwd");
+        assert_eq!(sanitize_filename("/tmp/report.pdf"), "report.pdf");
+        assert_eq!(sanitize_filename(".."), "attachment");
+        assert_eq!(sanitize_filename("photo.png"), "photo.png");
+    }
+
+    #[test]
+    fn test_numbered_filename() {
+        assert_eq!(numbered_filename("report.pdf", 0), "report.pdf");
+        assert_eq!(numbered_filename("report.pdf", 2), "report (2).pdf");
+        assert_eq!(numbered_filename("archive.tar.gz", 1), "archive.tar (1).gz");
+        assert_eq!(numbered_filename(".env", 1), ".env (1)");
+        assert_eq!(numbered_filename("README", 1), "README (1)");
+    }
+
+    #[test]
+    fn test_save_to_dir_keeps_same_named_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let first = save_to_dir(dir.path(), "notes.txt", b"first").unwrap();
+        let second = save_to_dir(dir.path(), "../notes.txt", b"second").unwrap();
+
+        assert_eq!(first, dir.path().join("notes.txt"));
+        assert_eq!(second, dir.path().join("notes (1).txt"));
+        assert_eq!(fs::read(first).unwrap(), b"first");
+        assert_eq!(fs::read(second).unwrap(), b"second");
+    }
+
+    #[test]
+    fn test_guess_mime_type() {
+        assert_eq!(guess_mime_type("a.PNG"), "image/png");
+        assert_eq!(guess_mime_type("notes.txt"), "text/plain");
+        assert_eq!(guess_mime_type("unknown.xyz"), "application/octet-stream");
+        assert_eq!(guess_mime_type("no_extension"), "application/octet-stream");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
@@ -1,17 +1,37 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
 
+use crate::conductor::ConductorClient;
 use crate::config::Config;
+use crate::contacts::ContactBook;
+use crate::crypto;
+use crate::keystore;
+use crate::resolver::{
+    self, DidCache, DidResolver, KeyResolver, MycelixResolver, RegistryResolver, ResolverChain,
+    WebResolver,
+};
+use crate::storage;
 use crate::types::*;
+use crate::wire;
+
+/// Zome holding message entries and mailbox links
+pub(crate) const MAIL_ZOME: &str = "mail_messages";
+
+/// Zome holding trust scores and spam reports
+const TRUST_ZOME: &str = "trust_filter";
+
+/// Zome holding the private address book
+const CONTACTS_ZOME: &str = "contacts";
 
 /// Client for interacting with Mycelix Mail system
 ///
 /// Provides high-level methods for all mail operations, DID resolution,
 /// and trust score management. Handles connections to:
+/// - Holochain conductor (app websocket, zome calls)
 /// - DID registry (HTTP)
 /// - MATL bridge (HTTP)
-///
-/// NOTE: Phase C (Holochain integration) is BLOCKED pending proper API documentation.
-/// Current implementation uses educational stubs that demonstrate the architecture.
 pub struct MycellixClient {
     /// HTTP client for external services
     http_client: reqwest::Client,
@@ -19,8 +39,14 @@ pub struct MycellixClient {
     /// Configuration
     config: Config,
 
-    /// Holochain conductor URL (for future Phase C)
-    conductor_url: String,
+    /// Holochain conductor connection (opened on first zome call)
+    conductor: Arc<ConductorClient>,
+
+    /// DID resolution: DNA bindings, then the registry and other methods
+    resolvers: ResolverChain,
+
+    /// HTTP DID registry
+    registry: RegistryResolver,
 
     /// DID registry URL
     did_registry_url: String,
@@ -42,99 +68,425 @@ impl MycellixClient {
             .build()
             .context("Failed to create HTTP client")?;
 
+        let app_id = config
+            .conductor
+            .app_id
+            .clone()
+            .unwrap_or_else(|| "mycelix_mail".to_string());
+        let conductor = Arc::new(ConductorClient::new(
+            conductor_url,
+            &config.conductor.admin_url,
+            &app_id,
+            &config.conductor.role_name,
+        ));
+        let registry = RegistryResolver::new(http_client.clone(), did_registry_url);
+        let resolvers = ResolverChain::new(
+            vec![
+                Box::new(MycelixResolver::new(conductor.clone())),
+                Box::new(registry.clone()),
+                Box::new(KeyResolver),
+                Box::new(WebResolver::new(http_client.clone())),
+            ],
+            DidCache::new(
+                Config::cache_dir()?.join("dids.json"),
+                config.preferences.cache_ttl,
+            ),
+        );
+
         Ok(Self {
             http_client,
             config,
-            conductor_url: conductor_url.to_string(),
+            conductor,
+            resolvers,
+            registry,
             did_registry_url: did_registry_url.to_string(),
             matl_bridge_url: matl_bridge_url.to_string(),
         })
     }
 
     //
-    // ===== MAIL OPERATIONS (Stub - Phase C Pending) =====
+    // ===== MAIL OPERATIONS (Holochain zome calls) =====
     //
 
     /// Send a mail message
     ///
-    /// TODO (Phase C): Replace with real Holochain zome call to `mail_messages::send_message`
+    /// Calls `mail_messages::send_message`, returning the new message ID.
+    /// `recipients.bcc` is not sent: each BCC recipient is delivered one of
+    /// `bcc_copies` instead, separately encrypted for them.
+    /// `expires_at` (unix seconds) hides the message from everyone once passed.
+    #[allow(clippy::too_many_arguments)]
     pub async fn send_message(
         &self,
-        to_did: String,
+        recipients: Recipients,
         subject: Vec<u8>,
         body_cid: String,
-        _thread_id: Option<String>,
+        recipient_keys: Vec<Vec<u8>>,
+        bcc_copies: Vec<wire::BccCopy>,
+        thread_id: Option<String>,
         tier: EpistemicTier,
+        expires_at: Option<i64>,
     ) -> Result<String> {
-        println!("📧 [STUB] Would send message to {}", to_did);
-        println!("   Subject: {} bytes (encrypted)", subject.len());
-        println!("   Body CID: {}", body_cid);
-        println!("   Tier: {}", tier);
+        let input = wire::SendMessageInput {
+            message: wire::MailMessage {
+                from_did: self.whoami()?,
+                to_dids: recipients.to,
+                cc_dids: recipients.cc,
+                subject_encrypted: subject,
+                body_cid,
+                timestamp: wire::now(),
+                thread_id,
+                epistemic_tier: tier,
+                recipient_keys,
+                expires_at: expires_at.map(wire::from_unix_seconds),
+            },
+            bcc_copies,
+        };
+
+        let hash: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(MAIL_ZOME, "send_message", input)
+            .await?;
 
-        // Simulated message ID
-        let message_id = format!("msg_stub_{}", chrono::Utc::now().timestamp());
-        Ok(message_id)
+        Ok(wire::encode_hash(&hash))
     }
 
     /// Get inbox messages
     ///
-    /// TODO (Phase C): Replace with real Holochain zome call to `mail_messages::get_inbox`
+    /// Calls `mail_messages::get_inbox` (newest first) and attaches our
+    /// private state to each message
     pub async fn get_inbox(&self) -> Result<Vec<MailMessage>> {
-        println!("📬 [STUB] Would fetch inbox from DHT");
-        Ok(vec![])
+        let items: Vec<wire::MailItem> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "get_inbox", ())
+            .await?;
+
+        self.with_states(items.into_iter().map(MailMessage::from).collect())
+            .await
+    }
+
+    /// Get one page of inbox messages from senders at or above `min_trust`
+    ///
+    /// Calls `trust_filter::filter_inbox_page`; the conductor only reads the
+    /// requested time range and page
+    pub async fn get_filtered_inbox_page(
+        &self,
+        min_trust: f64,
+        query: &PageQuery,
+    ) -> Result<MessagePage> {
+        let page: wire::MailPage = self
+            .conductor
+            .call_zome(
+                TRUST_ZOME,
+                "filter_inbox_page",
+                wire::FilterInboxPageInput {
+                    min_trust,
+                    query: query.into(),
+                },
+            )
+            .await?;
+
+        Ok(MessagePage {
+            messages: self
+                .with_states(page.items.into_iter().map(MailMessage::from).collect())
+                .await?,
+            next_cursor: page.next_cursor,
+        })
+    }
+
+    /// Add mail from before the time index to it
+    ///
+    /// Calls `mail_messages::index_mailbox`, returning the number indexed
+    pub async fn index_mailbox(&self) -> Result<u32> {
+        let indexed: u32 = self
+            .conductor
+            .call_zome(MAIL_ZOME, "index_mailbox", ())
+            .await?;
+
+        Ok(indexed)
+    }
+
+    /// Messages quarantined at `min_trust`, with score and reason
+    ///
+    /// Calls `trust_filter::get_quarantine`
+    pub async fn get_quarantine(&self, min_trust: f64) -> Result<Vec<QuarantinedMessage>> {
+        let items: Vec<wire::QuarantinedMail> = self
+            .conductor
+            .call_zome(TRUST_ZOME, "get_quarantine", min_trust)
+            .await?;
+
+        let mut states = self.get_message_states().await?;
+        Ok(items
+            .into_iter()
+            .map(QuarantinedMessage::from)
+            .map(|mut q| {
+                q.message.state = states.remove(&q.message.id).unwrap_or_default();
+                q
+            })
+            .collect())
+    }
+
+    /// Why a message would be quarantined at `min_trust`, or `None` if it
+    /// would reach the inbox
+    ///
+    /// Calls `trust_filter::screen_message`, which applies the same sender
+    /// rules and trust threshold as the inbox
+    pub async fn screen_message(
+        &self,
+        message_id: &str,
+        min_trust: f64,
+    ) -> Result<Option<QuarantinedMessage>> {
+        let input = wire::ScreenMessageInput {
+            message_hash: wire::decode_hash(message_id)?,
+            min_trust,
+        };
+
+        let quarantined: Option<wire::QuarantinedMail> = self
+            .conductor
+            .call_zome(TRUST_ZOME, "screen_message", input)
+            .await?;
+
+        Ok(quarantined.map(QuarantinedMessage::from))
+    }
+
+    /// Release a quarantined message and whitelist its sender
+    ///
+    /// Calls `trust_filter::release_from_quarantine`, returning the sender's DID
+    pub async fn release_from_quarantine(&self, message_id: &str) -> Result<String> {
+        let hash = wire::decode_hash(message_id)?;
+
+        let release: wire::QuarantineRelease = self
+            .conductor
+            .call_zome(TRUST_ZOME, "release_from_quarantine", hash)
+            .await?;
+
+        Ok(release.sender_did)
+    }
+
+    /// Attach our private state to each message
+    async fn with_states(&self, messages: Vec<MailMessage>) -> Result<Vec<MailMessage>> {
+        let mut states = self.get_message_states().await?;
+        Ok(messages
+            .into_iter()
+            .map(|mut msg| {
+                msg.state = states.remove(&msg.id).unwrap_or_default();
+                msg
+            })
+            .collect())
+    }
+
+    /// Subscribe to new-mail signals from the mail zome
+    ///
+    /// Each message delivered to us while subscribed arrives on the channel,
+    /// as an unverified claim by the agent that signalled it
+    pub async fn watch_new_mail(&self) -> Result<mpsc::UnboundedReceiver<NewMail>> {
+        let mut signals = self
+            .conductor
+            .subscribe::<wire::MailSignal>(MAIL_ZOME)
+            .await?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(signal) = signals.recv().await {
+                let Some(notice) = signal.into_new_mail() else {
+                    continue;
+                };
+                if sender.send(notice).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(receiver)
     }
 
     /// Get sent messages
     ///
-    /// TODO (Phase C): Replace with real Holochain zome call to `mail_messages::get_outbox`
+    /// Calls `mail_messages::get_outbox` (newest first)
     pub async fn get_sent(&self) -> Result<Vec<MailMessage>> {
-        println!("📤 [STUB] Would fetch sent messages from DHT");
-        Ok(vec![])
+        let items: Vec<wire::MailItem> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "get_outbox", ())
+            .await?;
+
+        Ok(items.into_iter().map(MailMessage::from).collect())
     }
 
     /// Get a specific message by ID
     ///
-    /// TODO (Phase C): Replace with real Holochain zome call to `mail_messages::get_message`
+    /// Calls `mail_messages::get_message` with the decoded action hash
     pub async fn get_message(&self, message_id: &str) -> Result<MailMessage> {
-        println!("🔍 [STUB] Would fetch message {} from DHT", message_id);
-
-        Ok(MailMessage {
-            from_did: self
-                .config
-                .identity
-                .did
-                .clone()
-                .unwrap_or_else(|| "did:mycelix:demo-sender".to_string()),
-            to_did: self
-                .config
-                .identity
-                .did
-                .clone()
-                .unwrap_or_else(|| "did:mycelix:demo-recipient".to_string()),
-            subject_encrypted: format!("ENC:Demo message {}", message_id).into_bytes(),
-            body_cid: format!("bafyrei{}", hex::encode(message_id.as_bytes())),
-            timestamp: chrono::Utc::now().timestamp(),
-            thread_id: None,
-            epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+        let hash = wire::decode_hash(message_id)?;
+
+        let message: Option<wire::MailMessage> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "get_message", hash)
+            .await?;
+
+        message
+            .map(|m| m.into_cli(message_id.trim().to_string()))
+            .with_context(|| format!("Message {} not found on the DHT", message_id))
+    }
+
+    /// Agent key (base64) that wrote a message, if it is on the DHT
+    ///
+    /// Calls `mail_messages::get_message_author`
+    pub async fn get_message_author(&self, message_id: &str) -> Result<Option<String>> {
+        let hash = wire::decode_hash(message_id)?;
+
+        let author: Option<holochain_types::prelude::AgentPubKey> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "get_message_author", hash)
+            .await?;
+
+        Ok(author.as_ref().map(wire::encode_agent_key))
+    }
+
+    /// Get the whole conversation containing a message
+    ///
+    /// Calls `mail_messages::get_thread` (oldest first, root included)
+    pub async fn get_thread(&self, message_id: &str) -> Result<Vec<MailMessage>> {
+        let hash = wire::decode_hash(message_id)?;
+
+        let items: Vec<wire::MailItem> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "get_thread", hash)
+            .await?;
+
+        Ok(items.into_iter().map(MailMessage::from).collect())
+    }
+
+    /// Store encrypted content on the DHT under its CID
+    ///
+    /// Calls `mail_messages::store_blob`; the integrity zome rejects the
+    /// entry if the CID does not match the ciphertext.
+    pub async fn store_blob(&self, cid: String, ciphertext: Vec<u8>) -> Result<()> {
+        let blob = wire::EncryptedBlob { cid, ciphertext };
+
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(MAIL_ZOME, "store_blob", blob)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetch encrypted content by CID and verify it against the CID
+    ///
+    /// Calls `mail_messages::get_blob`
+    pub async fn fetch_blob(&self, cid: &str) -> Result<Vec<u8>> {
+        self.try_fetch_blob(cid).await?.with_context(|| {
+            format!("Content {} not found on the DHT (it may not have replicated yet)", cid)
         })
     }
 
-    /// Mark a message as read
+    /// Like [`Self::fetch_blob`], but `None` when the DHT has no blob for `cid`
     ///
-    /// TODO (Phase C): Replace with real Holochain zome call to update message metadata
+    /// Conductor, network and integrity failures are still errors.
+    pub async fn try_fetch_blob(&self, cid: &str) -> Result<Option<Vec<u8>>> {
+        let blob: Option<wire::EncryptedBlob> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "get_blob", cid.to_string())
+            .await?;
+
+        let Some(blob) = blob else {
+            return Ok(None);
+        };
+        storage::verify_cid(cid, &blob.ciphertext)?;
+
+        Ok(Some(blob.ciphertext))
+    }
+
+    /// Mark a message as read
     pub async fn mark_read(&self, message_id: &str) -> Result<()> {
-        println!("✓ [STUB] Would mark message {} as read", message_id);
+        let update = MessageStateUpdate {
+            read: Some(true),
+            ..Default::default()
+        };
+        self.update_message_state(message_id, update).await?;
         Ok(())
     }
 
-    /// Delete a message
+    /// Change our private state for a message
     ///
-    /// TODO (Phase C): Replace with real Holochain zome call to `mail_messages::delete_message`
-    pub async fn delete_message(&self, message_id: &str) -> Result<()> {
-        println!("🗑️  [STUB] Would delete message {}", message_id);
+    /// Calls `mail_messages::update_message_state`, returning the new state
+    pub async fn update_message_state(
+        &self,
+        message_id: &str,
+        update: MessageStateUpdate,
+    ) -> Result<MessageState> {
+        let input = wire::MessageStateUpdate {
+            message_hash: wire::decode_hash(message_id)?,
+            read: update.read,
+            starred: update.starred,
+            archived: update.archived,
+            add_labels: update.add_labels,
+            remove_labels: update.remove_labels,
+        };
+
+        let state: wire::MessageState = self
+            .conductor
+            .call_zome(MAIL_ZOME, "update_message_state", input)
+            .await?;
+
+        Ok(state.into())
+    }
+
+    /// Our private state for every message that has any, keyed by message ID
+    ///
+    /// Calls `mail_messages::get_message_states`
+    pub async fn get_message_states(&self) -> Result<HashMap<String, MessageState>> {
+        let states: Vec<wire::MessageState> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "get_message_states", ())
+            .await?;
+
+        Ok(states
+            .into_iter()
+            .map(|state| (wire::encode_hash(&state.message_hash), state.into()))
+            .collect())
+    }
+
+    /// Retract a message we sent
+    ///
+    /// Calls `mail_messages::retract_message`, which removes every inbox
+    /// link we created for it and tombstones the entry
+    pub async fn retract_message(&self, message_id: &str) -> Result<()> {
+        let hash = wire::decode_hash(message_id)?;
+
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(MAIL_ZOME, "retract_message", hash)
+            .await?;
+
         Ok(())
     }
 
+    /// Remove a received message from our inbox
+    ///
+    /// Calls `mail_messages::remove_from_inbox`; the sender's copy is untouched
+    pub async fn remove_from_inbox(&self, message_id: &str) -> Result<()> {
+        let hash = wire::decode_hash(message_id)?;
+
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(MAIL_ZOME, "remove_from_inbox", hash)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Clean up expired mail in our inbox and outbox
+    ///
+    /// Calls `mail_messages::purge_expired`, returning how many messages went
+    pub async fn purge_expired(&self) -> Result<u32> {
+        let purged: u32 = self
+            .conductor
+            .call_zome(MAIL_ZOME, "purge_expired", ())
+            .await?;
+
+        Ok(purged)
+    }
+
     /// Search messages
     ///
     /// TODO (Phase C): Implement DHT-level search or client-side filtering
@@ -143,6 +495,101 @@ impl MycellixClient {
         Ok(vec![])
     }
 
+    //
+    // ===== CONTACT OPERATIONS (Holochain zome calls) =====
+    //
+
+    /// Add a contact to our private address book
+    ///
+    /// Calls `contacts::add_contact`
+    pub async fn add_contact(
+        &self,
+        name: String,
+        did: String,
+        email_alias: Option<String>,
+        notes: Option<String>,
+    ) -> Result<Contact> {
+        let input = wire::AddContactInput {
+            name,
+            did,
+            email_alias,
+            notes,
+        };
+
+        let contact: wire::Contact = self
+            .conductor
+            .call_zome(CONTACTS_ZOME, "add_contact", input)
+            .await?;
+
+        Ok(contact.into())
+    }
+
+    /// Update a contact; `None` leaves a field unchanged, `Some("")` clears it
+    ///
+    /// Calls `contacts::update_contact`
+    pub async fn update_contact(
+        &self,
+        did: String,
+        name: Option<String>,
+        email_alias: Option<String>,
+        notes: Option<String>,
+    ) -> Result<Contact> {
+        let input = wire::UpdateContactInput {
+            did,
+            name,
+            email_alias,
+            notes,
+        };
+
+        let contact: wire::Contact = self
+            .conductor
+            .call_zome(CONTACTS_ZOME, "update_contact", input)
+            .await?;
+
+        Ok(contact.into())
+    }
+
+    /// Remove a contact
+    ///
+    /// Calls `contacts::remove_contact`
+    pub async fn remove_contact(&self, did: String) -> Result<()> {
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(CONTACTS_ZOME, "remove_contact", did)
+            .await?;
+
+        Ok(())
+    }
+
+    /// List all contacts, sorted by name
+    ///
+    /// Calls `contacts::list_contacts`
+    pub async fn list_contacts(&self) -> Result<Vec<Contact>> {
+        let contacts: Vec<wire::Contact> = self
+            .conductor
+            .call_zome(CONTACTS_ZOME, "list_contacts", ())
+            .await?;
+
+        Ok(contacts.into_iter().map(Contact::from).collect())
+    }
+
+    /// Search contacts by name, DID, alias or notes
+    ///
+    /// Calls `contacts::search_contacts`
+    pub async fn search_contacts(&self, query: &str) -> Result<Vec<Contact>> {
+        let contacts: Vec<wire::Contact> = self
+            .conductor
+            .call_zome(CONTACTS_ZOME, "search_contacts", query.to_string())
+            .await?;
+
+        Ok(contacts.into_iter().map(Contact::from).collect())
+    }
+
+    /// The address book, for display names and recipient resolution
+    pub async fn contact_book(&self) -> Result<ContactBook> {
+        Ok(ContactBook::new(self.list_contacts().await?))
+    }
+
     //
     // ===== TRUST SCORE OPERATIONS (Partial - MATL HTTP) =====
     //
@@ -174,11 +621,82 @@ impl MycellixClient {
         }
     }
 
+    /// Final trust score for a DID and the per-source scores behind it
+    ///
+    /// Calls `trust_filter::get_trust_breakdown`
+    pub async fn get_trust_breakdown(&self, did: &str) -> Result<TrustBreakdown> {
+        let breakdown: wire::TrustBreakdown = self
+            .conductor
+            .call_zome(TRUST_ZOME, "get_trust_breakdown", did.to_string())
+            .await?;
+
+        Ok(breakdown.into())
+    }
+
+    /// Always accept or always reject senders matching `pattern`
+    ///
+    /// Calls `trust_filter::set_sender_rule`; replaces any rule for the pattern
+    pub async fn set_sender_rule(&self, pattern: String, action: RuleAction) -> Result<SenderRule> {
+        let rule: wire::SenderRule = self
+            .conductor
+            .call_zome(
+                TRUST_ZOME,
+                "set_sender_rule",
+                wire::SetSenderRuleInput { pattern, action },
+            )
+            .await?;
+
+        Ok(rule.into())
+    }
+
+    /// Remove the allow/block rule for `pattern`
+    pub async fn remove_sender_rule(&self, pattern: String) -> Result<()> {
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(TRUST_ZOME, "remove_sender_rule", pattern)
+            .await?;
+
+        Ok(())
+    }
+
+    /// All allow/block rules, most specific first
+    pub async fn list_sender_rules(&self) -> Result<Vec<SenderRule>> {
+        let rules: Vec<wire::SenderRule> = self
+            .conductor
+            .call_zome(TRUST_ZOME, "list_sender_rules", ())
+            .await?;
+
+        Ok(rules.into_iter().map(SenderRule::from).collect())
+    }
+
+    /// Report quarantined mail from blocked senders as spam
+    ///
+    /// Calls `trust_filter::report_blocked_senders`, returning the number of
+    /// reports filed
+    pub async fn report_blocked_senders(&self) -> Result<u32> {
+        let filed: u32 = self
+            .conductor
+            .call_zome(TRUST_ZOME, "report_blocked_senders", ())
+            .await?;
+
+        Ok(filed)
+    }
+
     /// Set/update trust score for a DID
     ///
-    /// TODO (Phase C): Sync to Holochain DHT via `trust_filter::update_trust_score`
+    /// Writes a manual score to the DHT via `trust_filter::update_trust_score`
     pub async fn set_trust_score(&self, did: String, score: f64) -> Result<()> {
-        println!("⭐ [STUB] Would set trust score for {} to {:.2}", did, score);
+        let trust_score = wire::TrustScore {
+            did,
+            score,
+            last_updated: wire::now(),
+            matl_source: "manual".to_string(),
+        };
+
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(TRUST_ZOME, "update_trust_score", trust_score)
+            .await?;
 
         // TODO: Also update MATL bridge
         // POST to {}/trust with TrustScoreUpdate
@@ -188,10 +706,14 @@ impl MycellixClient {
 
     /// List all trust scores
     ///
-    /// TODO (Phase C): Replace with real Holochain zome call to `trust_filter::get_all_trust_scores`
+    /// Calls `trust_filter::get_all_trust_scores`
     pub async fn list_trust_scores(&self) -> Result<Vec<TrustScore>> {
-        println!("📋 [STUB] Would list all trust scores from DHT");
-        self.sync_trust_from_matl().await
+        let scores: Vec<wire::TrustScore> = self
+            .conductor
+            .call_zome(TRUST_ZOME, "get_all_trust_scores", ())
+            .await?;
+
+        Ok(scores.into_iter().map(TrustScore::from).collect())
     }
 
     /// Sync trust scores from MATL
@@ -225,13 +747,20 @@ impl MycellixClient {
 
     /// Register a DID with the DID registry
     ///
-    /// Maps DID to Holochain AgentPubKey for message routing
-    pub async fn register_did(&self, did: String, agent_pub_key: String) -> Result<()> {
+    /// Maps DID to Holochain AgentPubKey for message routing, and publishes the
+    /// Ed25519 identity key senders need to encrypt mail for this DID
+    pub async fn register_did(
+        &self,
+        did: String,
+        agent_pub_key: String,
+        public_key: Option<String>,
+    ) -> Result<()> {
         let url = format!("{}/register", self.did_registry_url);
 
         let registration = serde_json::json!({
             "did": did,
             "agent_pub_key": agent_pub_key,
+            "public_key": public_key,
         });
 
         match self.http_client.post(&url)
@@ -256,60 +785,166 @@ impl MycellixClient {
         }
     }
 
-    /// Resolve a DID to an AgentPubKey
+    /// Bind our DID to this agent inside the DNA
     ///
-    /// Queries the DID registry to find the Holochain agent for a DID
-    pub async fn resolve_did(&self, did: String) -> Result<Option<DidResolution>> {
-        let url = format!("{}/resolve/{}", self.did_registry_url, did);
+    /// Calls `mail_messages::register_my_did` with our identity key's
+    /// signature over the agent key. Validation only accepts mail whose
+    /// `from_did` the author has bound this way.
+    pub async fn bind_did(&self, did: String) -> Result<()> {
+        let (public_key, signature) = self.did_proof().await?;
+        let input = wire::RegisterDidInput {
+            did,
+            public_key,
+            signature,
+        };
 
-        match self.http_client.get(&url).send().await {
-            Ok(response) if response.status().is_success() => {
-                let resolution: DidResolution = response.json().await
-                    .context("Failed to parse DID resolution response")?;
-                Ok(Some(resolution))
-            }
-            Ok(response) if response.status() == 404 => {
-                Ok(None)  // DID not found
-            }
-            Ok(response) => {
-                println!("⚠️  DID registry returned status: {}", response.status());
-                Ok(None)
-            }
-            Err(_) => {
-                println!("⚠️  DID registry not available");
-                Ok(Some(self.stub_did_resolution(did)))
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(MAIL_ZOME, "register_my_did", input)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Hand our DID over to another agent key
+    ///
+    /// Calls `mail_messages::rotate_did`, returning the rotation ID the new
+    /// agent passes to `accept_did_rotation`
+    pub async fn rotate_did(&self, did: String, new_agent_key: &str) -> Result<String> {
+        let input = wire::RotateDidInput {
+            did: did.clone(),
+            new_agent_pub_key: wire::decode_agent_key(new_agent_key)?,
+        };
+
+        let hash: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(MAIL_ZOME, "rotate_did", input)
+            .await?;
+        self.resolvers.invalidate(&did);
+
+        Ok(wire::encode_hash(&hash))
+    }
+
+    /// Take over a DID another agent rotated to this agent's key
+    ///
+    /// Calls `mail_messages::accept_did_rotation`, returning the new binding
+    pub async fn accept_did_rotation(&self, rotation_id: &str) -> Result<DidBinding> {
+        let (public_key, signature) = self.did_proof().await?;
+        let input = wire::AcceptDidRotationInput {
+            rotation_hash: wire::decode_hash(rotation_id)?,
+            public_key,
+            signature,
+        };
+
+        let record: wire::DidRecord = self
+            .conductor
+            .call_zome(MAIL_ZOME, "accept_did_rotation", input)
+            .await?;
+        self.resolvers.invalidate(&record.did);
+
+        Ok(record.into())
+    }
+
+    /// Permanently revoke our binding for a DID
+    ///
+    /// Calls `mail_messages::revoke_did`
+    pub async fn revoke_did(&self, did: String, reason: String) -> Result<()> {
+        let input = wire::RevokeDidInput { did: did.clone(), reason };
+
+        let _: holochain_types::prelude::ActionHash = self
+            .conductor
+            .call_zome(MAIL_ZOME, "revoke_did", input)
+            .await?;
+        self.resolvers.invalidate(&did);
+
+        Ok(())
+    }
+
+    /// Look up which agent key a DID is bound to on the DHT
+    ///
+    /// Calls `mail_messages::resolve_did`, which follows key rotations
+    pub async fn get_did_binding(&self, did: &str) -> Result<Option<DidBinding>> {
+        let record: Option<wire::DidRecord> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "resolve_did", did.to_string())
+            .await?;
+
+        Ok(record.map(Into::into))
+    }
+
+    /// The Holochain agent key our cell runs as
+    pub async fn my_agent_pub_key(&self) -> Result<String> {
+        let agent_key = self.conductor.agent_pub_key().await?;
+        Ok(wire::encode_agent_key(&agent_key))
+    }
+
+    /// Our identity key and its signature over our agent key
+    ///
+    /// Proves to the DNA that whoever binds a DID holds the key behind it.
+    async fn did_proof(&self) -> Result<(Vec<u8>, holochain_types::prelude::Signature)> {
+        let signing_key = keystore::load_signing_key(&self.config.identity.private_key_path)
+            .context("Binding a DID needs your private key. Run 'mycelix-mail init' first.")?;
+        let agent_key = self.conductor.agent_pub_key().await?;
+        let signature = crypto::sign_agent_key(&signing_key, agent_key.get_raw_39());
+
+        Ok((
+            signing_key.verifying_key().to_bytes().to_vec(),
+            holochain_types::prelude::Signature(signature),
+        ))
+    }
+
+    /// The DID registry's record for a DID
+    ///
+    /// Errors if the registry cannot be reached, rather than guessing.
+    pub async fn registry_record(&self, did: &str) -> Result<Option<DidResolution>> {
+        self.registry.lookup(did).await
+    }
+
+    /// Resolve a DID to its W3C DID document
+    ///
+    /// `did:mycelix` documents come from the DNA binding, falling back to the
+    /// registry; `did:key` from the identifier and `did:web` from the named
+    /// domain. Our own document also lists our email alias, when configured.
+    /// Returns `None` only when every source agrees the DID does not exist;
+    /// errors if it cannot be resolved.
+    pub async fn resolve_did(&self, did: &str) -> Result<Option<DidDocument>> {
+        let mut document = self.resolvers.resolve(did).await?;
+        if let (Some(document), Some(email)) = (document.as_mut(), &self.config.identity.email) {
+            if self.config.identity.did.as_deref() == Some(did) {
+                document.service.push(Service {
+                    id: format!("{}#email", did),
+                    service_type: resolver::EMAIL_ALIAS_SERVICE.to_string(),
+                    service_endpoint: format!("mailto:{}", email).into(),
+                });
             }
         }
+
+        Ok(document)
+    }
+
+    /// The DID a recipient receives mail under on the DHT
+    ///
+    /// `did:web` subjects name it in their document's `alsoKnownAs`; other
+    /// DIDs receive mail as themselves.
+    pub async fn delivery_did(&self, did: &str) -> Result<String> {
+        if !did.starts_with("did:web:") {
+            return Ok(did.to_string());
+        }
+
+        let document = self
+            .resolve_did(did)
+            .await?
+            .with_context(|| format!("Recipient DID {} could not be resolved", did))?;
+        resolver::delivery_did(&document)
+            .map(str::to_string)
+            .with_context(|| format!("{} does not list a Mycelix mail identity in alsoKnownAs", did))
     }
 
     /// List all registered DIDs
     ///
     /// Queries the DID registry for all known DIDs
     pub async fn list_dids(&self) -> Result<Vec<DidResolution>> {
-        let url = format!("{}/list", self.did_registry_url);
-
-        match self.http_client.get(&url).send().await {
-            Ok(response) if response.status().is_success() => {
-                let dids: Vec<DidResolution> = response.json().await
-                    .context("Failed to parse DID list response")?;
-                Ok(dids)
-            }
-            Ok(response) => {
-                println!("⚠️  DID registry returned status: {}", response.status());
-                Ok(vec![])
-            }
-            Err(_) => {
-                println!("⚠️  DID registry not available");
-                let fallback = self.stub_did_resolution(
-                    self.config
-                        .identity
-                        .did
-                        .clone()
-                        .unwrap_or_else(|| "did:mycelix:demo".to_string()),
-                );
-                Ok(vec![fallback])
-            }
-        }
+        self.registry.list().await
     }
 
     /// Get the current user's DID
@@ -318,6 +953,53 @@ impl MycellixClient {
             .context("No DID configured. Run 'mycelix-mail init' first.")
     }
 
+    //
+    // ===== ENCRYPTION KEYS =====
+    //
+
+    /// Our X25519 secret key, derived from the Ed25519 key created by `init`
+    pub fn encryption_key(&self) -> Result<crypto_box::SecretKey> {
+        let signing_key = keystore::load_signing_key(&self.config.identity.private_key_path)
+            .context("Cannot decrypt mail without your private key. Run 'mycelix-mail init' first.")?;
+        Ok(crypto::x25519_secret(&signing_key))
+    }
+
+    /// Our X25519 public key, so we can seal a copy of each content key for ourselves
+    pub fn my_encryption_public_key(&self) -> Result<crypto_box::PublicKey> {
+        let verifying_key = crypto::load_verifying_key(&self.config.identity.public_key_path)?;
+        Ok(crypto::x25519_public(&verifying_key))
+    }
+
+    /// Our Ed25519 public key (hex), as published in the DID registry
+    pub fn my_public_key(&self) -> Result<String> {
+        let verifying_key = crypto::load_verifying_key(&self.config.identity.public_key_path)?;
+        Ok(hex::encode(verifying_key.to_bytes()))
+    }
+
+    /// Our identity key encoded for backup or another device
+    ///
+    /// Keystore exports are encrypted under a newly chosen passphrase.
+    pub fn export_identity_key(&self, format: keystore::KeyFormat) -> Result<Vec<u8>> {
+        let signing_key = keystore::load_signing_key(&self.config.identity.private_key_path)
+            .context("Exporting needs your private key. Run 'mycelix-mail init' first.")?;
+        keystore::encode_signing_key(&signing_key, format, keystore::new_passphrase)
+    }
+
+    /// Look up the X25519 key to encrypt mail for `did`
+    ///
+    /// The key comes from the DID document's key agreement key. A
+    /// `did:mycelix` not yet bound on the DHT resolves through the registry,
+    /// whose key is checked against the DID itself so a registry cannot
+    /// substitute its own.
+    pub async fn recipient_encryption_key(&self, did: &str) -> Result<crypto_box::PublicKey> {
+        let document = self
+            .resolve_did(did)
+            .await?
+            .with_context(|| format!("Recipient DID {} is not registered", did))?;
+
+        resolver::encryption_key(&document)
+    }
+
     //
     // ===== UTILITY OPERATIONS =====
     //
@@ -373,10 +1055,17 @@ impl MycellixClient {
             }
         };
 
-        // TODO (Phase C): Check Holochain conductor connection
-        println!("  ⏳ Holochain Conductor: PENDING (Phase C)");
+        // Check Holochain conductor
+        let conductor_ok = self.conductor.is_connected().await;
+        if conductor_ok {
+            println!("  ✓ Holochain Conductor: OK");
+        } else {
+            println!("  ✗ Holochain Conductor: UNAVAILABLE");
+        }
 
-        Ok(did_ok || matl_ok)  // At least one service should be available
+        // Mail cannot flow without the conductor; at least one HTTP service
+        // should be available for DID resolution or trust sync
+        Ok(conductor_ok && (did_ok || matl_ok))
     }
 
     //
@@ -401,7 +1090,7 @@ impl MycellixClient {
 
     /// Get the conductor URL
     pub fn get_conductor_url(&self) -> &str {
-        &self.conductor_url
+        self.conductor.app_url()
     }
 
     /// Get the DID registry URL
@@ -432,21 +1121,4 @@ impl MycellixClient {
     pub async fn sync_all_trust_scores(&self) -> Result<Vec<TrustScore>> {
         self.sync_trust_from_matl().await
     }
-
-    fn stub_did_resolution(&self, did: String) -> DidResolution {
-        let agent_key = self
-            .config
-            .identity
-            .agent_pub_key
-            .clone()
-            .unwrap_or_else(|| "uhCAkDemoAgentKey".to_string());
-        let now = chrono::Utc::now().timestamp();
-
-        DidResolution {
-            did,
-            agent_pub_key: agent_key,
-            created_at: now,
-            updated_at: now,
-        }
-    }
 }
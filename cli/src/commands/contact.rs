@@ -0,0 +1,159 @@
+use anyhow::{Context, Result, bail};
+
+use crate::client::MycellixClient;
+use crate::types::Contact;
+
+/// Add a contact to the private address book
+pub async fn handle_add(
+    client: &MycellixClient,
+    name: String,
+    did: String,
+    alias: Option<String>,
+    notes: Option<String>,
+) -> Result<()> {
+    let did = did.trim().to_string();
+    if !did.starts_with("did:") {
+        bail!("Invalid DID: '{}'\nExpected format: did:mycelix:<base58>", did);
+    }
+    if name.trim().is_empty() {
+        bail!("Contact name cannot be empty");
+    }
+    if name.trim().starts_with("did:") {
+        bail!("Contact name cannot look like a DID");
+    }
+
+    let contact = client
+        .add_contact(name, did, alias, notes)
+        .await
+        .context("Failed to add contact")?;
+
+    println!("✅ Contact added");
+    println!();
+    display_contact(&contact);
+    println!();
+    println!("💡 Use 'mycelix-mail send {} ...' to write to them", contact.name);
+
+    Ok(())
+}
+
+/// Update a contact found by DID, name or alias
+pub async fn handle_update(
+    client: &MycellixClient,
+    contact: String,
+    name: Option<String>,
+    alias: Option<String>,
+    notes: Option<String>,
+) -> Result<()> {
+    if name.is_none() && alias.is_none() && notes.is_none() {
+        bail!("Nothing to change. Use --name, --alias or --notes (empty to clear)");
+    }
+    if name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        bail!("Contact name cannot be empty");
+    }
+
+    let did = resolve_contact(client, &contact).await?;
+    let contact = client
+        .update_contact(did, name, alias, notes)
+        .await
+        .context("Failed to update contact")?;
+
+    println!("✅ Contact updated");
+    println!();
+    display_contact(&contact);
+
+    Ok(())
+}
+
+/// Remove a contact found by DID, name or alias
+pub async fn handle_remove(client: &MycellixClient, contact: String) -> Result<()> {
+    let did = resolve_contact(client, &contact).await?;
+
+    client
+        .remove_contact(did.clone())
+        .await
+        .context("Failed to remove contact")?;
+
+    println!("🗑️  Contact removed: {}", did);
+
+    Ok(())
+}
+
+/// List all contacts
+pub async fn handle_list(client: &MycellixClient) -> Result<()> {
+    println!("📇 Fetching contacts...");
+    println!();
+
+    let contacts = client
+        .list_contacts()
+        .await
+        .context("Failed to fetch contacts")?;
+
+    if contacts.is_empty() {
+        println!("Your address book is empty.");
+        println!();
+        println!("💡 Use 'mycelix-mail contact add <name> <did>' to add a contact");
+        return Ok(());
+    }
+
+    display_contacts(&contacts);
+
+    Ok(())
+}
+
+/// Search contacts by name, DID, alias or notes
+pub async fn handle_search(client: &MycellixClient, query: String) -> Result<()> {
+    println!("🔎 Searching contacts for: {}", query);
+    println!();
+
+    let contacts = client
+        .search_contacts(&query)
+        .await
+        .context("Failed to search contacts")?;
+
+    if contacts.is_empty() {
+        println!("No contacts match '{}'.", query);
+        return Ok(());
+    }
+
+    display_contacts(&contacts);
+
+    Ok(())
+}
+
+/// Resolve a DID, contact name or alias to the contact's DID
+async fn resolve_contact(client: &MycellixClient, contact: &str) -> Result<String> {
+    let book = client
+        .contact_book()
+        .await
+        .context("Failed to fetch contacts")?;
+    let did = book.resolve(contact)?;
+
+    if book.find_by_did(&did).is_none() {
+        bail!("No contact saved for {}", did);
+    }
+
+    Ok(did)
+}
+
+fn display_contacts(contacts: &[Contact]) {
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("{} contact(s)", contacts.len());
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+
+    for contact in contacts {
+        display_contact(contact);
+        println!();
+    }
+}
+
+fn display_contact(contact: &Contact) {
+    println!("👤 {}", contact.name);
+    println!("   DID:   {}", contact.did);
+    if let Some(ref alias) = contact.email_alias {
+        println!("   Alias: {}", alias);
+    }
+    if let Some(ref notes) = contact.notes {
+        println!("   Notes: {}", notes);
+    }
+}
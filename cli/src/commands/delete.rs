@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+
+use crate::client::MycellixClient;
+
+/// Retract a message we sent: it disappears from every recipient's inbox
+pub async fn handle_retract(client: &MycellixClient, message_id: String) -> Result<()> {
+    client
+        .retract_message(&message_id)
+        .await
+        .context("Failed to retract message")?;
+
+    println!("↩️  Retracted message {}", message_id);
+    println!("   Recipients will no longer see it in their inbox");
+    println!();
+    println!("💡 Copies already read or exported by recipients can't be recalled");
+
+    Ok(())
+}
+
+/// Remove a received message from our own inbox
+pub async fn handle_delete(client: &MycellixClient, message_id: String) -> Result<()> {
+    client
+        .remove_from_inbox(&message_id)
+        .await
+        .context("Failed to delete message")?;
+
+    println!("🗑️  Removed message {} from your inbox", message_id);
+    println!();
+    println!("💡 To take back mail you sent, use 'mycelix-mail retract <id>'");
+
+    Ok(())
+}
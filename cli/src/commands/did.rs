@@ -1,5 +1,8 @@
 use anyhow::{Context, Result};
 use crate::client::MycellixClient;
+use crate::config::Config;
+use crate::resolver;
+use crate::types::DidBinding;
 
 /// Register a new DID
 pub async fn handle_register(
@@ -26,11 +29,30 @@ pub async fn handle_register(
     // Register with DID registry
     println!("📡 Registering with DID registry...");
 
+    // Publish our encryption key only when registering our own DID
+    let public_key = match client.get_my_did() {
+        Ok(my_did) if my_did == did => client.my_public_key().ok(),
+        _ => None,
+    };
+
+    let is_mine = public_key.is_some();
     client
-        .register_did(did.clone(), agent_key.clone())
+        .register_did(did.clone(), agent_key.clone(), public_key)
         .await
         .context("Failed to register DID with registry")?;
 
+    // Our own DID must also be bound in the DNA before we can send mail
+    if is_mine {
+        println!("📡 Binding DID to this agent on the DHT...");
+        match client.bind_did(did.clone()).await {
+            Ok(()) => println!("✓ DID bound to this agent"),
+            Err(e) if e.to_string().contains("already registered") => {
+                println!("✓ DID already bound on the DHT")
+            }
+            Err(e) => return Err(e).context("Failed to bind DID on the DHT"),
+        }
+    }
+
     println!();
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("                 REGISTRATION COMPLETE");
@@ -47,6 +69,79 @@ pub async fn handle_register(
     Ok(())
 }
 
+/// Hand our DID over to another agent key (e.g. a new device)
+pub async fn handle_rotate(client: &MycellixClient, new_agent_key: String) -> Result<()> {
+    let did = client.get_my_did()?;
+
+    println!("🔄 Rotating {} to agent key {}...", did, truncate_key(&new_agent_key));
+
+    let rotation_id = client
+        .rotate_did(did.clone(), &new_agent_key)
+        .await
+        .context("Failed to rotate DID")?;
+
+    println!();
+    println!("✅ Handover published");
+    println!();
+    println!("🆔 Rotation ID: {}", rotation_id);
+    println!();
+    println!("💡 On the new device, run:");
+    println!("   mycelix-mail did accept {}", rotation_id);
+    println!("   This agent can no longer send mail as {}", did);
+    println!("   Once accepted, recipients stop showing mail this agent sends as it after that;");
+    println!("   mail it sent before the handover stays in their inboxes");
+
+    Ok(())
+}
+
+/// Take over a DID that was rotated to this agent
+pub async fn handle_accept(client: &MycellixClient, rotation_id: String) -> Result<()> {
+    println!("🔄 Accepting DID rotation {}...", rotation_id);
+
+    let binding = client
+        .accept_did_rotation(&rotation_id)
+        .await
+        .context("Failed to accept DID rotation")?;
+
+    // Mail from now on goes out as the DID we took over
+    let mut config = Config::load_or_create()?;
+    config.set_did(binding.did.clone())?;
+
+    println!();
+    println!("✅ {} is now bound to this agent", binding.did);
+    println!("🔑 Agent Key:  {}", truncate_key(&binding.agent_pub_key));
+    println!("🔁 Rotations:  {}", binding.rotations);
+    println!();
+    println!("💡 Your configuration now uses this DID for sending");
+
+    Ok(())
+}
+
+/// Permanently revoke this agent's binding for our DID
+pub async fn handle_revoke(client: &MycellixClient, reason: Option<String>, yes: bool) -> Result<()> {
+    let did = client.get_my_did()?;
+
+    if !yes {
+        println!("⚠️  Revoking {} is permanent:", did);
+        println!("   • nobody can rotate it afterwards, and inboxes drop mail any key sends as it from now on");
+        println!("   • mail you already sent as it stays in recipients' inboxes");
+        println!("   • mail addressed to it can no longer be delivered");
+        println!();
+        println!("💡 To move it to a new key instead, use 'mycelix-mail did rotate <agent-key>'");
+        println!("   Re-run with --yes to revoke");
+        return Ok(());
+    }
+
+    client
+        .revoke_did(did.clone(), reason.unwrap_or_default())
+        .await
+        .context("Failed to revoke DID")?;
+
+    println!("🚫 Revoked {}", did);
+
+    Ok(())
+}
+
 /// Resolve a DID to agent key
 pub async fn handle_resolve(client: &MycellixClient, did: String) -> Result<()> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -56,32 +151,55 @@ pub async fn handle_resolve(client: &MycellixClient, did: String) -> Result<()>
     println!("🔍 Resolving DID: {}", did);
     println!();
 
-    // Resolve DID
-    println!("📡 Querying DID registry...");
+    // Resolve DID (DHT first, then registry and cache)
+    println!("📡 Querying DHT and DID registry...");
 
     match client
-        .resolve_did(did.clone())
+        .resolve_did(&did)
         .await
         .context("Failed to resolve DID")?
     {
-        Some(resolution) => {
+        Some(document) => {
             println!();
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
             println!("                  RESOLUTION RESULT");
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
             println!();
-            println!("🆔 DID:         {}", resolution.did);
-            println!("🔑 Agent Key:   {}", resolution.agent_pub_key);
-            println!("📅 Created:     {}", format_timestamp(resolution.created_at));
-            println!("🔄 Updated:     {}", format_timestamp(resolution.updated_at));
-            println!();
+            println!("🆔 DID:          {}", document.id);
+            match document.service_endpoint(resolver::HOLOCHAIN_AGENT_SERVICE) {
+                Some(agent_key) => println!("🔑 Agent Key:    {}", agent_key),
+                None => println!("🔑 Agent Key:    Not bound on the DHT"),
+            }
+            if let Some(key) = document
+                .verification_method
+                .first()
+                .and_then(|method| method.public_key_multibase.as_deref())
+            {
+                println!("🔐 Identity Key: {}", key);
+            }
+            for alias in &document.also_known_as {
+                println!("🔗 Also Known As: {}", alias);
+            }
 
-            let age = format_age(resolution.created_at);
-            println!("💡 This DID was registered {}", age);
+            if did.starts_with("did:mycelix:") {
+                if let Ok(Some(binding)) = client.get_did_binding(&did).await {
+                    println!("📅 Bound:        {}", format_timestamp(binding.bound_at));
+                    println!("🔁 Rotations:    {}", binding.rotations);
+                    println!();
+                    println!("💡 This DID was bound {}", format_age(binding.bound_at));
+                } else {
+                    println!();
+                    println!("💡 Resolved from the DID registry; not yet bound on the DHT");
+                }
+            } else {
+                println!();
+                println!("💡 Resolved from the DID itself or its domain");
+            }
+            println!("   Use --document to print the full DID document");
         }
         None => {
             println!();
-            println!("⚠️  DID not found on registry: {}", did);
+            println!("⚠️  DID not found: {}", did);
             println!("   Use 'mycelix-mail did register' to add it.");
         }
     }
@@ -89,6 +207,21 @@ pub async fn handle_resolve(client: &MycellixClient, did: String) -> Result<()>
     Ok(())
 }
 
+/// Print a DID's W3C DID document as JSON-LD
+///
+/// Only the document goes to stdout so it can be piped into other tools.
+pub async fn handle_resolve_document(client: &MycellixClient, did: String) -> Result<()> {
+    let document = client
+        .resolve_did(&did)
+        .await
+        .with_context(|| format!("Failed to resolve {}", did))?
+        .with_context(|| format!("DID not found: {}", did))?;
+
+    println!("{}", serde_json::to_string_pretty(&document)?);
+
+    Ok(())
+}
+
 /// List all known DIDs
 pub async fn handle_list(client: &MycellixClient, filter: Option<String>) -> Result<()> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
@@ -184,7 +317,17 @@ pub async fn handle_whoami(client: &MycellixClient) -> Result<()> {
                 }
             }
 
-            match client.resolve_did(did.clone()).await {
+            println!("🔗 DHT Binding:");
+            match client.get_did_binding(&did).await {
+                Ok(binding) => {
+                    let my_key = client.my_agent_pub_key().await.ok();
+                    println!("   {}", describe_binding(binding.as_ref(), my_key.as_deref()));
+                }
+                Err(err) => println!("   ⚠️  Could not query the DHT: {}", err),
+            }
+            println!();
+
+            match client.registry_record(&did).await {
                 Ok(Some(resolution)) => {
                     println!("📡 Registry Status:");
                     println!("   ✅ Registered on network");
@@ -218,6 +361,30 @@ pub async fn handle_whoami(client: &MycellixClient) -> Result<()> {
 
 // ========== Helper Functions ==========
 
+/// One-line summary of who holds a DID on the DHT
+fn describe_binding(binding: Option<&DidBinding>, my_key: Option<&str>) -> String {
+    let Some(binding) = binding else {
+        return "⚠️  Not bound yet; run 'mycelix-mail did register' to bind it".to_string();
+    };
+    if let Some(ref reason) = binding.revoked {
+        return match reason.as_str() {
+            "" => "🚫 Revoked".to_string(),
+            reason => format!("🚫 Revoked: {}", reason),
+        };
+    }
+
+    let rotations = match binding.rotations {
+        0 => String::new(),
+        1 => " (rotated once)".to_string(),
+        n => format!(" (rotated {} times)", n),
+    };
+    if my_key == Some(binding.agent_pub_key.as_str()) {
+        format!("✅ Bound to this agent{}", rotations)
+    } else {
+        format!("⚠️  Bound to another key: {}{}", truncate_key(&binding.agent_pub_key), rotations)
+    }
+}
+
 /// Format timestamp as human-readable string
 fn format_timestamp(ts: i64) -> String {
     use chrono::{DateTime, Utc};
@@ -331,6 +498,30 @@ mod tests {
         assert!(truncated.ends_with("yN8mH9B4K"));
     }
 
+    #[test]
+    fn test_describe_binding() {
+        let mut binding = DidBinding {
+            did: "did:mycelix:abc".to_string(),
+            agent_pub_key: "uhCAkMine".to_string(),
+            public_key: "ab".repeat(32),
+            binding_id: "uhCkkBinding".to_string(),
+            bound_at: 1609459200,
+            rotations: 0,
+            revoked: None,
+        };
+
+        assert!(describe_binding(None, Some("uhCAkMine")).contains("Not bound"));
+        assert_eq!(describe_binding(Some(&binding), Some("uhCAkMine")), "✅ Bound to this agent");
+
+        binding.rotations = 2;
+        let other = describe_binding(Some(&binding), Some("uhCAkOther"));
+        assert!(other.contains("another key: uhCAkMine"));
+        assert!(other.ends_with("(rotated 2 times)"));
+
+        binding.revoked = Some("laptop stolen".to_string());
+        assert_eq!(describe_binding(Some(&binding), Some("uhCAkMine")), "🚫 Revoked: laptop stolen");
+    }
+
     #[test]
     fn test_format_timestamp() {
         // Test with known timestamp (2021-01-01 00:00:00 UTC)
@@ -1,9 +1,11 @@
 use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
+use crypto_box::SecretKey;
 use std::fs::File;
 use std::io::Write;
 
 use crate::client::MycellixClient;
+use crate::crypto;
 use crate::types::MailMessage;
 
 /// Export messages to file in various formats
@@ -71,10 +73,11 @@ pub async fn handle_export(
     println!("💾 Writing {} message(s) to {}...", all_messages.len(), output);
 
     // 4. Export to file
+    let secret = client.encryption_key()?;
     match format {
         "json" => export_json(&all_messages, &output)?,
-        "mbox" => export_mbox(&all_messages, &output)?,
-        "csv" => export_csv(&all_messages, &output)?,
+        "mbox" => export_mbox(&all_messages, &output, &secret)?,
+        "csv" => export_csv(&all_messages, &output, &secret)?,
         _ => bail!("Unsupported export format: {}", format),
     }
 
@@ -107,7 +110,7 @@ fn export_json(messages: &[MailMessage], output: &str) -> Result<()> {
 }
 
 /// Export messages to MBOX format (standard Unix mailbox format)
-fn export_mbox(messages: &[MailMessage], output: &str) -> Result<()> {
+fn export_mbox(messages: &[MailMessage], output: &str, secret: &SecretKey) -> Result<()> {
     let mut file = File::create(output)
         .context(format!("Failed to create output file: {}", output))?;
 
@@ -122,11 +125,16 @@ fn export_mbox(messages: &[MailMessage], output: &str) -> Result<()> {
 
         // Headers
         let headers = format!(
-            "From: {}\nTo: {}\nDate: {}\nSubject: {}\n",
+            "From: {}\nTo: {}\n{}Date: {}\nSubject: {}\n",
             msg.from_did,
-            msg.to_did,
+            msg.to_dids.join(", "),
+            if msg.cc_dids.is_empty() {
+                String::new()
+            } else {
+                format!("Cc: {}\n", msg.cc_dids.join(", "))
+            },
             format_timestamp_rfc2822(msg.timestamp),
-            decrypt_subject(&msg.subject_encrypted)
+            crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, secret)
         );
         file.write_all(headers.as_bytes())
             .context("Failed to write MBOX headers")?;
@@ -141,27 +149,28 @@ fn export_mbox(messages: &[MailMessage], output: &str) -> Result<()> {
 }
 
 /// Export messages to CSV format
-fn export_csv(messages: &[MailMessage], output: &str) -> Result<()> {
+fn export_csv(messages: &[MailMessage], output: &str, secret: &SecretKey) -> Result<()> {
     let mut file = File::create(output)
         .context(format!("Failed to create output file: {}", output))?;
 
     // CSV header
-    let header = "Timestamp,Date,From,To,Subject,BodyCID,Tier,ThreadID\n";
+    let header = "Timestamp,Date,From,To,Cc,Subject,BodyCID,Tier,ThreadID\n";
     file.write_all(header.as_bytes())
         .context("Failed to write CSV header")?;
 
     // CSV rows
     for msg in messages {
-        let subject = decrypt_subject(&msg.subject_encrypted);
+        let subject = crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, secret);
         let thread_id = msg.thread_id.as_deref().unwrap_or("");
         let tier = format!("{:?}", msg.epistemic_tier);
 
         let row = format!(
-            "{},{},{},{},{},{},{},{}\n",
+            "{},{},{},{},{},{},{},{},{}\n",
             msg.timestamp,
             format_timestamp_iso8601(msg.timestamp),
             escape_csv(&msg.from_did),
-            escape_csv(&msg.to_did),
+            escape_csv(&msg.to_dids.join(";")),
+            escape_csv(&msg.cc_dids.join(";")),
             escape_csv(&subject),
             escape_csv(&msg.body_cid),
             tier,
@@ -222,19 +231,6 @@ fn format_name(format: &str) -> &str {
     }
 }
 
-/// Decrypt subject (placeholder implementation)
-fn decrypt_subject(encrypted: &[u8]) -> String {
-    if let Ok(s) = String::from_utf8(encrypted.to_vec()) {
-        if s.starts_with("ENC:") {
-            s[4..].to_string()
-        } else {
-            s
-        }
-    } else {
-        "<encrypted>".to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,17 +267,6 @@ mod tests {
         assert!(formatted.contains("GMT") || formatted.contains("+0000"));
     }
 
-    #[test]
-    fn test_decrypt_subject() {
-        let encrypted = b"ENC:Test Subject";
-        let decrypted = decrypt_subject(encrypted);
-        assert_eq!(decrypted, "Test Subject");
-
-        let plain = b"Plain Subject";
-        let result = decrypt_subject(plain);
-        assert_eq!(result, "Plain Subject");
-    }
-
     #[test]
     fn test_parse_date() {
         // Test with Unix timestamp
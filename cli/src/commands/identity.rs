@@ -0,0 +1,40 @@
+use anyhow::{Context, Result, bail};
+use std::path::Path;
+
+use crate::client::MycellixClient;
+use crate::keystore::{self, KeyFormat};
+
+/// Export our identity key for backup or to set up another device
+pub async fn handle_export(client: &MycellixClient, format: String, output: String) -> Result<()> {
+    let format: KeyFormat = format.parse()?;
+    let path = Path::new(&output);
+    // Fail before prompting for a passphrase; the file itself is created
+    // with create_new, so one appearing in the meantime is not replaced
+    if path.exists() {
+        bail!("{} already exists. Choose another output file.", output);
+    }
+
+    println!("🔑 Exporting identity key as {}...", format);
+
+    let contents = client
+        .export_identity_key(format)
+        .context("Failed to export identity key")?;
+    keystore::create_private_file(path, &contents)?;
+
+    println!();
+    println!("✅ Exported to {} (owner-only)", output);
+    if let Ok(did) = client.get_my_did() {
+        println!("🆔 DID: {}", did);
+    }
+    println!();
+
+    if format.is_plaintext() {
+        println!("⚠️  This file holds your private key unencrypted:");
+        println!("   anyone who reads it can read your mail and send as you");
+        println!();
+    }
+    println!("💡 To restore it on another machine, run:");
+    println!("   mycelix-mail init --import-keys {}", output);
+
+    Ok(())
+}
@@ -1,27 +1,76 @@
-use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use crypto_box::SecretKey;
+use std::fs;
+use std::path::Path;
 
 use crate::client::MycellixClient;
-use crate::types::MailMessage;
+use crate::config::Config;
+use crate::contacts::ContactBook;
+use crate::crypto;
+use crate::types::{MailMessage, MessagePage, PageQuery};
 
 /// List inbox messages with filtering and formatting
+///
+/// The conductor pages through the time index, so only `page.limit`
+/// messages are fetched; sender and state filters then apply to that page.
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_inbox(
     client: &MycellixClient,
     from: Option<String>,
     trust_min: Option<f64>,
     unread: bool,
-    limit: usize,
+    archived: bool,
+    quarantine: bool,
+    page: PageQuery,
     format: &str,
 ) -> Result<()> {
+    // Senders below the threshold are held in quarantine rather than dropped
+    let min_trust = match trust_min {
+        Some(min_trust) => min_trust,
+        None => client.get_config().preferences.min_trust,
+    };
+
+    if quarantine {
+        return super::quarantine::handle_quarantine(client, min_trust, page.limit).await;
+    }
+
     println!("📬 Fetching inbox...");
     println!();
 
-    // 1. Get inbox messages from client
-    let mut messages = client
-        .get_inbox()
+    if client.get_config().preferences.auto_report_blocked {
+        let filed = client
+            .report_blocked_senders()
+            .await
+            .context("Failed to report blocked senders")?;
+        if filed > 0 {
+            println!("🚩 Reported {} message(s) from blocked senders as spam", filed);
+            println!();
+        }
+    }
+
+    // Mail delivered before the time index existed is invisible to paged
+    // listing until it is indexed
+    if page.cursor.is_none() {
+        if let Err(e) = ensure_mailbox_indexed(client).await {
+            println!("⚠️  Could not index older mail: {}", e);
+            println!("   Messages may be missing; run 'mycelix-mail sync' to retry");
+            println!();
+        }
+    }
+
+    // 1. Get one page of inbox messages that pass the spam filter
+    let MessagePage { mut messages, next_cursor } = client
+        .get_filtered_inbox_page(min_trust, &page)
         .await
         .context("Failed to fetch inbox messages")?;
 
+    // Contacts only improve display, so an unavailable address book is not fatal
+    let book = client.contact_book().await.unwrap_or_default();
+
+    // A contact name or alias works as a sender filter too
+    let from = from.map(|sender| book.resolve(&sender).unwrap_or(sender));
+
     // Show filters being applied
     let mut filter_count = 0;
     if from.is_some() {
@@ -33,6 +82,12 @@ pub async fn handle_inbox(
     if unread {
         filter_count += 1;
     }
+    if archived {
+        filter_count += 1;
+    }
+    if page.since.is_some() || page.until.is_some() {
+        filter_count += 1;
+    }
 
     if filter_count > 0 {
         println!("🔍 Applying {} filter(s):", filter_count);
@@ -45,22 +100,27 @@ pub async fn handle_inbox(
         if unread {
             println!("   • Unread only");
         }
+        if archived {
+            println!("   • Archived");
+        }
+        if let Some(since) = page.since {
+            println!("   • Since: {}", format_date(since));
+        }
+        if let Some(until) = page.until {
+            println!("   • Until: {}", format_date(until));
+        }
         println!();
     }
 
     // 2. Apply filters
-    messages = apply_filters(messages, from, trust_min, unread);
-
-    // 3. Sort by timestamp (newest first)
-    messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
-
-    // 4. Apply limit
-    let total_count = messages.len();
-    messages.truncate(limit);
+    let page_count = messages.len();
+    messages = apply_filters(messages, from, unread, archived);
 
     // Handle empty inbox
     if messages.is_empty() {
-        if filter_count > 0 {
+        if next_cursor.is_some() {
+            println!("No messages on this page match your filters.");
+        } else if filter_count > 0 || page.cursor.is_some() {
             println!("No messages match your filters.");
             println!("Try removing some filters or check 'mycelix-mail inbox' to see all messages.");
         } else {
@@ -70,41 +130,132 @@ pub async fn handle_inbox(
             println!("  1. Share your DID with contacts: mycelix-mail did");
             println!("  2. Wait for others to send you messages");
         }
+        print_next_page_tip(next_cursor.as_deref());
         return Ok(());
     }
 
-    // 5. Format and display
+    // 3. Format and display
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    println!("Showing {} of {} message(s)", messages.len(), total_count);
+    println!("Showing {} of {} message(s) on this page", messages.len(), page_count);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
     match format {
         "json" => display_json(&messages)?,
-        "raw" => display_raw(&messages),
-        _ => display_table(&messages),
+        "raw" => display_raw(&messages, &book),
+        _ => display_table(&messages, &book, &client.encryption_key()?),
     }
 
-    println!();
+    print_next_page_tip(next_cursor.as_deref());
 
-    // Show helpful tips
-    if messages.len() < total_count {
-        println!(
-            "💡 Showing first {} messages. Use --limit {} to see more.",
-            limit,
-            total_count
-        );
+    Ok(())
+}
+
+/// Index mail that predates the mailbox time index, once per agent
+///
+/// New mail is indexed as it is delivered, so only an inbox from before the
+/// index needs this. A marker file in the cache directory lists the agents
+/// whose mailbox has been indexed; `sync` re-runs the indexing regardless.
+async fn ensure_mailbox_indexed(client: &MycellixClient) -> Result<()> {
+    let agent = client.my_agent_pub_key().await?;
+    let marker = Config::cache_dir()?.join("mailbox_indexed");
+    if is_marked_indexed(&marker, &agent) {
+        return Ok(());
     }
 
-    Ok(())
+    let indexed = client.index_mailbox().await?;
+    if indexed > 0 {
+        println!("📇 Indexed {} older message(s) for paged listing", indexed);
+        println!();
+    }
+    mark_indexed(&marker, &agent)
+}
+
+fn is_marked_indexed(marker: &Path, agent: &str) -> bool {
+    fs::read_to_string(marker)
+        .map(|contents| contents.lines().any(|line| line.trim() == agent))
+        .unwrap_or(false)
+}
+
+fn mark_indexed(marker: &Path, agent: &str) -> Result<()> {
+    if let Some(dir) = marker.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    let mut contents = fs::read_to_string(marker).unwrap_or_default();
+    contents.push_str(agent);
+    contents.push('\n');
+    fs::write(marker, contents)
+        .with_context(|| format!("Failed to write {}", marker.display()))
+}
+
+/// Build the page query for `inbox` from its command-line arguments
+///
+/// `since` and `until` accept a date (`YYYY-MM-DD`), an RFC 3339 timestamp
+/// or Unix seconds; a bare `until` date includes that whole day.
+pub fn page_query(
+    since: Option<String>,
+    until: Option<String>,
+    limit: usize,
+    cursor: Option<String>,
+) -> Result<PageQuery> {
+    let since = since.map(|s| parse_time_bound(&s, false)).transpose()?;
+    let until = until.map(|s| parse_time_bound(&s, true)).transpose()?;
+    if let (Some(since), Some(until)) = (since, until) {
+        if since > until {
+            bail!("--since must not be later than --until");
+        }
+    }
+    if limit == 0 {
+        bail!("--limit must be at least 1");
+    }
+
+    Ok(PageQuery { since, until, limit, cursor })
+}
+
+/// Parse a time bound to Unix seconds
+fn parse_time_bound(value: &str, end_of_day: bool) -> Result<i64> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<i64>() {
+        return Ok(seconds);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.timestamp());
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| {
+        format!("Invalid date: '{}'. Use YYYY-MM-DD, RFC 3339 or Unix seconds", value)
+    })?;
+    let time = if end_of_day {
+        date.and_hms_opt(23, 59, 59)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(time.context("Invalid date")?.and_utc().timestamp())
+}
+
+fn print_next_page_tip(next_cursor: Option<&str>) {
+    if let Some(cursor) = next_cursor {
+        println!();
+        println!("💡 More messages: mycelix-mail inbox --cursor {}", cursor);
+        println!("   (repeat --since/--until and filters to keep the same view)");
+    }
+}
+
+fn format_date(ts: i64) -> String {
+    DateTime::<Utc>::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| ts.to_string())
 }
 
 /// Apply filters to message list
+///
+/// Archived messages are only listed when `archived` is set, and then
+/// exclusively.
 fn apply_filters(
     messages: Vec<MailMessage>,
     from: Option<String>,
-    trust_min: Option<f64>,
     unread: bool,
+    archived: bool,
 ) -> Vec<MailMessage> {
     messages
         .into_iter()
@@ -116,46 +267,46 @@ fn apply_filters(
                 }
             }
 
-            // Filter by trust score
-            // TODO: Implement trust score lookup
-            // For now, we skip trust filtering since we don't have trust data
-            if trust_min.is_some() {
-                // Would check: client.get_trust_score(&msg.from_did).score >= trust_min
-            }
-
             // Filter by unread status
-            if unread {
-                // TODO: Implement read status tracking
-                // For now, all messages are considered unread
+            if unread && msg.state.read {
+                return false;
             }
 
-            true
+            msg.state.archived == archived
         })
         .collect()
 }
 
 /// Display messages in table format
-fn display_table(messages: &[MailMessage]) {
-    println!("{:<6} {:<40} {:<20} {:<20} {:<6}",
-        "ID", "From", "Subject", "Time", "Tier"
+fn display_table(messages: &[MailMessage], book: &ContactBook, secret: &SecretKey) {
+    println!("{:<3} {:<40} {:<20} {:<20} {:<6}",
+        "", "From", "Subject", "Time", "Tier"
     );
-    println!("{}", "─".repeat(95));
+    println!("{}", "─".repeat(93));
 
-    for (i, msg) in messages.iter().enumerate() {
-        let msg_id = format!("#{}", i + 1);
-        let from_short = truncate_did(&msg.from_did, 38);
-        let subject = decrypt_subject(&msg.subject_encrypted);
+    for msg in messages {
+        let from_short = match book.find_by_did(&msg.from_did) {
+            Some(contact) => truncate_string(&contact.name, 38),
+            None => truncate_did(&msg.from_did, 38),
+        };
+        let subject = crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, secret);
         let subject_short = truncate_string(&subject, 18);
         let time_str = format_timestamp(msg.timestamp);
         let tier_short = format_tier_short(&msg.epistemic_tier);
 
-        println!("{:<6} {:<40} {:<20} {:<20} {:<6}",
-            msg_id, from_short, subject_short, time_str, tier_short
+        println!("{:<3} {:<40} {:<20} {:<20} {:<6}",
+            format_flags(msg), from_short, subject_short, time_str, tier_short
         );
+        println!("    ID: {}", msg.id);
+        if !msg.state.labels.is_empty() {
+            println!("    Labels: {}", msg.state.labels.join(", "));
+        }
     }
 
     println!();
     println!("💡 Use 'mycelix-mail read <id>' to view full message");
+    println!("   (● = unread, ★ = starred)");
+    println!("   Low-trust mail is held back: 'mycelix-mail inbox --quarantine'");
 }
 
 /// Display messages in JSON format
@@ -167,11 +318,15 @@ fn display_json(messages: &[MailMessage]) -> Result<()> {
 }
 
 /// Display messages in raw format
-fn display_raw(messages: &[MailMessage]) {
+fn display_raw(messages: &[MailMessage], book: &ContactBook) {
     for (i, msg) in messages.iter().enumerate() {
         println!("Message #{}", i + 1);
-        println!("  From: {}", msg.from_did);
-        println!("  To: {}", msg.to_did);
+        println!("  ID: {}", msg.id);
+        println!("  From: {}", book.display(&msg.from_did));
+        println!("  To: {}", book.display_list(&msg.to_dids));
+        if !msg.cc_dids.is_empty() {
+            println!("  Cc: {}", book.display_list(&msg.cc_dids));
+        }
         println!("  Subject (encrypted): {} bytes", msg.subject_encrypted.len());
         println!("  Body CID: {}", msg.body_cid);
         println!("  Timestamp: {} ({})", msg.timestamp, format_timestamp(msg.timestamp));
@@ -184,7 +339,7 @@ fn display_raw(messages: &[MailMessage]) {
 }
 
 /// Truncate a DID for display
-fn truncate_did(did: &str, max_len: usize) -> String {
+pub(crate) fn truncate_did(did: &str, max_len: usize) -> String {
     if did.len() <= max_len {
         did.to_string()
     } else {
@@ -195,17 +350,25 @@ fn truncate_did(did: &str, max_len: usize) -> String {
     }
 }
 
+/// Unread/starred markers for the table view
+fn format_flags(msg: &MailMessage) -> String {
+    let unread = if msg.state.read { ' ' } else { '●' };
+    let starred = if msg.state.starred { '★' } else { ' ' };
+    format!("{}{}", unread, starred)
+}
+
 /// Truncate a string for display
-fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+pub(crate) fn truncate_string(s: &str, max_len: usize) -> String {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+        let kept: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
     }
 }
 
 /// Format timestamp as relative time
-fn format_timestamp(ts: i64) -> String {
+pub(crate) fn format_timestamp(ts: i64) -> String {
     let dt = DateTime::<Utc>::from_timestamp(ts, 0)
         .unwrap_or_else(|| Utc::now());
 
@@ -237,32 +400,25 @@ fn format_tier_short(tier: &crate::types::EpistemicTier) -> String {
     }
 }
 
-/// Decrypt subject (placeholder implementation)
-///
-/// TODO: Implement real decryption
-/// - Fetch sender's public key
-/// - Decrypt using our private key
-fn decrypt_subject(encrypted: &[u8]) -> String {
-    // Placeholder: Just try to convert from bytes
-    // In real implementation, this would use NaCl decryption
-
-    if let Ok(s) = String::from_utf8(encrypted.to_vec()) {
-        // If it's our fake "ENC:" prefix, strip it
-        if s.starts_with("ENC:") {
-            s[4..].to_string()
-        } else {
-            s
-        }
-    } else {
-        "<encrypted>".to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::EpistemicTier;
 
+    #[test]
+    fn test_mailbox_index_marker() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("cache").join("mailbox_indexed");
+
+        assert!(!is_marked_indexed(&marker, "uhCAkAlice"));
+        mark_indexed(&marker, "uhCAkAlice").unwrap();
+        mark_indexed(&marker, "uhCAkBob").unwrap();
+
+        assert!(is_marked_indexed(&marker, "uhCAkAlice"));
+        assert!(is_marked_indexed(&marker, "uhCAkBob"));
+        assert!(!is_marked_indexed(&marker, "uhCAkCarol"));
+    }
+
     #[test]
     fn test_truncate_did() {
         let did = "did:mycelix:ATHMuhr4Mk9fx2VMUx5kzVPVkL5zyvQGZ1gofWQmJtG6";
@@ -287,39 +443,105 @@ mod tests {
     }
 
     #[test]
-    fn test_decrypt_subject_placeholder() {
-        let encrypted = b"ENC:Test Subject";
-        let decrypted = decrypt_subject(encrypted);
-        assert_eq!(decrypted, "Test Subject");
+    fn test_page_query_parses_time_bounds() {
+        let query = page_query(
+            Some("2021-01-01".to_string()),
+            Some("2021-01-01".to_string()),
+            20,
+            None,
+        )
+        .unwrap();
+        assert_eq!(query.since, Some(1609459200));
+        assert_eq!(query.until, Some(1609459200 + 86399));
+
+        let query = page_query(Some("1609459200".to_string()), None, 5, Some("c".to_string())).unwrap();
+        assert_eq!(query.since, Some(1609459200));
+        assert_eq!(query.cursor.as_deref(), Some("c"));
+
+        assert_eq!(
+            parse_time_bound("2021-01-01T01:00:00+01:00", false).unwrap(),
+            1609459200
+        );
+        assert!(page_query(Some("yesterday".to_string()), None, 20, None).is_err());
+        assert!(page_query(
+            Some("2021-02-01".to_string()),
+            Some("2021-01-01".to_string()),
+            20,
+            None
+        )
+        .is_err());
+        assert!(page_query(None, None, 0, None).is_err());
     }
 
     #[test]
     fn test_apply_filters_empty() {
         let messages = vec![];
-        let filtered = apply_filters(messages, None, None, false);
+        let filtered = apply_filters(messages, None, false, false);
         assert_eq!(filtered.len(), 0);
     }
 
     #[test]
     fn test_apply_filters_by_sender() {
         let msg = MailMessage {
+            id: "uhCkkABC".to_string(),
             from_did: "did:mycelix:ABC123".to_string(),
-            to_did: "did:mycelix:XYZ789".to_string(),
+            to_dids: vec!["did:mycelix:XYZ789".to_string()],
+            cc_dids: vec![],
             subject_encrypted: b"Test".to_vec(),
             body_cid: "bafyrei123".to_string(),
             timestamp: 1234567890,
             thread_id: None,
             epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+            recipient_keys: vec![],
+            expires_at: None,
+            state: Default::default(),
         };
 
         let messages = vec![msg.clone()];
 
         // Should match
-        let filtered = apply_filters(messages.clone(), Some("ABC".to_string()), None, false);
+        let filtered = apply_filters(messages.clone(), Some("ABC".to_string()), false, false);
         assert_eq!(filtered.len(), 1);
 
         // Should not match
-        let filtered = apply_filters(messages.clone(), Some("ZZZ".to_string()), None, false);
+        let filtered = apply_filters(messages.clone(), Some("ZZZ".to_string()), false, false);
         assert_eq!(filtered.len(), 0);
     }
+
+    #[test]
+    fn test_apply_filters_by_state() {
+        let base = MailMessage {
+            id: "uhCkkABC".to_string(),
+            from_did: "did:mycelix:ABC123".to_string(),
+            to_dids: vec!["did:mycelix:XYZ789".to_string()],
+            cc_dids: vec![],
+            subject_encrypted: b"Test".to_vec(),
+            body_cid: "bafyrei123".to_string(),
+            timestamp: 1234567890,
+            thread_id: None,
+            epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+            recipient_keys: vec![],
+            expires_at: None,
+            state: Default::default(),
+        };
+        let mut read = base.clone();
+        read.state.read = true;
+        let mut archived = base.clone();
+        archived.state.archived = true;
+
+        let messages = vec![base, read, archived];
+
+        // Archived messages are hidden from the normal view
+        assert_eq!(apply_filters(messages.clone(), None, false, false).len(), 2);
+
+        // --unread drops read messages
+        let unread = apply_filters(messages.clone(), None, true, false);
+        assert_eq!(unread.len(), 1);
+        assert!(!unread[0].state.read);
+
+        // --archived shows only archived messages
+        let only_archived = apply_filters(messages, None, false, true);
+        assert_eq!(only_archived.len(), 1);
+        assert!(only_archived[0].state.archived);
+    }
 }
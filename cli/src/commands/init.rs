@@ -1,14 +1,16 @@
 use anyhow::{Context, Result, bail};
-use ed25519_dalek::{SigningKey, VerifyingKey};
-use blake2::{Blake2b512, Digest};
+use ed25519_dalek::SigningKey;
 use std::fs;
 
 use crate::config::Config;
 use crate::client::MycellixClient;
+use crate::crypto::create_did;
+use crate::keystore::{self, KeyFormat};
 
 /// Initialize Mycelix Mail
 ///
-/// Sets up user profile, generates keys, and registers DID
+/// Sets up user profile, generates keys, registers the DID and binds it to
+/// our agent in the DNA so we can send mail as it
 pub async fn handle_init(
     email: Option<String>,
     import_keys: Option<String>,
@@ -37,8 +39,17 @@ pub async fn handle_init(
     // 3. Generate or import keys
     let (signing_key, verifying_key) = if let Some(keys_path) = import_keys {
         println!("🔑 Importing existing keys from {}...", keys_path);
-        // TODO: Implement key import from file
-        bail!("Key import not yet implemented. Please generate new keys instead.");
+
+        let bytes = fs::read(&keys_path)
+            .with_context(|| format!("Failed to read key file: {}", keys_path))?;
+        let (signing_key, format) = keystore::decode_signing_key(&bytes, || {
+            keystore::read_passphrase("🔐 Passphrase for the imported keystore: ")
+        })
+        .with_context(|| format!("Failed to import keys from {}", keys_path))?;
+        println!("   Format: {}", format);
+
+        let verifying_key = signing_key.verifying_key();
+        (signing_key, verifying_key)
     } else {
         println!("🔑 Generating new Ed25519 keypair...");
 
@@ -57,17 +68,13 @@ pub async fn handle_init(
         (signing_key, verifying_key)
     };
 
-    // 4. Save keys to disk
+    // 4. Save keys to disk, the private key in a passphrase-encrypted keystore
+    println!();
+    let passphrase = keystore::new_passphrase()?;
     println!("💾 Saving keys to {}...", config.identity.private_key_path.display());
 
-    // Ensure keys directory exists
-    if let Some(parent) = config.identity.private_key_path.parent() {
-        fs::create_dir_all(parent)
-            .context("Failed to create keys directory")?;
-    }
-
-    // Save private key (32 bytes) - IMPORTANT: Keep this secure!
-    fs::write(&config.identity.private_key_path, signing_key.to_bytes())
+    let private_key = keystore::encode_signing_key(&signing_key, KeyFormat::Keystore, || Ok(passphrase))?;
+    keystore::write_private_file(&config.identity.private_key_path, &private_key)
         .context("Failed to write private key")?;
 
     // Save public key (32 bytes)
@@ -75,8 +82,9 @@ pub async fn handle_init(
         .context("Failed to write public key")?;
 
     println!("✅ Keys saved successfully");
-    println!("   Private key: {}", config.identity.private_key_path.display());
+    println!("   Private key: {} (encrypted, owner-only)", config.identity.private_key_path.display());
     println!("   Public key: {}", config.identity.public_key_path.display());
+    println!("   💡 Keep your passphrase safe: it cannot be recovered");
 
     // 5. Create DID from public key
     println!();
@@ -98,7 +106,8 @@ pub async fn handle_init(
         config.clone(),
     ).await?;
 
-    match client.register_did(did.clone(), agent_pub_key.clone()).await {
+    let public_key = hex::encode(verifying_key.to_bytes());
+    match client.register_did(did.clone(), agent_pub_key.clone(), Some(public_key)).await {
         Ok(_) => {
             println!("✅ DID registered successfully");
         }
@@ -109,9 +118,29 @@ pub async fn handle_init(
         }
     }
 
-    // 8. Update configuration with DID and keys
+    // 8. Bind the DID to our agent in the DNA; sending fails until it is
+    println!();
+    println!("📡 Binding DID to this agent on the DHT...");
+    let bound = match client.bind_did(did.clone()).await {
+        Ok(()) => {
+            println!("✅ DID bound to this agent");
+            true
+        }
+        Err(e) if e.to_string().contains("already registered") => {
+            println!("✅ DID already bound on the DHT");
+            true
+        }
+        Err(e) => {
+            println!("⚠️  Warning: DID binding failed: {}", e);
+            println!("   You cannot send mail until it is bound. Once the conductor is running, run:");
+            println!("   mycelix-mail did register {} {}", did, agent_pub_key);
+            false
+        }
+    };
+
+    // 9. Update configuration with DID and keys
     config.set_did(did.clone())?;
-    config.set_agent_key(agent_pub_key)?;
+    config.set_agent_key(agent_pub_key.clone())?;
 
     if let Some(email_addr) = email {
         println!("✉️  Setting email: {}", email_addr);
@@ -128,6 +157,9 @@ pub async fn handle_init(
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
     println!("Next steps:");
+    if !bound {
+        println!("  • Run 'mycelix-mail did register {} {}' before sending mail", did, agent_pub_key);
+    }
     println!("  • Run 'mycelix-mail did whoami' to verify your identity");
     println!("  • Run 'mycelix-mail send <did> --subject \"Hello\" --body \"Test\"' to send a message");
     println!("  • Run 'mycelix-mail inbox' to check for messages");
@@ -136,27 +168,6 @@ pub async fn handle_init(
     Ok(())
 }
 
-/// Create a DID from a verifying key
-///
-/// Format: did:mycelix:{base58(blake2b(pubkey))}
-///
-/// This creates a deterministic DID from the public key using:
-/// 1. Blake2b-512 hash of the public key (for collision resistance)
-/// 2. Take first 32 bytes of the hash (256 bits of entropy)
-/// 3. Base58 encode for human-friendly representation
-fn create_did(verifying_key: &VerifyingKey) -> String {
-    // Hash the public key with Blake2b-512
-    let mut hasher = Blake2b512::new();
-    hasher.update(verifying_key.to_bytes());
-    let hash = hasher.finalize();
-
-    // Take first 32 bytes of hash and encode as base58
-    let hash_bytes = &hash[..32];
-    let encoded = bs58::encode(hash_bytes).into_string();
-
-    format!("did:mycelix:{}", encoded)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
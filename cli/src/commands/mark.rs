@@ -0,0 +1,80 @@
+use anyhow::{Context, Result, bail};
+
+use crate::client::MycellixClient;
+use crate::types::MessageStateUpdate;
+
+/// Change a message's private read/starred/archived/label state
+pub async fn handle_mark(
+    client: &MycellixClient,
+    message_id: String,
+    update: MessageStateUpdate,
+) -> Result<()> {
+    if update.is_empty() {
+        bail!(
+            "Nothing to change. Use --read/--unread, --star/--unstar, \
+             --archive/--unarchive, --label or --unlabel"
+        );
+    }
+
+    println!("🏷️  Updating message state...");
+    println!();
+
+    let state = client
+        .update_message_state(&message_id, update)
+        .await
+        .context("Failed to update message state")?;
+
+    println!("✅ Message updated");
+    println!();
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Message ID: {}", message_id.trim());
+    println!("Read:       {}", yes_no(state.read));
+    println!("Starred:    {}", yes_no(state.starred));
+    println!("Archived:   {}", yes_no(state.archived));
+    if state.labels.is_empty() {
+        println!("Labels:     (none)");
+    } else {
+        println!("Labels:     {}", state.labels.join(", "));
+    }
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+    println!("🔒 Message state is private to you and never leaves your source chain.");
+
+    Ok(())
+}
+
+/// Combine an on/off flag pair into an optional change
+pub fn toggle(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle() {
+        assert_eq!(toggle(true, false), Some(true));
+        assert_eq!(toggle(false, true), Some(false));
+        assert_eq!(toggle(false, false), None);
+    }
+
+    #[test]
+    fn test_empty_update() {
+        assert!(MessageStateUpdate::default().is_empty());
+
+        let update = MessageStateUpdate {
+            add_labels: vec!["work".to_string()],
+            ..Default::default()
+        };
+        assert!(!update.is_empty());
+    }
+}
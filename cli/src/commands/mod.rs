@@ -6,10 +6,17 @@
 pub mod init;
 pub mod send;
 pub mod inbox;
+pub mod quarantine;
+pub mod delete;
+pub mod mark;
 pub mod read;
+pub mod thread;
 pub mod trust;
 pub mod did;
+pub mod identity;
+pub mod contact;
 pub mod search;
 pub mod export;
 pub mod status;
 pub mod sync;
+pub mod watch;
@@ -0,0 +1,138 @@
+use anyhow::{Context, Result};
+
+use crate::client::MycellixClient;
+use crate::contacts::ContactBook;
+use crate::types::QuarantinedMessage;
+
+use super::inbox::{format_timestamp, truncate_did, truncate_string};
+
+/// List messages held back because their sender is below `min_trust`
+pub async fn handle_quarantine(client: &MycellixClient, min_trust: f64, limit: usize) -> Result<()> {
+    println!("🚧 Fetching quarantine (minimum trust {:.2})...", min_trust);
+    println!();
+
+    let mut quarantined = client
+        .get_quarantine(min_trust)
+        .await
+        .context("Failed to fetch quarantined messages")?;
+
+    if quarantined.is_empty() {
+        println!("Quarantine is empty. Nothing was held back.");
+        return Ok(());
+    }
+
+    let book = client.contact_book().await.unwrap_or_default();
+
+    quarantined.sort_by(|a, b| b.message.timestamp.cmp(&a.message.timestamp));
+    let total_count = quarantined.len();
+    quarantined.truncate(limit);
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Showing {} of {} quarantined message(s)", quarantined.len(), total_count);
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+
+    println!("{:<40} {:<20} {:<6}", "From", "Time", "Trust");
+    println!("{}", "─".repeat(68));
+
+    for entry in &quarantined {
+        println!("{}", format_quarantine_row(entry, &book));
+        println!("    ID:     {}", entry.message.id);
+        println!("    Reason: {}", entry.reason);
+    }
+
+    println!();
+    println!("💡 Use 'mycelix-mail release <id>' to move a message to your inbox");
+    println!("   and trust its sender for future mail");
+    if quarantined.iter().any(|entry| entry.blocked_by.is_some()) {
+        println!("   ⛔ = blocked sender; 'mycelix-mail trust unblock <pattern>' lifts the rule");
+    }
+
+    Ok(())
+}
+
+/// Release a quarantined message; its sender gains local trust
+pub async fn handle_release(client: &MycellixClient, message_id: String) -> Result<()> {
+    let sender = client
+        .release_from_quarantine(&message_id)
+        .await
+        .context("Failed to release message")?;
+
+    let book = client.contact_book().await.unwrap_or_default();
+
+    println!("✅ Released message {}", message_id);
+    println!("   Sender {} is now trusted more for future mail", book.display(&sender));
+    println!();
+    println!("💡 Use 'mycelix-mail read {}' to open it", message_id);
+
+    Ok(())
+}
+
+/// One table row for a quarantined message
+fn format_quarantine_row(entry: &QuarantinedMessage, book: &ContactBook) -> String {
+    let from = match book.find_by_did(&entry.message.from_did) {
+        Some(contact) => truncate_string(&contact.name, 38),
+        None => truncate_did(&entry.message.from_did, 38),
+    };
+
+    let trust = match entry.blocked_by {
+        Some(_) => "⛔".to_string(),
+        None => format!("{:.2}", entry.trust_score),
+    };
+
+    format!(
+        "{:<40} {:<20} {:<6}",
+        from,
+        format_timestamp(entry.message.timestamp),
+        trust
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Contact, EpistemicTier, MailMessage};
+
+    #[test]
+    fn test_format_quarantine_row() {
+        let entry = QuarantinedMessage {
+            message: MailMessage {
+                id: "uhCkkABC".to_string(),
+                from_did: "did:mycelix:ABC123".to_string(),
+                to_dids: vec!["did:mycelix:XYZ789".to_string()],
+                cc_dids: vec![],
+                subject_encrypted: b"Test".to_vec(),
+                body_cid: "bafyrei123".to_string(),
+                timestamp: 1234567890,
+                thread_id: None,
+                epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+                recipient_keys: vec![],
+                expires_at: None,
+                state: Default::default(),
+            },
+            trust_score: 0.214,
+            reason: "Sender trust 0.21 is below 0.50".to_string(),
+            blocked_by: None,
+        };
+
+        let row = format_quarantine_row(&entry, &ContactBook::default());
+        assert!(row.starts_with("did:mycelix:ABC123"));
+        assert!(row.contains("2009-02-13"));
+        assert!(row.trim_end().ends_with("0.21"));
+
+        let book = ContactBook::new(vec![Contact {
+            did: "did:mycelix:ABC123".to_string(),
+            name: "Alice".to_string(),
+            email_alias: None,
+            notes: None,
+            trust_score: None,
+        }]);
+        assert!(format_quarantine_row(&entry, &book).starts_with("Alice "));
+
+        let blocked = QuarantinedMessage {
+            blocked_by: Some("did:mycelix:ABC*".to_string()),
+            ..entry
+        };
+        assert!(format_quarantine_row(&blocked, &book).trim_end().ends_with("⛔"));
+    }
+}
@@ -1,13 +1,18 @@
 use anyhow::{Context, Result, bail};
 use chrono::{DateTime, Utc};
+use std::path::Path;
 
+use crate::attachments::{self, AttachmentRef};
 use crate::client::MycellixClient;
+use crate::crypto::{self, ContentKey};
+use crate::storage::{self, MessageContent};
 
 /// Read and display a specific message
 pub async fn handle_read(
     client: &MycellixClient,
     message_id: String,
     mark_read: bool,
+    save_attachments: Option<String>,
 ) -> Result<()> {
     println!("📖 Reading message...");
     println!();
@@ -18,21 +23,37 @@ pub async fn handle_read(
         .await
         .context("Failed to fetch message")?;
 
-    // 2. Decrypt subject
-    let subject = decrypt_subject(&message.subject_encrypted);
+    // 2. Open our slot of the content key with our X25519 key
+    let secret = client.encryption_key()?;
+    let content_key = crypto::open_content_key(&message.recipient_keys, &secret)
+        .context("This message was not encrypted for your key")?;
+    let subject = crypto::decrypt_subject(&message.subject_encrypted, &message.recipient_keys, &secret);
 
-    // 3. Fetch body from IPFS/DHT
-    let body = fetch_body(&message.body_cid).await?;
+    // 3. Fetch, verify and decrypt body from the DHT
+    let content = fetch_body(client, &message.body_cid, &content_key).await?;
+
+    // 4. Display formatted message, naming senders and recipients we know
+    let book = client.contact_book().await.unwrap_or_default();
 
-    // 4. Display formatted message
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("                         MESSAGE DETAILS");
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
-    println!("📬 From:    {}", message.from_did);
-    println!("📭 To:      {}", message.to_did);
+    println!("📬 From:    {}", book.display(&message.from_did));
+    println!("📭 To:      {}", book.display_list(&message.to_dids));
+    if !message.cc_dids.is_empty() {
+        println!("📋 Cc:      {}", book.display_list(&message.cc_dids));
+    }
+    if let Ok(me) = client.whoami() {
+        if me != message.from_did && !message.lists_recipient(&me) {
+            println!("🙈 Bcc:     you were blind-copied");
+        }
+    }
     println!("📅 Date:    {}", format_timestamp(message.timestamp));
     println!("🏷️  Tier:    {}", format_tier(&message.epistemic_tier));
+    if let Some(expiry) = message.expires_at {
+        println!("⏳ Expires: {}", format_timestamp(expiry));
+    }
 
     if let Some(ref thread) = message.thread_id {
         println!("🧵 Thread:  {}", thread);
@@ -43,10 +64,37 @@ pub async fn handle_read(
     println!("Subject: {}", subject);
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
-    println!("{}", body);
+    println!("{}", content.body);
     println!();
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
+    if !content.attachments.is_empty() {
+        println!();
+        println!("📎 Attachments ({}):", content.attachments.len());
+        for attachment in &content.attachments {
+            println!(
+                "   • {} ({}, {})",
+                attachment.filename,
+                attachment.mime_type,
+                attachments::format_size(attachment.size)
+            );
+        }
+
+        match save_attachments {
+            Some(dir) => {
+                println!();
+                for attachment in &content.attachments {
+                    let path = save_attachment(client, attachment, Path::new(&dir)).await?;
+                    println!("   💾 Saved {}", path);
+                }
+            }
+            None => {
+                println!();
+                println!("💡 Use --save-attachments <dir> to download them");
+            }
+        }
+    }
+
     // 5. Mark as read if requested
     if mark_read {
         println!();
@@ -66,57 +114,53 @@ pub async fn handle_read(
     Ok(())
 }
 
-/// Decrypt subject (placeholder implementation)
-///
-/// TODO: Implement real decryption
-/// - Fetch sender's public key
-/// - Decrypt using our private key
-fn decrypt_subject(encrypted: &[u8]) -> String {
-    // Placeholder: Just try to convert from bytes
-    // In real implementation, this would use NaCl decryption
-
-    if let Ok(s) = String::from_utf8(encrypted.to_vec()) {
-        // If it's our fake "ENC:" prefix, strip it
-        if s.starts_with("ENC:") {
-            s[4..].to_string()
-        } else {
-            s
-        }
-    } else {
-        "<encrypted>".to_string()
+/// Fetch body from the DHT, verify it against its CID and decrypt it
+async fn fetch_body(
+    client: &MycellixClient,
+    cid: &str,
+    content_key: &ContentKey,
+) -> Result<MessageContent> {
+    if !storage::is_valid_cid(cid) {
+        bail!("Invalid CID format: {}", cid);
     }
+
+    let ciphertext = client
+        .fetch_blob(cid)
+        .await
+        .context("Failed to fetch message body")?;
+
+    storage::decrypt_body(cid, &ciphertext, content_key)
 }
 
-/// Fetch body from IPFS/DHT (placeholder implementation)
+/// Fetch, verify and decrypt an attachment's chunks and write it into `dir`
 ///
-/// TODO: Implement real body fetching
-/// - Fetch from IPFS or Holochain DHT using CID
-/// - Decrypt if encrypted
-/// - Return body text
-async fn fetch_body(cid: &str) -> Result<String> {
-    // Placeholder: Just return a message indicating we would fetch from IPFS
-    // In real implementation:
-    // 1. Connect to IPFS node or Holochain DHT
-    // 2. Fetch content by CID
-    // 3. Decrypt if encrypted
-    // 4. Return text content
-
-    if cid.starts_with("bafyrei") {
-        // This is our fake CID format from send command
-        Ok(format!(
-            "(Message body would be fetched from IPFS/DHT using CID: {})\n\
-             \n\
-             In production, this would display the actual message content\n\
-             fetched from the distributed hash table.",
-            cid
-        ))
-    } else {
-        bail!("Invalid CID format: {}", cid)
+/// Returns the path written. Chunks that have not replicated yet are
+/// reported rather than producing a partial file, and an existing file is
+/// never overwritten (see [`attachments::save_to_dir`]).
+async fn save_attachment(
+    client: &MycellixClient,
+    attachment: &AttachmentRef,
+    dir: &Path,
+) -> Result<String> {
+    attachments::check_reference(attachment)?;
+
+    let mut chunks = Vec::with_capacity(attachment.chunks.len());
+    for cid in &attachment.chunks {
+        let chunk = client
+            .try_fetch_blob(cid)
+            .await
+            .with_context(|| format!("Failed to fetch a chunk of {}", attachment.filename))?;
+        chunks.push(chunk);
     }
+
+    let data = attachments::reassemble_attachment(attachment, &chunks)?;
+    let path = attachments::save_to_dir(dir, &attachment.filename, &data)?;
+
+    Ok(path.display().to_string())
 }
 
 /// Format timestamp as human-readable date/time
-fn format_timestamp(ts: i64) -> String {
+pub(crate) fn format_timestamp(ts: i64) -> String {
     let dt = DateTime::<Utc>::from_timestamp(ts, 0)
         .unwrap_or_else(|| Utc::now());
 
@@ -139,38 +183,6 @@ fn format_tier(tier: &crate::types::EpistemicTier) -> String {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_decrypt_subject_placeholder() {
-        // Test with our fake "ENC:" prefix
-        let encrypted = b"ENC:Test Subject";
-        let decrypted = decrypt_subject(encrypted);
-        assert_eq!(decrypted, "Test Subject");
-
-        // Test with non-encrypted text
-        let plain = b"Plain Subject";
-        let result = decrypt_subject(plain);
-        assert_eq!(result, "Plain Subject");
-    }
-
-    #[tokio::test]
-    async fn test_fetch_body_valid_cid() {
-        let cid = "bafyrei1234567890abcdef";
-        let body = fetch_body(cid).await.unwrap();
-
-        // Should contain CID in message
-        assert!(body.contains(cid));
-        assert!(body.contains("IPFS/DHT"));
-    }
-
-    #[tokio::test]
-    async fn test_fetch_body_invalid_cid() {
-        let cid = "invalid-cid-format";
-        let result = fetch_body(cid).await;
-
-        // Should fail for invalid CID
-        assert!(result.is_err());
-    }
-
     #[test]
     fn test_format_timestamp() {
         // Test with known timestamp
@@ -1,5 +1,7 @@
 use anyhow::{Context, Result};
+use crypto_box::SecretKey;
 use crate::client::MycellixClient;
+use crate::crypto;
 use crate::types::{MailMessage, EpistemicTier};
 
 /// Search messages across inbox and sent folders
@@ -40,8 +42,9 @@ pub async fn handle_search(
     println!("🔎 Searching {} total message(s)...", all_messages.len());
     println!();
 
-    // 3. Apply search filter
-    let mut results = search_messages(&all_messages, &query, in_field);
+    // 3. Apply search filter (subjects are decrypted locally)
+    let secret = client.encryption_key()?;
+    let mut results = search_messages(&all_messages, &query, in_field, &secret);
 
     // 4. Sort by timestamp (newest first)
     results.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
@@ -70,8 +73,8 @@ pub async fn handle_search(
 
     match format {
         "json" => display_json(&results)?,
-        "raw" => display_raw(&results),
-        _ => display_table(&results, &query),
+        "raw" => display_raw(&results, &secret),
+        _ => display_table(&results, &query, &secret),
     }
 
     println!();
@@ -83,7 +86,12 @@ pub async fn handle_search(
 }
 
 /// Search messages based on query and field
-fn search_messages(messages: &[MailMessage], query: &str, field: &str) -> Vec<MailMessage> {
+fn search_messages(
+    messages: &[MailMessage],
+    query: &str,
+    field: &str,
+    secret: &SecretKey,
+) -> Vec<MailMessage> {
     let query_lower = query.to_lowercase();
 
     messages
@@ -91,9 +99,13 @@ fn search_messages(messages: &[MailMessage], query: &str, field: &str) -> Vec<Ma
         .filter(|msg| {
             match field {
                 "from" => msg.from_did.to_lowercase().contains(&query_lower),
-                "to" => msg.to_did.to_lowercase().contains(&query_lower),
+                "to" => msg
+                    .to_dids
+                    .iter()
+                    .chain(&msg.cc_dids)
+                    .any(|did| did.to_lowercase().contains(&query_lower)),
                 "subject" => {
-                    let subject = decrypt_subject(&msg.subject_encrypted);
+                    let subject = crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, secret);
                     subject.to_lowercase().contains(&query_lower)
                 }
                 "body" => {
@@ -103,9 +115,9 @@ fn search_messages(messages: &[MailMessage], query: &str, field: &str) -> Vec<Ma
                 }
                 "all" | _ => {
                     // Search in all fields
-                    let subject = decrypt_subject(&msg.subject_encrypted);
+                    let subject = crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, secret);
                     msg.from_did.to_lowercase().contains(&query_lower)
-                        || msg.to_did.to_lowercase().contains(&query_lower)
+                        || msg.recipients_display().to_lowercase().contains(&query_lower)
                         || subject.to_lowercase().contains(&query_lower)
                         || msg.body_cid.to_lowercase().contains(&query_lower)
                 }
@@ -116,7 +128,7 @@ fn search_messages(messages: &[MailMessage], query: &str, field: &str) -> Vec<Ma
 }
 
 /// Display results in table format
-fn display_table(messages: &[MailMessage], query: &str) {
+fn display_table(messages: &[MailMessage], query: &str, secret: &SecretKey) {
     println!("{:<6} {:<30} {:<30} {:<25} {:<6}",
         "ID", "From", "To", "Subject", "Tier"
     );
@@ -125,8 +137,8 @@ fn display_table(messages: &[MailMessage], query: &str) {
     for (i, msg) in messages.iter().enumerate() {
         let msg_id = format!("#{}", i + 1);
         let from_short = truncate_string(&msg.from_did, 28);
-        let to_short = truncate_string(&msg.to_did, 28);
-        let subject = decrypt_subject(&msg.subject_encrypted);
+        let to_short = truncate_string(&msg.recipients_display(), 28);
+        let subject = crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, secret);
         let subject_short = truncate_string(&subject, 23);
         let tier_short = format_tier_short(&msg.epistemic_tier);
 
@@ -140,6 +152,7 @@ fn display_table(messages: &[MailMessage], query: &str) {
         println!("{:<6} {:<30} {:<30} {:<25} {:<6}",
             msg_id, from_short, to_short, subject_display, tier_short
         );
+        println!("       ID: {}", msg.id);
     }
 
     println!();
@@ -156,14 +169,18 @@ fn display_json(messages: &[MailMessage]) -> Result<()> {
 }
 
 /// Display results in raw format
-fn display_raw(messages: &[MailMessage]) {
+fn display_raw(messages: &[MailMessage], secret: &SecretKey) {
     for (i, msg) in messages.iter().enumerate() {
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
         println!("Result #{}", i + 1);
         println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        println!("ID:        {}", msg.id);
         println!("From:      {}", msg.from_did);
-        println!("To:        {}", msg.to_did);
-        println!("Subject:   {}", decrypt_subject(&msg.subject_encrypted));
+        println!("To:        {}", msg.to_dids.join(", "));
+        if !msg.cc_dids.is_empty() {
+            println!("Cc:        {}", msg.cc_dids.join(", "));
+        }
+        println!("Subject:   {}", crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, secret));
         println!("Body CID:  {}", msg.body_cid);
         println!("Timestamp: {}", msg.timestamp);
         println!("Tier:      {:?}", msg.epistemic_tier);
@@ -207,22 +224,14 @@ fn format_tier_short(tier: &EpistemicTier) -> String {
     .to_string()
 }
 
-/// Decrypt subject (placeholder implementation)
-fn decrypt_subject(encrypted: &[u8]) -> String {
-    if let Ok(s) = String::from_utf8(encrypted.to_vec()) {
-        if s.starts_with("ENC:") {
-            s[4..].to_string()
-        } else {
-            s
-        }
-    } else {
-        "<encrypted>".to_string()
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ed25519_dalek::SigningKey;
+
+    fn test_key() -> SigningKey {
+        SigningKey::from_bytes(&[42u8; 32])
+    }
 
     #[test]
     fn test_truncate_string() {
@@ -252,74 +261,86 @@ mod tests {
         assert_eq!(format_tier_short(&EpistemicTier::Tier4PubliclyReproducible), "T4");
     }
 
-    #[test]
-    fn test_decrypt_subject() {
-        let encrypted = b"ENC:Test Subject";
-        let decrypted = decrypt_subject(encrypted);
-        assert_eq!(decrypted, "Test Subject");
-
-        let plain = b"Plain Subject";
-        let result = decrypt_subject(plain);
-        assert_eq!(result, "Plain Subject");
-    }
-
     #[test]
     fn test_search_messages_by_from() {
         let messages = vec![
             MailMessage {
+                id: String::new(),
                 from_did: "did:mycelix:ABC123".to_string(),
-                to_did: "did:mycelix:XYZ789".to_string(),
+                to_dids: vec!["did:mycelix:XYZ789".to_string()],
+                cc_dids: vec![],
                 subject_encrypted: b"Test".to_vec(),
                 body_cid: "bafyrei123".to_string(),
                 timestamp: 1234567890,
                 thread_id: None,
                 epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+                recipient_keys: vec![],
+                expires_at: None,
+                state: Default::default(),
             },
             MailMessage {
+                id: String::new(),
                 from_did: "did:mycelix:DEF456".to_string(),
-                to_did: "did:mycelix:XYZ789".to_string(),
+                to_dids: vec!["did:mycelix:XYZ789".to_string()],
+                cc_dids: vec![],
                 subject_encrypted: b"Another".to_vec(),
                 body_cid: "bafyrei456".to_string(),
                 timestamp: 1234567891,
                 thread_id: None,
                 epistemic_tier: EpistemicTier::Tier1Testimonial,
+                recipient_keys: vec![],
+                expires_at: None,
+                state: Default::default(),
             },
         ];
 
+        let secret = crypto::x25519_secret(&test_key());
+
         // Should find message from ABC
-        let results = search_messages(&messages, "ABC", "from");
+        let results = search_messages(&messages, "ABC", "from", &secret);
         assert_eq!(results.len(), 1);
         assert!(results[0].from_did.contains("ABC"));
 
         // Should not find anything
-        let results = search_messages(&messages, "ZZZ", "from");
+        let results = search_messages(&messages, "ZZZ", "from", &secret);
         assert_eq!(results.len(), 0);
     }
 
     #[test]
     fn test_search_messages_all_fields() {
+        let key = test_key();
+        let public = crypto::x25519_public(&key.verifying_key());
+        let secret = crypto::x25519_secret(&key);
+        let content_key = crypto::generate_content_key();
+        let recipient_keys = crypto::seal_content_key(&content_key, &[public]).unwrap();
+
         let messages = vec![
             MailMessage {
+                id: String::new(),
                 from_did: "did:mycelix:sender1".to_string(),
-                to_did: "did:mycelix:recipient1".to_string(),
-                subject_encrypted: b"ENC:Important Message".to_vec(),
+                to_dids: vec!["did:mycelix:recipient1".to_string()],
+                cc_dids: vec![],
+                subject_encrypted: crypto::encrypt_subject("Important Message", &content_key).unwrap(),
                 body_cid: "bafyrei123".to_string(),
                 timestamp: 1234567890,
                 thread_id: None,
                 epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+                recipient_keys,
+                expires_at: None,
+                state: Default::default(),
             },
         ];
 
         // Should find by sender
-        let results = search_messages(&messages, "sender1", "all");
+        let results = search_messages(&messages, "sender1", "all", &secret);
         assert_eq!(results.len(), 1);
 
-        // Should find by subject
-        let results = search_messages(&messages, "Important", "all");
+        // Should find by (decrypted) subject
+        let results = search_messages(&messages, "Important", "all", &secret);
         assert_eq!(results.len(), 1);
 
         // Should not find
-        let results = search_messages(&messages, "nonexistent", "all");
+        let results = search_messages(&messages, "nonexistent", "all", &secret);
         assert_eq!(results.len(), 0);
     }
 }
@@ -1,45 +1,82 @@
 use anyhow::{Context, Result, bail};
+use std::fs;
 use std::io::{self, Read};
+use std::path::Path;
 
+use crate::attachments::{self, AttachmentRef};
 use crate::client::MycellixClient;
-use crate::types::EpistemicTier;
+use crate::contacts::ContactBook;
+use crate::crypto::{self, ContentKey};
+use crate::storage::{self, MessageContent};
+use crate::types::{EpistemicTier, Recipients};
+use crate::wire;
+
+use super::read::format_timestamp;
 
 /// Send an email message
+#[allow(clippy::too_many_arguments)]
 pub async fn handle_send(
     client: &MycellixClient,
-    to: String,
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
     subject: String,
     body: Option<String>,
     attach: Option<Vec<String>>,
     reply_to: Option<String>,
     tier: u8,
+    expires_in: Option<String>,
 ) -> Result<()> {
     println!("📧 Composing message...");
     println!();
 
-    // 1. Validate epistemic tier
+    // 1. Validate epistemic tier and expiry
     let epistemic_tier = EpistemicTier::from_u8(tier)
         .with_context(|| format!("Invalid epistemic tier: {}. Must be 0-4", tier))?;
+    let expires_at = match expires_in.as_deref() {
+        Some(duration) => Some(chrono::Utc::now().timestamp() + parse_duration(duration)?),
+        None => None,
+    };
 
     println!("   Tier: {}", epistemic_tier);
-
-    // 2. Validate recipient (must be a valid DID)
-    if !to.starts_with("did:") {
-        bail!(
-            "Invalid recipient format: '{}'\n\
-             Recipient must be a DID (e.g., did:mycelix:ABC123...)\n\
-             Use 'mycelix-mail did resolve <email>' to find a user's DID",
-            to
-        );
+    if let Some(expiry) = expires_at {
+        println!("   Expires: {}", format_timestamp(expiry));
     }
 
-    // Check DID format (should be did:mycelix:base58)
-    if !to.starts_with("did:mycelix:") {
-        println!("⚠️  Warning: Recipient DID uses non-standard method: {}", to);
-        println!("   Expected format: did:mycelix:<base58>");
+    // 2. Resolve contact names and aliases, then validate recipients
+    //    (contacts are only required when a recipient isn't a DID)
+    let needs_contacts = to.iter().chain(&cc).chain(&bcc).any(|r| !r.trim().starts_with("did:"));
+    let book = match client.contact_book().await {
+        Ok(book) => book,
+        Err(e) if needs_contacts => {
+            return Err(e).context("Failed to fetch contacts to resolve recipients")
+        }
+        Err(_) => ContactBook::default(),
+    };
+    let resolve = |list: Vec<String>| -> Result<Vec<String>> {
+        list.iter().map(|r| book.resolve(r)).collect()
+    };
+    let recipients = build_recipients(
+        route_recipients(client, resolve(to)?).await?,
+        route_recipients(client, resolve(cc)?).await?,
+        route_recipients(client, resolve(bcc)?).await?,
+    )?;
+
+    for did in recipients.all() {
+        // Check DID format (should be did:mycelix:base58)
+        if !did.starts_with("did:mycelix:") && !did.starts_with("did:key:") {
+            println!("⚠️  Warning: Recipient DID uses non-standard method: {}", did);
+            println!("   Expected format: did:mycelix:<base58>");
+        }
     }
 
-    println!("   To: {}", to);
+    println!("   To: {}", book.display_list(&recipients.to));
+    if !recipients.cc.is_empty() {
+        println!("   Cc: {}", book.display_list(&recipients.cc));
+    }
+    if !recipients.bcc.is_empty() {
+        println!("   Bcc: {}", book.display_list(&recipients.bcc));
+    }
 
     // 3. Validate and get body text
     let body_text = get_body_text(body).await?;
@@ -51,37 +88,125 @@ pub async fn handle_send(
         println!("   Body: {} ({} chars)", body_text, body_text.len());
     }
 
-    // 4. Handle attachments (not yet implemented)
-    if let Some(attachments) = &attach {
-        if !attachments.is_empty() {
-            println!("⚠️  Warning: Attachments not yet implemented");
-            println!("   Ignoring {} attachment(s)", attachments.len());
-            // TODO: Implement attachment upload to IPFS/Holochain
+    // 4. Validate attachments before anything is uploaded
+    let attach = attach.unwrap_or_default();
+    for path in &attach {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Failed to read attachment: {}", path))?;
+        if !metadata.is_file() {
+            bail!("Attachment is not a file: {}", path);
         }
+        if metadata.len() > attachments::MAX_ATTACHMENT_SIZE {
+            bail!(
+                "Attachment {} is too large ({}, limit {})",
+                path,
+                attachments::format_size(metadata.len()),
+                attachments::format_size(attachments::MAX_ATTACHMENT_SIZE)
+            );
+        }
+        println!(
+            "   Attach: {} ({})",
+            path,
+            attachments::format_size(metadata.len())
+        );
     }
 
-    // 5. Handle reply-to (for threading)
-    if let Some(parent_id) = &reply_to {
-        println!("   Reply to: {}", parent_id);
-    }
+    // 5. Handle reply-to (for threading): the parent's message ID becomes the thread ID
+    let reply_to = match reply_to {
+        Some(parent_id) => {
+            let parent_id = parent_id.trim().to_string();
+            wire::decode_hash(&parent_id)?;
+            client
+                .get_message(&parent_id)
+                .await
+                .context("Cannot reply to a message that is not on the DHT")?;
+            println!("   Reply to: {}", parent_id);
+            Some(parent_id)
+        }
+        None => None,
+    };
 
     println!();
     println!("📝 Subject: {}", subject);
 
-    // 6. Encrypt subject (placeholder for now)
-    let encrypted_subject = encrypt_subject(&subject);
-    println!("🔒 Subject encrypted: {} bytes", encrypted_subject.len());
+    // 6. Seal a fresh content key for every named recipient (and ourselves, so
+    //    the sent folder stays readable), then encrypt the subject under it.
+    //    BCC recipients get no slot here; each is sent a separate copy below.
+    let my_key = client
+        .my_encryption_public_key()
+        .context("Cannot encrypt mail without your key. Run 'mycelix-mail init' first.")?;
+    let mut sealing_keys = vec![my_key.clone()];
+    let mut bcc_keys = Vec::with_capacity(recipients.bcc.len());
+    for did in recipients.all() {
+        let key = client
+            .recipient_encryption_key(did)
+            .await
+            .with_context(|| format!("Failed to look up encryption key for {}", did))?;
+        if recipients.bcc.contains(did) {
+            bcc_keys.push(key);
+        } else {
+            sealing_keys.push(key);
+        }
+    }
+
+    let content_key = crypto::generate_content_key();
+    let recipient_keys = crypto::seal_content_key(&content_key, &sealing_keys)?;
+    let encrypted_subject = crypto::encrypt_subject(&subject, &content_key)?;
+    println!(
+        "🔒 Subject encrypted, key sealed for {} recipient(s)",
+        recipients.len()
+    );
+
+    // 7. Encrypt and store attachments, then the body that references them
+    let mut attachment_refs = Vec::with_capacity(attach.len());
+    for path in &attach {
+        let reference = upload_attachment(client, Path::new(path)).await?;
+        println!(
+            "📎 Attachment uploaded: {} ({} chunk(s))",
+            reference.filename,
+            reference.chunks.len()
+        );
+        attachment_refs.push(reference);
+    }
 
-    // 7. Upload body to DHT/IPFS (stub for now)
-    let body_cid = upload_body(&body_text).await?;
+    let content = MessageContent {
+        body: body_text,
+        attachments: attachment_refs,
+    };
+    let body_cid = upload_body(client, &content, &content_key).await?;
     println!("📤 Body uploaded: {}", body_cid);
 
-    // 8. Send message via Holochain
+    // 8. Re-encrypt subject and body under a fresh key for each BCC recipient,
+    //    so their copy shares no ciphertext or key slots with the message
+    let mut bcc_copies = Vec::with_capacity(recipients.bcc.len());
+    for (did, key) in recipients.bcc.iter().zip(bcc_keys) {
+        let copy_key = crypto::generate_content_key();
+        bcc_copies.push(wire::BccCopy {
+            did: did.clone(),
+            subject_encrypted: crypto::encrypt_subject(&subject, &copy_key)?,
+            body_cid: upload_body(client, &content, &copy_key).await?,
+            recipient_keys: crypto::seal_content_key(&copy_key, &[my_key.clone(), key])?,
+        });
+    }
+    if !bcc_copies.is_empty() {
+        println!("🙈 {} blind copy(ies) encrypted separately", bcc_copies.len());
+    }
+
+    // 9. Send message via Holochain
     println!();
     println!("📡 Sending message...");
 
     match client
-        .send_message(to.clone(), encrypted_subject, body_cid, reply_to, epistemic_tier)
+        .send_message(
+            recipients.clone(),
+            encrypted_subject,
+            body_cid,
+            recipient_keys,
+            bcc_copies,
+            reply_to,
+            epistemic_tier,
+            expires_at,
+        )
         .await
     {
         Ok(message_id) => {
@@ -90,12 +215,24 @@ pub async fn handle_send(
             println!();
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
             println!("Message ID: {}", message_id);
-            println!("To: {}", to);
+            println!("To: {}", book.display_list(&recipients.to));
+            if !recipients.cc.is_empty() {
+                println!("Cc: {}", book.display_list(&recipients.cc));
+            }
+            if !recipients.bcc.is_empty() {
+                println!("Bcc: {}", book.display_list(&recipients.bcc));
+            }
             println!("Subject: {}", subject);
             println!("Tier: {}", epistemic_tier);
+            if !content.attachments.is_empty() {
+                println!("Attachments: {}", content.attachments.len());
+            }
+            if let Some(expiry) = expires_at {
+                println!("Expires: {}", format_timestamp(expiry));
+            }
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
             println!();
-            println!("The recipient will receive your message shortly.");
+            println!("Recipients will receive your message shortly.");
         }
         Err(e) => {
             println!();
@@ -103,7 +240,7 @@ pub async fn handle_send(
             println!();
             println!("This may be because:");
             println!("  • Holochain conductor is not running");
-            println!("  • The recipient's DID doesn't exist");
+            println!("  • A recipient's DID doesn't exist");
             println!("  • Network connectivity issues");
             println!();
             println!("Try again later or check your configuration.");
@@ -114,6 +251,84 @@ pub async fn handle_send(
     Ok(())
 }
 
+/// Swap each recipient for the DID they receive mail under on the DHT
+///
+/// Only `did:web` recipients change; their document names the identity.
+async fn route_recipients(client: &MycellixClient, dids: Vec<String>) -> Result<Vec<String>> {
+    let mut routed = Vec::with_capacity(dids.len());
+    for did in dids {
+        let delivery = client
+            .delivery_did(&did)
+            .await
+            .with_context(|| format!("Cannot deliver to {}", did))?;
+        if delivery != did {
+            println!("   ↪ {} receives mail as {}", did, delivery);
+        }
+        routed.push(delivery);
+    }
+    Ok(routed)
+}
+
+/// Parse a lifetime like `30m`, `12h` or `7d` into seconds
+fn parse_duration(input: &str) -> Result<i64> {
+    let input = input.trim();
+    // Split on a char boundary so a non-ASCII unit is an error, not a panic
+    let split = input.char_indices().last().map_or(0, |(index, _)| index);
+    let (amount, unit) = input.split_at(split);
+    let multiplier = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => bail!("Invalid duration '{}'. Use a number followed by m, h, d or w (e.g. 7d)", input),
+    };
+    let amount: i64 = amount
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .with_context(|| format!("Invalid duration '{}'. The amount must be a positive number", input))?;
+
+    amount
+        .checked_mul(multiplier)
+        .with_context(|| format!("Duration '{}' is too long", input))
+}
+
+/// Validate recipient DIDs and drop duplicates
+///
+/// A DID listed more than once keeps its most visible role (to, then cc,
+/// then bcc) so nobody is both named and blind-copied.
+fn build_recipients(to: Vec<String>, cc: Vec<String>, bcc: Vec<String>) -> Result<Recipients> {
+    if to.is_empty() {
+        bail!("At least one recipient is required. Use --to <did|contact> (repeatable)");
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut take = |list: Vec<String>| -> Result<Vec<String>> {
+        let mut out = Vec::new();
+        for did in list {
+            let did = did.trim().to_string();
+            if !did.starts_with("did:") {
+                bail!(
+                    "Invalid recipient format: '{}'\n\
+                     Recipient must be a DID (e.g., did:mycelix:ABC123...) or a saved contact\n\
+                     Use 'mycelix-mail contact add <name> <did>' to save a contact",
+                    did
+                );
+            }
+            if seen.insert(did.clone()) {
+                out.push(did);
+            }
+        }
+        Ok(out)
+    };
+
+    Ok(Recipients {
+        to: take(to)?,
+        cc: take(cc)?,
+        bcc: take(bcc)?,
+    })
+}
+
 /// Get body text from argument or stdin
 async fn get_body_text(body: Option<String>) -> Result<String> {
     match body {
@@ -147,87 +362,104 @@ async fn get_body_text(body: Option<String>) -> Result<String> {
     }
 }
 
-/// Encrypt subject (placeholder implementation)
+/// Encrypt a file's chunks and store each one on the DHT
 ///
-/// TODO: Implement real encryption using recipient's public key
-/// - Fetch recipient's public key from DID registry
-/// - Use NaCl sealed box or similar for encryption
-/// - Return encrypted bytes
-fn encrypt_subject(subject: &str) -> Vec<u8> {
-    // Placeholder: Just convert to bytes
-    // In real implementation:
-    // 1. Fetch recipient's public key from DID or Holochain
-    // 2. Encrypt subject with NaCl/TweetNaCl sealed box
-    // 3. Return encrypted bytes
-
-    // For now, just prefix with "ENC:" to indicate it should be encrypted
-    format!("ENC:{}", subject).into_bytes()
+/// The returned reference (including the chunk key) is only ever written
+/// inside the sealed message body.
+async fn upload_attachment(client: &MycellixClient, path: &Path) -> Result<AttachmentRef> {
+    let data = fs::read(path)
+        .with_context(|| format!("Failed to read attachment: {}", path.display()))?;
+    let filename = path.to_string_lossy();
+    let (reference, chunks) = attachments::encrypt_attachment(&filename, &data)?;
+
+    for chunk in chunks {
+        client
+            .store_blob(chunk.cid, chunk.ciphertext)
+            .await
+            .with_context(|| format!("Failed to store attachment {}", reference.filename))?;
+    }
+
+    Ok(reference)
 }
 
-/// Upload body to DHT/IPFS (placeholder implementation)
+/// Encrypt the body under the content key and store it on the DHT
 ///
-/// TODO: Implement real body upload
-/// - Upload to IPFS or Holochain DHT
-/// - Return content identifier (CID)
-async fn upload_body(body: &str) -> Result<String> {
-    // Placeholder: Just create a fake CID based on body hash
-    // In real implementation:
-    // 1. Upload body to IPFS via ipfs-api or Holochain DHT
-    // 2. Get content identifier (CID)
-    // 3. Return CID
-
-    use blake2::{Blake2b512, Digest};
-
-    let mut hasher = Blake2b512::new();
-    hasher.update(body.as_bytes());
-    let hash = hasher.finalize();
-
-    // Create a fake CID (just hex encoding of first 16 bytes)
-    let cid = hex::encode(&hash[..16]);
-
-    Ok(format!("bafyrei{}", cid))
+/// Returns the CIDv1 of the ciphertext, which goes into `body_cid`
+async fn upload_body(
+    client: &MycellixClient,
+    content: &MessageContent,
+    content_key: &ContentKey,
+) -> Result<String> {
+    let (cid, ciphertext) = storage::encrypt_body(content, content_key)?;
+
+    client
+        .store_blob(cid.clone(), ciphertext)
+        .await
+        .context("Failed to store message body on the DHT")?;
+
+    Ok(cid)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_encrypt_subject() {
-        let subject = "Hello World";
-        let encrypted = encrypt_subject(subject);
-
-        // Should produce bytes
-        assert!(!encrypted.is_empty());
-
-        // For now, should start with "ENC:"
-        let decrypted = String::from_utf8(encrypted).unwrap();
-        assert!(decrypted.starts_with("ENC:"));
-        assert!(decrypted.contains("Hello World"));
+    #[tokio::test]
+    async fn test_get_body_text() {
+        let body = get_body_text(Some("Test message body".to_string())).await.unwrap();
+        assert_eq!(body, "Test message body");
     }
 
     #[tokio::test]
-    async fn test_upload_body() {
-        let body = "Test message body";
-        let cid = upload_body(body).await.unwrap();
+    async fn test_get_body_text_rejects_empty() {
+        assert!(get_body_text(Some("   ".to_string())).await.is_err());
+        assert!(get_body_text(None).await.is_err());
+    }
 
-        // Should produce a CID-like string
-        assert!(cid.starts_with("bafyrei"));
-        assert!(cid.len() > 10);
+    fn dids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|d| d.to_string()).collect()
+    }
 
-        // Same body should produce same CID (deterministic)
-        let cid2 = upload_body(body).await.unwrap();
-        assert_eq!(cid, cid2);
+    #[test]
+    fn test_build_recipients_dedupes_across_roles() {
+        let recipients = build_recipients(
+            dids(&["did:mycelix:a", "did:mycelix:b", "did:mycelix:a"]),
+            dids(&["did:mycelix:b", "did:mycelix:c"]),
+            dids(&["did:mycelix:c", "did:mycelix:d"]),
+        )
+        .unwrap();
+
+        assert_eq!(recipients.to, dids(&["did:mycelix:a", "did:mycelix:b"]));
+        assert_eq!(recipients.cc, dids(&["did:mycelix:c"]));
+        assert_eq!(recipients.bcc, dids(&["did:mycelix:d"]));
+        assert_eq!(recipients.len(), 4);
     }
 
-    #[tokio::test]
-    async fn test_different_bodies_different_cids() {
-        let body1 = "Message 1";
-        let body2 = "Message 2";
+    #[test]
+    fn test_build_recipients_validation() {
+        assert!(build_recipients(vec![], dids(&["did:mycelix:a"]), vec![]).is_err());
+        assert!(build_recipients(dids(&["alice@example.com"]), vec![], vec![]).is_err());
+        assert!(build_recipients(dids(&["did:mycelix:a"]), vec![], dids(&["bob"])).is_err());
+    }
 
-        let cid1 = upload_body(body1).await.unwrap();
-        let cid2 = upload_body(body2).await.unwrap();
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30m").unwrap(), 1800);
+        assert_eq!(parse_duration("12h").unwrap(), 43200);
+        assert_eq!(parse_duration(" 7d ").unwrap(), 604800);
+        assert_eq!(parse_duration("1w").unwrap(), 604800);
+        assert!(parse_duration("7").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("0h").is_err());
+        assert!(parse_duration("-1d").is_err());
+        assert!(parse_duration("").is_err());
+    }
 
-        assert_ne!(cid1, cid2);
+    #[test]
+    fn test_parse_duration_non_ascii() {
+        assert!(parse_duration("7é").is_err());
+        assert!(parse_duration("é").is_err());
+        assert!(parse_duration("٧d").is_err());
+        assert!(parse_duration("7日").is_err());
     }
 }
@@ -44,13 +44,11 @@ pub async fn handle_status(client: &MycellixClient, detailed: bool) -> Result<()
 
     if health_status {
         println!("   Status: ✅ All systems operational");
-        println!("   Conductor: Connected");
     } else {
-        println!("   Status: ⚠️  Backend not connected");
-        println!("   Conductor: Not connected (stub mode)");
+        println!("   Status: ⚠️  Some services unavailable");
         println!();
-        println!("   Note: Currently running with stub backend.");
-        println!("   Start Holochain conductor to enable full functionality.");
+        println!("   Note: Sending and reading mail requires a running Holochain");
+        println!("   conductor with the mycelix_mail app installed.");
     }
     println!();
 
@@ -86,6 +84,8 @@ pub async fn handle_status(client: &MycellixClient, detailed: bool) -> Result<()
         println!("   Auto Sync:       {}", if config.preferences.auto_sync { "Enabled" } else { "Disabled" });
         println!("   Cache TTL:       {} seconds", config.preferences.cache_ttl);
         println!("   Display Format:  {}", config.preferences.display_format);
+        println!("   Min Trust:       {:.2}", config.preferences.min_trust);
+        println!("   Report Blocked:  {}", if config.preferences.auto_report_blocked { "yes" } else { "no" });
         println!("   Timeout:         {} seconds", config.conductor.timeout);
 
         if let Some(ref email) = config.identity.email {
@@ -24,7 +24,7 @@ pub async fn handle_sync(client: &MycellixClient, force: bool) -> Result<()> {
     println!("📬 Syncing messages from DHT...");
     match sync_messages(client).await {
         Ok(count) => {
-            println!("   ✅ Synced {} new message(s)", count);
+            println!("   ✅ Indexed {} message(s) for paged listing", count);
             sync_summary.messages_synced = count;
         }
         Err(e) => {
@@ -34,7 +34,21 @@ pub async fn handle_sync(client: &MycellixClient, force: bool) -> Result<()> {
     }
     println!();
 
-    // 2. Sync trust scores from MATL
+    // 2. Clean up expired mail
+    println!("⏳ Purging expired messages...");
+    match client.purge_expired().await {
+        Ok(count) => {
+            println!("   ✅ Removed {} expired message(s)", count);
+            sync_summary.messages_purged = count as usize;
+        }
+        Err(e) => {
+            println!("   ⚠️  Failed to purge expired messages: {}", e);
+            sync_summary.messages_failed = true;
+        }
+    }
+    println!();
+
+    // 3. Sync trust scores from MATL
     println!("🔐 Syncing trust scores from MATL...");
     match sync_trust_scores(client).await {
         Ok(count) => {
@@ -48,7 +62,7 @@ pub async fn handle_sync(client: &MycellixClient, force: bool) -> Result<()> {
     }
     println!();
 
-    // 3. Update mailbox statistics
+    // 4. Update mailbox statistics
     println!("📊 Updating mailbox statistics...");
     match update_stats(client).await {
         Ok(_) => {
@@ -67,7 +81,7 @@ pub async fn handle_sync(client: &MycellixClient, force: bool) -> Result<()> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!();
 
-    println!("📬 Messages:      {} new", sync_summary.messages_synced);
+    println!("📬 Messages:      {} indexed, {} expired removed", sync_summary.messages_synced, sync_summary.messages_purged);
     println!("🔐 Trust Scores:  {} updated", sync_summary.trust_scores_synced);
     println!("📊 Statistics:    {}", if sync_summary.stats_updated { "Updated" } else { "Not updated" });
 
@@ -85,18 +99,16 @@ pub async fn handle_sync(client: &MycellixClient, force: bool) -> Result<()> {
 }
 
 /// Sync messages from DHT
+///
+/// Adds mail that predates the mailbox time index to it, so paged inbox
+/// queries see every message. Returns the number of messages indexed.
 async fn sync_messages(client: &MycellixClient) -> Result<usize> {
-    // TODO: Implement actual DHT sync
-    // In real implementation:
-    // 1. Connect to Holochain DHT
-    // 2. Query for new messages since last sync
-    // 3. Download and decrypt new messages
-    // 4. Update local cache
-    // 5. Return count of new messages
-
-    // For now, return 0 (no new messages in stub mode)
-    let _ = client; // Suppress unused warning
-    Ok(0)
+    let indexed = client
+        .index_mailbox()
+        .await
+        .context("Failed to index mailbox")?;
+
+    Ok(indexed as usize)
 }
 
 /// Sync trust scores from MATL
@@ -127,6 +139,7 @@ async fn update_stats(_client: &MycellixClient) -> Result<()> {
 #[derive(Default)]
 struct SyncSummary {
     messages_synced: usize,
+    messages_purged: usize,
     messages_failed: bool,
     trust_scores_synced: usize,
     trust_scores_failed: bool,
@@ -157,6 +170,7 @@ mod tests {
     fn test_sync_summary_success() {
         let summary = SyncSummary {
             messages_synced: 5,
+            messages_purged: 0,
             messages_failed: false,
             trust_scores_synced: 3,
             trust_scores_failed: false,
@@ -169,6 +183,7 @@ mod tests {
     fn test_sync_summary_partial_failure() {
         let summary = SyncSummary {
             messages_synced: 5,
+            messages_purged: 0,
             messages_failed: false,
             trust_scores_synced: 0,
             trust_scores_failed: true,
@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+
+use crate::client::MycellixClient;
+use crate::crypto;
+use crate::types::MailMessage;
+
+/// Show the whole conversation a message belongs to
+pub async fn handle_thread(client: &MycellixClient, message_id: String) -> Result<()> {
+    println!("🧵 Fetching conversation...");
+    println!();
+
+    let message_id = message_id.trim().to_string();
+    let messages = client
+        .get_thread(&message_id)
+        .await
+        .context("Failed to fetch conversation")?;
+
+    if messages.is_empty() {
+        println!("No messages found for {}.", message_id);
+        println!("The message may not have replicated yet; try again shortly.");
+        return Ok(());
+    }
+
+    let secret = client.encryption_key()?;
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("Conversation: {} message(s)", messages.len());
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+
+    for (depth, msg) in conversation_order(&messages) {
+        let indent = "    ".repeat(depth);
+        let marker = if depth == 0 { "📨" } else { "↳" };
+        let current = if msg.id == message_id { "  ◀" } else { "" };
+        let subject = crypto::decrypt_subject(&msg.subject_encrypted, &msg.recipient_keys, &secret);
+
+        println!("{}{} {}{}", indent, marker, subject, current);
+        println!("{}   From: {}", indent, msg.from_did);
+        println!("{}   Date: {}", indent, format_timestamp(msg.timestamp));
+        println!("{}   ID:   {}", indent, msg.id);
+        println!();
+    }
+
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("💡 Use 'mycelix-mail read <id>' to read a message");
+    println!("💡 Use 'mycelix-mail send <did> --reply-to <id>' to reply");
+
+    Ok(())
+}
+
+/// Order a conversation depth-first, oldest first at each level
+///
+/// Returns `(depth, message)` pairs. Replies whose parent is not in the set
+/// (e.g. deleted or not yet replicated) are shown at the top level rather
+/// than dropped.
+fn conversation_order(messages: &[MailMessage]) -> Vec<(usize, &MailMessage)> {
+    let ids: HashSet<&str> = messages.iter().map(|m| m.id.as_str()).collect();
+
+    let mut sorted: Vec<&MailMessage> = messages.iter().collect();
+    sorted.sort_by_key(|m| m.timestamp);
+
+    let mut children: HashMap<&str, Vec<&MailMessage>> = HashMap::new();
+    let mut roots = Vec::new();
+    for msg in sorted {
+        match msg.thread_id.as_deref() {
+            Some(parent) if ids.contains(parent) && parent != msg.id => {
+                children.entry(parent).or_default().push(msg)
+            }
+            _ => roots.push(msg),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(messages.len());
+    let mut stack: Vec<(usize, &MailMessage)> = roots.into_iter().rev().map(|m| (0, m)).collect();
+    let mut seen = HashSet::new();
+    while let Some((depth, msg)) = stack.pop() {
+        if !seen.insert(msg.id.as_str()) {
+            continue;
+        }
+        ordered.push((depth, msg));
+        if let Some(replies) = children.get(msg.id.as_str()) {
+            stack.extend(replies.iter().rev().map(|m| (depth + 1, *m)));
+        }
+    }
+
+    ordered
+}
+
+/// Format timestamp as human-readable date/time
+fn format_timestamp(ts: i64) -> String {
+    DateTime::<Utc>::from_timestamp(ts, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EpistemicTier;
+
+    fn message(id: &str, parent: Option<&str>, timestamp: i64) -> MailMessage {
+        MailMessage {
+            id: id.to_string(),
+            from_did: "did:mycelix:alice".to_string(),
+            to_dids: vec!["did:mycelix:bob".to_string()],
+            cc_dids: vec![],
+            subject_encrypted: vec![1],
+            body_cid: "bafkreitest".to_string(),
+            timestamp,
+            thread_id: parent.map(str::to_string),
+            epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+            recipient_keys: vec![],
+            expires_at: None,
+            state: Default::default(),
+        }
+    }
+
+    fn ids<'a>(ordered: &[(usize, &'a MailMessage)]) -> Vec<(usize, &'a str)> {
+        ordered.iter().map(|(d, m)| (*d, m.id.as_str())).collect()
+    }
+
+    #[test]
+    fn test_conversation_order_nests_replies() {
+        let messages = vec![
+            message("reply-b", Some("root"), 300),
+            message("root", None, 100),
+            message("reply-a", Some("root"), 200),
+            message("reply-a-1", Some("reply-a"), 400),
+        ];
+
+        let ordered = conversation_order(&messages);
+        assert_eq!(
+            ids(&ordered),
+            vec![(0, "root"), (1, "reply-a"), (2, "reply-a-1"), (1, "reply-b")]
+        );
+    }
+
+    #[test]
+    fn test_conversation_order_keeps_orphans() {
+        let messages = vec![
+            message("root", None, 100),
+            message("orphan", Some("deleted-parent"), 200),
+        ];
+
+        let ordered = conversation_order(&messages);
+        assert_eq!(ids(&ordered), vec![(0, "root"), (0, "orphan")]);
+    }
+}
@@ -1,7 +1,8 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use crate::client::MycellixClient;
+use crate::types::{RuleAction, SenderRule, SourceTrust};
 
-/// Get trust score for a DID
+/// Get trust score for a DID, with the sources that produced it
 pub async fn handle_get(client: &MycellixClient, did: String) -> Result<()> {
     println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
     println!("                   TRUST SCORE QUERY");
@@ -10,42 +11,49 @@ pub async fn handle_get(client: &MycellixClient, did: String) -> Result<()> {
     println!("🔍 DID:  {}", did);
     println!();
 
-    // Get trust score from local store
-    let trust_score = client
-        .get_trust_score(did.clone())
+    // Aggregate every source on the DHT
+    let breakdown = client
+        .get_trust_breakdown(&did)
         .await
         .context("Failed to get trust score")?;
 
-    match trust_score {
-        Some(score) => {
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("                    TRUST SCORE");
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!();
-            println!("📊 Score:        {:.2}", score.score);
-            println!("📅 Last Updated: {}", format_timestamp(score.last_updated));
-            println!("🏷️  Source:       {}", score.source);
-            println!();
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("                    TRUST SCORE");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+    println!("📊 Score:        {:.2}", breakdown.score);
+    println!();
 
-            // Visual trust indicator
-            let trust_bar = format_trust_bar(score.score);
-            println!("Trust Level:  {}", trust_bar);
-            println!();
+    // Visual trust indicator
+    let trust_bar = format_trust_bar(breakdown.score);
+    println!("Trust Level:  {}", trust_bar);
+    println!();
 
-            // Interpretation
-            let interpretation = interpret_trust_score(score.score);
-            println!("💡 {}", interpretation);
-        }
-        None => {
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("No trust score found for this DID.");
-            println!();
-            println!("💡 Tips:");
-            println!("   • Use 'mycelix-mail trust sync {}' to fetch from MATL", did);
-            println!("   • Use 'mycelix-mail trust set {} <score>' to set manually", did);
-        }
+    // Interpretation
+    let interpretation = interpret_trust_score(breakdown.score);
+    println!("💡 {}", interpretation);
+    println!();
+
+    // Why: each source's score and how much it counted
+    println!("Breakdown:");
+    println!("{:<10} {:<7} {:<7} {:<11} {:<7} {}",
+        "Source", "Score", "Weight", "Confidence", "Share", "Basis"
+    );
+    println!("{}", "─".repeat(70));
+    for source in &breakdown.sources {
+        println!("{}", format_source_row(source));
+    }
+    println!();
+
+    if breakdown.sources.iter().all(|s| s.share == 0.0) {
+        println!("ℹ️  No history yet: this is the starting score for unknown senders.");
+        println!();
     }
 
+    println!("💡 Tips:");
+    println!("   • Use 'mycelix-mail trust sync {}' to fetch from MATL", did);
+    println!("   • Use 'mycelix-mail trust set {} <score>' to set manually", did);
+
     Ok(())
 }
 
@@ -233,8 +241,140 @@ pub async fn handle_sync(client: &MycellixClient, did: Option<String>) -> Result
     Ok(())
 }
 
+/// Always accept mail from senders matching a DID or DID prefix
+pub async fn handle_allow(client: &MycellixClient, pattern: String) -> Result<()> {
+    let pattern = normalize_pattern(&pattern)?;
+
+    let rule = client
+        .set_sender_rule(pattern, RuleAction::Allow)
+        .await
+        .context("Failed to save allow rule")?;
+
+    println!("✅ Allowing mail from {}", rule.pattern);
+    println!("   These senders skip the trust check and are never quarantined.");
+
+    Ok(())
+}
+
+/// Always quarantine mail from senders matching a DID or DID prefix
+pub async fn handle_block(client: &MycellixClient, pattern: String, report: bool) -> Result<()> {
+    let pattern = normalize_pattern(&pattern)?;
+
+    let rule = client
+        .set_sender_rule(pattern, RuleAction::Block)
+        .await
+        .context("Failed to save block rule")?;
+
+    println!("⛔ Blocking mail from {}", rule.pattern);
+    println!("   Their messages are quarantined whatever their trust score.");
+
+    if report {
+        let filed = client
+            .report_blocked_senders()
+            .await
+            .context("Failed to report blocked senders")?;
+        println!("🚩 Filed {} spam report(s) for mail from blocked senders", filed);
+    } else {
+        println!();
+        println!("💡 Use --report to also report their mail as spam");
+    }
+
+    Ok(())
+}
+
+/// Remove the allow or block rule for a pattern
+pub async fn handle_unblock(client: &MycellixClient, pattern: String) -> Result<()> {
+    let pattern = normalize_pattern(&pattern)?;
+
+    client
+        .remove_sender_rule(pattern.clone())
+        .await
+        .context("Failed to remove sender rule")?;
+
+    println!("✅ Removed rule for {}", pattern);
+    println!("   Their mail is filtered by trust score again.");
+
+    Ok(())
+}
+
+/// List allow/block rules
+pub async fn handle_rules(client: &MycellixClient) -> Result<()> {
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!("                  SENDER RULES");
+    println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    println!();
+
+    let rules = client
+        .list_sender_rules()
+        .await
+        .context("Failed to fetch sender rules")?;
+
+    if rules.is_empty() {
+        println!("No allow or block rules.");
+        println!();
+        println!("💡 Use 'mycelix-mail trust allow <did>' or 'trust block <did>'");
+        println!("   (end a DID with * to match every DID with that prefix)");
+        return Ok(());
+    }
+
+    println!("{:<8} {:<50} {}", "Action", "Pattern", "Added");
+    println!("{}", "─".repeat(70));
+    for rule in &rules {
+        println!("{}", format_rule_row(rule));
+    }
+    println!();
+    println!("💡 The most specific matching rule wins; exact DIDs beat wildcards.");
+
+    Ok(())
+}
+
 // ========== Helper Functions ==========
 
+/// Check a rule pattern: a DID, optionally ending in a single `*` wildcard
+fn normalize_pattern(pattern: &str) -> Result<String> {
+    let pattern = pattern.trim();
+    if !pattern.starts_with("did:") {
+        bail!("Invalid pattern: '{}'
+Expected a DID or DID prefix, e.g. did:mycelix:ABC*", pattern);
+    }
+    if pattern.strip_suffix('*').unwrap_or(pattern).contains('*') {
+        bail!("Invalid pattern: '{}'
+A wildcard is only allowed at the end", pattern);
+    }
+    Ok(pattern.to_string())
+}
+
+/// One row of the `trust rules` table
+fn format_rule_row(rule: &SenderRule) -> String {
+    let action = match rule.action {
+        RuleAction::Allow => "allow",
+        RuleAction::Block => "block",
+    };
+    format!("{:<8} {:<50} {}",
+        action,
+        truncate_string(&rule.pattern, 50),
+        format_relative_time(rule.created_at)
+    )
+}
+
+/// One row of the `trust get` breakdown table
+fn format_source_row(source: &SourceTrust) -> String {
+    let basis = match (&source.detail, source.last_updated) {
+        (Some(detail), _) => detail.clone(),
+        (None, Some(ts)) => format!("updated {}", format_timestamp(ts)),
+        (None, None) => String::new(),
+    };
+
+    format!("{:<10} {:<7} {:<7} {:<11} {:<7} {}",
+        truncate_string(&source.source, 10),
+        format!("{:.2}", source.score),
+        format!("{:.1}", source.weight),
+        format!("{:.0}%", source.confidence * 100.0),
+        format!("{:.0}%", source.share * 100.0),
+        basis
+    )
+}
+
 /// Format timestamp as human-readable string
 fn format_timestamp(ts: i64) -> String {
     use chrono::{DateTime, Utc};
@@ -329,6 +469,45 @@ mod tests {
         assert!(interpret_trust_score(0.1).contains("Very low"));
     }
 
+    #[test]
+    fn test_normalize_pattern() {
+        assert_eq!(normalize_pattern(" did:mycelix:ABC ").unwrap(), "did:mycelix:ABC");
+        assert_eq!(normalize_pattern("did:mycelix:acme*").unwrap(), "did:mycelix:acme*");
+        assert!(normalize_pattern("alice").is_err());
+        assert!(normalize_pattern("did:*:acme").is_err());
+        assert!(normalize_pattern("did:mycelix:a**").is_err());
+    }
+
+    #[test]
+    fn test_format_source_row() {
+        let local = SourceTrust {
+            source: "local".to_string(),
+            score: 0.3,
+            weight: 1.0,
+            confidence: 0.0,
+            share: 0.0,
+            last_updated: None,
+            detail: Some("0 correspondent(s), 0 spam report(s), 0 released".to_string()),
+        };
+        let row = format_source_row(&local);
+        assert!(row.starts_with("local"));
+        assert!(row.contains("0.30"));
+        assert!(row.ends_with("0 correspondent(s), 0 spam report(s), 0 released"));
+
+        let matl = SourceTrust {
+            source: "matl".to_string(),
+            score: 0.9,
+            weight: 2.0,
+            confidence: 0.5,
+            share: 0.75,
+            last_updated: Some(1_700_000_000),
+            detail: None,
+        };
+        let row = format_source_row(&matl);
+        assert!(row.contains("50%") && row.contains("75%"));
+        assert!(row.ends_with("updated 2023-11-14 22:13:20 UTC"));
+    }
+
     #[test]
     fn test_truncate_string() {
         let short = "Hello";
@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local, Utc};
+use crypto_box::SecretKey;
+
+use crate::client::MycellixClient;
+use crate::contacts::ContactBook;
+use crate::crypto;
+use crate::types::{MailMessage, NewMail};
+
+/// How often to retry fetching a message the signal arrived ahead of
+const FETCH_ATTEMPTS: u32 = 10;
+
+/// Print each message as it is delivered, optionally running a hook command
+pub async fn handle_watch(client: &MycellixClient, exec: Option<String>) -> Result<()> {
+    let mut new_mail = client
+        .watch_new_mail()
+        .await
+        .context("Failed to subscribe to new-mail signals")?;
+
+    let book = client.contact_book().await.unwrap_or_default();
+    let secret = client.encryption_key()?;
+    let min_trust = client.get_config().preferences.min_trust;
+
+    println!("👀 Watching for new mail (Ctrl-C to stop)...");
+    if let Some(ref hook) = exec {
+        println!("   Running for each message: {}", hook);
+    }
+    println!();
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {
+                println!();
+                println!("👋 Stopped watching");
+                return Ok(());
+            }
+            next = new_mail.recv() => {
+                let Some(notice) = next else {
+                    bail!("Lost the connection to the conductor");
+                };
+                let shown = show_new_mail(client, &book, &secret, min_trust, &notice, exec.as_deref()).await;
+                if let Err(e) = shown {
+                    println!("⚠️  Message {}: {:#}", notice.message_id, e);
+                }
+            }
+        }
+    }
+}
+
+/// Fetch, print and hand a delivered message to the hook
+///
+/// The signal is only a claim, so it is checked against the message once
+/// that arrives: it must come from the message's author and name the same
+/// sender. Forged signals and mail the inbox would quarantine (blocked
+/// senders, low trust, keys not holding the sender DID) are skipped so they
+/// never trigger the hook; quarantined mail waits in `inbox --quarantine`.
+async fn show_new_mail(
+    client: &MycellixClient,
+    book: &ContactBook,
+    secret: &SecretKey,
+    min_trust: f64,
+    notice: &NewMail,
+    exec: Option<&str>,
+) -> Result<()> {
+    let message = fetch_message(client, &notice.message_id).await?;
+    let author = client
+        .get_message_author(&message.id)
+        .await
+        .context("Failed to look up the message's author")?;
+    if author.as_deref() != Some(notice.provenance.as_str()) {
+        tracing::debug!("Ignoring signal for {} from {}: not its author", message.id, notice.provenance);
+        return Ok(());
+    }
+    if message.from_did != notice.claimed_from {
+        tracing::debug!(
+            "Ignoring signal for {}: claims {}, message is from {}",
+            message.id,
+            notice.claimed_from,
+            message.from_did
+        );
+        return Ok(());
+    }
+
+    let quarantined = client
+        .screen_message(&message.id, min_trust)
+        .await
+        .context("Failed to screen message")?;
+    if let Some(quarantined) = quarantined {
+        tracing::debug!("Not showing {}: {}", message.id, quarantined.reason);
+        return Ok(());
+    }
+
+    let trust = client
+        .get_trust_breakdown(&message.from_did)
+        .await
+        .context("Failed to get sender trust")?
+        .score;
+    let subject = crypto::decrypt_subject(&message.subject_encrypted, &message.recipient_keys, secret);
+
+    println!("{}", format_notification(&message, &subject, trust, min_trust, book));
+
+    if let Some(hook) = exec {
+        let status = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(hook)
+            .envs(hook_env(&message, &subject, trust))
+            .status()
+            .await
+            .context("Failed to run hook")?;
+        if !status.success() {
+            println!("   ⚠️  Hook exited with {}", status);
+        }
+    }
+
+    Ok(())
+}
+
+/// The sender signals before its message is published, so the signal almost
+/// always arrives before the message reaches us through the DHT
+async fn fetch_message(client: &MycellixClient, message_id: &str) -> Result<MailMessage> {
+    let mut attempt = 1;
+    loop {
+        match client.get_message(message_id).await {
+            Ok(message) => return Ok(message),
+            Err(_) if attempt < FETCH_ATTEMPTS => {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// One line per arriving message
+fn format_notification(
+    message: &MailMessage,
+    subject: &str,
+    trust: f64,
+    min_trust: f64,
+    book: &ContactBook,
+) -> String {
+    let time = DateTime::<Utc>::from_timestamp(message.timestamp, 0)
+        .map(|dt| dt.with_timezone(&Local).format("%H:%M:%S").to_string())
+        .unwrap_or_default();
+    let warning = if trust < min_trust { "  🚧 low trust" } else { "" };
+
+    format!(
+        "📨 {}  {}  \"{}\"  trust {:.2}{}\n   ID: {}",
+        time,
+        book.display(&message.from_did),
+        subject,
+        trust,
+        warning,
+        message.id
+    )
+}
+
+/// Environment passed to the hook command
+fn hook_env(message: &MailMessage, subject: &str, trust: f64) -> Vec<(&'static str, String)> {
+    vec![
+        ("MYCELIX_MESSAGE_ID", message.id.clone()),
+        ("MYCELIX_FROM", message.from_did.clone()),
+        ("MYCELIX_SUBJECT", subject.to_string()),
+        ("MYCELIX_TIMESTAMP", message.timestamp.to_string()),
+        ("MYCELIX_TRUST", format!("{:.2}", trust)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Contact, EpistemicTier};
+
+    fn message() -> MailMessage {
+        MailMessage {
+            id: "uhCkkABC".to_string(),
+            from_did: "did:mycelix:ABC123".to_string(),
+            to_dids: vec!["did:mycelix:XYZ789".to_string()],
+            cc_dids: vec![],
+            subject_encrypted: b"Test".to_vec(),
+            body_cid: "bafyrei123".to_string(),
+            timestamp: 1234567890,
+            thread_id: None,
+            epistemic_tier: EpistemicTier::Tier2PrivatelyVerifiable,
+            recipient_keys: vec![],
+            expires_at: None,
+            state: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_format_notification() {
+        let book = ContactBook::new(vec![Contact {
+            did: "did:mycelix:ABC123".to_string(),
+            name: "Alice".to_string(),
+            email_alias: None,
+            notes: None,
+            trust_score: None,
+        }]);
+
+        let line = format_notification(&message(), "Hello", 0.82, 0.5, &book);
+        assert!(line.contains("Alice <did:mycelix:ABC123>"));
+        assert!(line.contains("\"Hello\""));
+        assert!(line.contains("trust 0.82"));
+        assert!(line.contains("ID: uhCkkABC"));
+        assert!(!line.contains("low trust"));
+
+        let line = format_notification(&message(), "Hello", 0.2, 0.5, &ContactBook::default());
+        assert!(line.contains("low trust"));
+    }
+
+    #[test]
+    fn test_hook_env() {
+        let env = hook_env(&message(), "Hello", 0.824);
+        assert!(env.contains(&("MYCELIX_MESSAGE_ID", "uhCkkABC".to_string())));
+        assert!(env.contains(&("MYCELIX_FROM", "did:mycelix:ABC123".to_string())));
+        assert!(env.contains(&("MYCELIX_SUBJECT", "Hello".to_string())));
+        assert!(env.contains(&("MYCELIX_TRUST", "0.82".to_string())));
+    }
+}
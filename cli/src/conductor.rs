@@ -0,0 +1,259 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use holochain_client::{
+    AdminWebsocket, AppWebsocket, AuthorizeSigningCredentialsPayload, ClientAgentSigner,
+    ConductorApiError, ExternIO, IssueAppAuthenticationTokenPayload, Signal, ZomeCallTarget,
+};
+use holochain_conductor_api::CellInfo;
+use holochain_types::prelude::{AgentPubKey, CellId, FunctionName, ZomeName};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+use tokio::sync::{mpsc, OnceCell};
+
+/// Errors raised while talking to the Holochain conductor
+#[derive(Debug, Error)]
+pub enum ConductorError {
+    /// The conductor could not be reached (not running, wrong port, ...)
+    #[error("Holochain conductor unavailable at {url}: {reason}")]
+    Unavailable { url: String, reason: String },
+
+    /// The conductor is running but the mail app is not installed/enabled
+    #[error("App '{app_id}' has no cell for role '{role_name}' on the conductor")]
+    MissingCell { app_id: String, role_name: String },
+
+    /// The zome function itself returned an error
+    #[error("Zome call {zome}::{function} failed: {reason}")]
+    ZomeCall {
+        zome: String,
+        function: String,
+        reason: String,
+    },
+
+    /// Payload could not be encoded or the response could not be decoded
+    #[error("Failed to (de)serialize {zome}::{function} payload: {reason}")]
+    Serialization {
+        zome: String,
+        function: String,
+        reason: String,
+    },
+}
+
+/// Lazily established connection to the conductor's app interface
+///
+/// Commands like `status` must keep working when the conductor is down, so
+/// the websocket is only opened on the first zome call and then reused for
+/// the lifetime of the CLI process.
+pub struct ConductorClient {
+    app_url: String,
+    admin_url: String,
+    app_id: String,
+    role_name: String,
+    connection: OnceCell<AppConnection>,
+}
+
+struct AppConnection {
+    app_ws: AppWebsocket,
+    cell_id: CellId,
+}
+
+impl ConductorClient {
+    pub fn new(app_url: &str, admin_url: &str, app_id: &str, role_name: &str) -> Self {
+        Self {
+            app_url: app_url.to_string(),
+            admin_url: admin_url.to_string(),
+            app_id: app_id.to_string(),
+            role_name: role_name.to_string(),
+            connection: OnceCell::new(),
+        }
+    }
+
+    /// Call a zome function on the mail DNA and decode its response
+    pub async fn call_zome<I, O>(
+        &self,
+        zome: &str,
+        function: &str,
+        payload: I,
+    ) -> Result<O, ConductorError>
+    where
+        I: Serialize + Debug,
+        O: DeserializeOwned + Debug,
+    {
+        let connection = self.connection().await?;
+
+        let encoded = ExternIO::encode(payload).map_err(|e| ConductorError::Serialization {
+            zome: zome.to_string(),
+            function: function.to_string(),
+            reason: e.to_string(),
+        })?;
+
+        let response = connection
+            .app_ws
+            .call_zome(
+                ZomeCallTarget::CellId(connection.cell_id.clone()),
+                ZomeName::from(zome),
+                FunctionName::from(function),
+                encoded,
+            )
+            .await
+            .map_err(|e| self.map_call_error(zome, function, e))?;
+
+        response.decode().map_err(|e| ConductorError::Serialization {
+            zome: zome.to_string(),
+            function: function.to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Receive the app signals a zome emits on the mail cell
+    ///
+    /// Signals that fail to decode as `T` are skipped. The channel stays
+    /// open for as long as the connection does.
+    pub async fn subscribe<T>(&self, zome: &str) -> Result<mpsc::UnboundedReceiver<T>, ConductorError>
+    where
+        T: DeserializeOwned + Debug + Send + 'static,
+    {
+        let connection = self.connection().await?;
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let cell_id = connection.cell_id.clone();
+        let zome = ZomeName::from(zome);
+
+        connection
+            .app_ws
+            .on_signal(move |signal| {
+                if let Signal::App { cell_id: from_cell, zome_name, signal } = signal {
+                    if from_cell != cell_id || zome_name != zome {
+                        return;
+                    }
+                    match signal.into_inner().decode::<T>() {
+                        Ok(decoded) => {
+                            let _ = sender.send(decoded);
+                        }
+                        Err(e) => tracing::debug!("Ignoring undecodable signal: {}", e),
+                    }
+                }
+            })
+            .await;
+
+        Ok(receiver)
+    }
+
+    /// The agent key this app's cell runs as
+    pub async fn agent_pub_key(&self) -> Result<AgentPubKey, ConductorError> {
+        Ok(self.connection().await?.cell_id.agent_pubkey().clone())
+    }
+
+    /// Check whether the conductor is reachable and the app is installed
+    pub async fn is_connected(&self) -> bool {
+        self.connection().await.is_ok()
+    }
+
+    /// Get the app websocket URL
+    pub fn app_url(&self) -> &str {
+        &self.app_url
+    }
+
+    async fn connection(&self) -> Result<&AppConnection, ConductorError> {
+        self.connection.get_or_try_init(|| self.connect()).await
+    }
+
+    /// Open the admin + app websockets and authorize this process to sign zome calls
+    async fn connect(&self) -> Result<AppConnection, ConductorError> {
+        let admin_ws = AdminWebsocket::connect(socket_addr(&self.admin_url), None)
+            .await
+            .map_err(|e| self.unavailable(&self.admin_url, e))?;
+
+        let token = admin_ws
+            .issue_app_auth_token(IssueAppAuthenticationTokenPayload::for_installed_app_id(
+                self.app_id.clone(),
+            ))
+            .await
+            .map_err(|e| self.unavailable(&self.admin_url, e))?
+            .token;
+
+        let signer = ClientAgentSigner::default();
+        let app_ws = AppWebsocket::connect(
+            socket_addr(&self.app_url),
+            token,
+            Arc::new(signer.clone()),
+            None,
+        )
+        .await
+        .map_err(|e| self.unavailable(&self.app_url, e))?;
+
+        let cell_id = app_ws
+            .cached_app_info()
+            .cell_info
+            .get(&self.role_name)
+            .and_then(|cells| {
+                cells.iter().find_map(|cell| match cell {
+                    CellInfo::Provisioned(provisioned) => Some(provisioned.cell_id.clone()),
+                    _ => None,
+                })
+            })
+            .ok_or_else(|| ConductorError::MissingCell {
+                app_id: self.app_id.clone(),
+                role_name: self.role_name.clone(),
+            })?;
+
+        let credentials = admin_ws
+            .authorize_signing_credentials(AuthorizeSigningCredentialsPayload {
+                cell_id: cell_id.clone(),
+                functions: None,
+            })
+            .await
+            .map_err(|e| self.unavailable(&self.admin_url, e))?;
+        signer.add_credentials(cell_id.clone(), credentials);
+
+        Ok(AppConnection { app_ws, cell_id })
+    }
+
+    fn unavailable(&self, url: &str, error: impl Debug) -> ConductorError {
+        ConductorError::Unavailable {
+            url: url.to_string(),
+            reason: format!("{:?}", error),
+        }
+    }
+
+    fn map_call_error(&self, zome: &str, function: &str, error: ConductorApiError) -> ConductorError {
+        match error {
+            ConductorApiError::WebsocketError(e) => self.unavailable(&self.app_url, e),
+            other => ConductorError::ZomeCall {
+                zome: zome.to_string(),
+                function: function.to_string(),
+                reason: format!("{:?}", other),
+            },
+        }
+    }
+}
+
+/// Strip the `ws://` scheme so the URL can be used as a socket address
+fn socket_addr(url: &str) -> String {
+    url.trim_start_matches("ws://")
+        .trim_start_matches("wss://")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_socket_addr() {
+        assert_eq!(socket_addr("ws://localhost:8888"), "localhost:8888");
+        assert_eq!(socket_addr("ws://127.0.0.1:8888/"), "127.0.0.1:8888");
+        assert_eq!(socket_addr("localhost:8888"), "localhost:8888");
+    }
+
+    #[test]
+    fn test_unavailable_error_message() {
+        let err = ConductorError::Unavailable {
+            url: "ws://localhost:8888".to_string(),
+            reason: "connection refused".to_string(),
+        };
+        assert!(err.to_string().contains("unavailable"));
+        assert!(err.to_string().contains("ws://localhost:8888"));
+    }
+}
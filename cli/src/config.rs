@@ -42,10 +42,18 @@ pub struct ConductorConfig {
     /// Holochain conductor WebSocket URL
     pub url: String,
 
+    /// Conductor admin WebSocket URL (used to issue app auth tokens)
+    #[serde(default = "default_admin_url")]
+    pub admin_url: String,
+
     /// Application ID (defaults to "mycelix-mail")
     #[serde(default = "default_app_id")]
     pub app_id: Option<String>,
 
+    /// Role name of the mail DNA inside the installed app
+    #[serde(default = "default_role_name")]
+    pub role_name: String,
+
     /// Connection timeout in seconds
     #[serde(default = "default_timeout")]
     pub timeout: u64,
@@ -77,15 +85,26 @@ pub struct PreferencesConfig {
     /// Message display format
     #[serde(default = "default_format")]
     pub display_format: String,
+
+    /// Senders below this trust score are quarantined
+    #[serde(default = "default_min_trust")]
+    pub min_trust: f64,
+
+    /// Report mail from blocked senders as spam when listing the inbox
+    #[serde(default)]
+    pub auto_report_blocked: bool,
 }
 
 // Default value functions
 fn default_timeout() -> u64 { 30 }
+fn default_admin_url() -> String { "ws://localhost:8889".to_string() }
 fn default_app_id() -> Option<String> { Some("mycelix-mail".to_string()) }
+fn default_role_name() -> String { "mycelix_mail".to_string() }
 fn default_tier() -> u8 { 2 }
 fn default_true() -> bool { true }
 fn default_cache_ttl() -> u64 { 3600 }
 fn default_format() -> String { "table".to_string() }
+fn default_min_trust() -> f64 { 0.5 }
 
 impl Config {
     /// Get the default config directory path
@@ -105,6 +124,11 @@ impl Config {
         Ok(Self::config_dir()?.join("keys"))
     }
 
+    /// Get cache directory path
+    pub fn cache_dir() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("cache"))
+    }
+
     /// Load configuration from file, or create default if it doesn't exist
     pub fn load_or_create() -> Result<Self> {
         let config_file = Self::config_file()?;
@@ -186,7 +210,9 @@ impl Default for Config {
             },
             conductor: ConductorConfig {
                 url: "ws://localhost:8888".to_string(),
+                admin_url: default_admin_url(),
                 app_id: Some("mycelix_mail".to_string()),
+                role_name: default_role_name(),
                 timeout: default_timeout(),
             },
             services: ServicesConfig {
@@ -198,6 +224,8 @@ impl Default for Config {
                 auto_sync: default_true(),
                 cache_ttl: default_cache_ttl(),
                 display_format: default_format(),
+                min_trust: default_min_trust(),
+                auto_report_blocked: false,
             },
         }
     }
@@ -0,0 +1,129 @@
+use anyhow::{Result, bail};
+
+use crate::types::Contact;
+
+/// Address book lookups: display names for DIDs and recipient resolution
+#[derive(Debug, Clone, Default)]
+pub struct ContactBook {
+    contacts: Vec<Contact>,
+}
+
+impl ContactBook {
+    pub fn new(contacts: Vec<Contact>) -> Self {
+        Self { contacts }
+    }
+
+    /// Contact saved for a DID, if any
+    pub fn find_by_did(&self, did: &str) -> Option<&Contact> {
+        self.contacts.iter().find(|c| c.did == did)
+    }
+
+    /// `Name <did>` for known contacts, the bare DID otherwise
+    pub fn display(&self, did: &str) -> String {
+        match self.find_by_did(did) {
+            Some(contact) => format!("{} <{}>", contact.name, did),
+            None => did.to_string(),
+        }
+    }
+
+    /// Comma-separated `display` of several DIDs
+    pub fn display_list(&self, dids: &[String]) -> String {
+        dids.iter()
+            .map(|did| self.display(did))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Resolve a recipient given as a DID, contact name or email alias
+    ///
+    /// DIDs pass through unchanged; names and aliases match case-insensitively
+    /// and must identify exactly one contact.
+    pub fn resolve(&self, recipient: &str) -> Result<String> {
+        let recipient = recipient.trim();
+        if recipient.starts_with("did:") {
+            return Ok(recipient.to_string());
+        }
+
+        let matches: Vec<&Contact> = self
+            .contacts
+            .iter()
+            .filter(|c| {
+                c.name.eq_ignore_ascii_case(recipient)
+                    || c.email_alias
+                        .as_deref()
+                        .is_some_and(|alias| alias.eq_ignore_ascii_case(recipient))
+            })
+            .collect();
+
+        match matches.as_slice() {
+            [contact] => Ok(contact.did.clone()),
+            [] => bail!(
+                "Unknown recipient: '{}'\n\
+                 Recipient must be a DID (e.g., did:mycelix:ABC123...), contact name or email alias\n\
+                 Use 'mycelix-mail contact add <name> <did>' to save a contact",
+                recipient
+            ),
+            _ => bail!(
+                "'{}' matches several contacts ({}); use the DID instead",
+                recipient,
+                matches
+                    .iter()
+                    .map(|c| c.did.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contact(name: &str, did: &str, alias: Option<&str>) -> Contact {
+        Contact {
+            did: did.to_string(),
+            name: name.to_string(),
+            email_alias: alias.map(str::to_string),
+            notes: None,
+            trust_score: None,
+        }
+    }
+
+    fn book() -> ContactBook {
+        ContactBook::new(vec![
+            contact("Alice", "did:mycelix:alice", Some("alice@example.com")),
+            contact("Bob", "did:mycelix:bob", None),
+            contact("Sam", "did:mycelix:sam1", None),
+            contact("sam", "did:mycelix:sam2", None),
+        ])
+    }
+
+    #[test]
+    fn test_resolve_by_did_name_and_alias() {
+        let book = book();
+        assert_eq!(book.resolve("did:mycelix:zed").unwrap(), "did:mycelix:zed");
+        assert_eq!(book.resolve("alice").unwrap(), "did:mycelix:alice");
+        assert_eq!(book.resolve(" Alice@Example.com ").unwrap(), "did:mycelix:alice");
+        assert_eq!(book.resolve("Bob").unwrap(), "did:mycelix:bob");
+    }
+
+    #[test]
+    fn test_resolve_rejects_unknown_and_ambiguous() {
+        let book = book();
+        assert!(book.resolve("carol").is_err());
+        let err = book.resolve("sam").unwrap_err().to_string();
+        assert!(err.contains("did:mycelix:sam1") && err.contains("did:mycelix:sam2"));
+    }
+
+    #[test]
+    fn test_display_names() {
+        let book = book();
+        assert_eq!(book.display("did:mycelix:alice"), "Alice <did:mycelix:alice>");
+        assert_eq!(book.display("did:mycelix:zed"), "did:mycelix:zed");
+        assert_eq!(
+            book.display_list(&["did:mycelix:bob".to_string(), "did:mycelix:zed".to_string()]),
+            "Bob <did:mycelix:bob>, did:mycelix:zed"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! End-to-end encryption helpers
+//!
+//! Identities are Ed25519 keypairs generated by `init`. For encryption the
+//! same keys are converted to X25519 (Montgomery form) and used with
+//! libsodium-compatible sealed boxes, so only the recipient's private key
+//! can open what is written to the DHT.
+//!
+//! Each message is encrypted once under a random XChaCha20-Poly1305
+//! content key; that key is then sealed separately for every recipient and
+//! for the sender, so one entry serves the whole recipient list.
+
+use anyhow::{Context, Result, bail};
+use blake2::{Blake2b512, Digest};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use crypto_box::{PublicKey, SecretKey};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand::seq::SliceRandom;
+use std::fs;
+use std::path::Path;
+
+/// Symmetric key a message's subject and body are encrypted under
+pub type ContentKey = chacha20poly1305::Key;
+
+const NONCE_LEN: usize = 24;
+
+/// Shown in place of a subject that could not be decrypted
+pub const UNDECRYPTABLE: &str = "<encrypted>";
+
+/// Create a DID from a verifying key
+///
+/// Format: did:mycelix:{base58(blake2b(pubkey))}
+///
+/// This creates a deterministic DID from the public key using:
+/// 1. Blake2b-512 hash of the public key (for collision resistance)
+/// 2. Take first 32 bytes of the hash (256 bits of entropy)
+/// 3. Base58 encode for human-friendly representation
+pub fn create_did(verifying_key: &VerifyingKey) -> String {
+    // Hash the public key with Blake2b-512
+    let mut hasher = Blake2b512::new();
+    hasher.update(verifying_key.to_bytes());
+    let hash = hasher.finalize();
+
+    // Take first 32 bytes of hash and encode as base58
+    let hash_bytes = &hash[..32];
+    let encoded = bs58::encode(hash_bytes).into_string();
+
+    format!("did:mycelix:{}", encoded)
+}
+
+/// Load the Ed25519 public key written by `init`
+///
+/// Unlike the private key this needs no passphrase.
+pub fn load_verifying_key(path: &Path) -> Result<VerifyingKey> {
+    let bytes = fs::read(path)
+        .with_context(|| format!("Failed to read public key: {}", path.display()))?;
+    let bytes: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes, found {}", bytes.len()))?;
+    VerifyingKey::from_bytes(&bytes).context("Public key is not a valid Ed25519 point")
+}
+
+/// Parse a hex-encoded Ed25519 public key
+pub fn parse_verifying_key(hex_key: &str) -> Result<VerifyingKey> {
+    let bytes = hex::decode(hex_key.trim()).context("Public key is not valid hex")?;
+    let bytes: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes, found {}", bytes.len()))?;
+    VerifyingKey::from_bytes(&bytes).context("Public key is not a valid Ed25519 point")
+}
+
+/// Check that a `did:mycelix` identifier was derived from the given key
+///
+/// Guards against a registry (or cache) handing out someone else's key.
+pub fn verify_did_key(did: &str, verifying_key: &VerifyingKey) -> Result<()> {
+    if create_did(verifying_key) != did {
+        bail!("Public key does not match DID {}", did);
+    }
+    Ok(())
+}
+
+/// Sign a Holochain agent key's raw bytes with our identity key
+///
+/// The DNA only binds a DID to an agent that presents this proof, and only
+/// binds a new DID whose identifier `create_did` derives from the same key.
+pub fn sign_agent_key(signing_key: &SigningKey, agent_key: &[u8]) -> [u8; 64] {
+    signing_key.sign(agent_key).to_bytes()
+}
+
+/// X25519 secret key derived from our Ed25519 signing key
+pub fn x25519_secret(signing_key: &SigningKey) -> SecretKey {
+    SecretKey::from(signing_key.to_scalar_bytes())
+}
+
+/// X25519 public key derived from an Ed25519 verifying key
+pub fn x25519_public(verifying_key: &VerifyingKey) -> PublicKey {
+    PublicKey::from(verifying_key.to_montgomery().to_bytes())
+}
+
+/// Encrypt bytes so only the holder of `recipient`'s secret key can read them
+pub fn seal(recipient: &PublicKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut rng = rand::rngs::OsRng;
+    recipient
+        .seal(&mut rng, plaintext)
+        .map_err(|_| anyhow::anyhow!("Sealed box encryption failed"))
+}
+
+/// Open a sealed box addressed to `secret`
+pub fn unseal(secret: &SecretKey, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    secret
+        .unseal(ciphertext)
+        .map_err(|_| anyhow::anyhow!("Sealed box could not be opened with this key"))
+}
+
+/// Generate a fresh random content key
+pub fn generate_content_key() -> ContentKey {
+    XChaCha20Poly1305::generate_key(&mut OsRng)
+}
+
+/// Encrypt with XChaCha20-Poly1305, returning `nonce || ciphertext`
+pub fn encrypt_with_key(key: &ContentKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = XChaCha20Poly1305::new(key)
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| anyhow::anyhow!("Symmetric encryption failed"))?;
+
+    let mut out = nonce.to_vec();
+    out.extend(ciphertext);
+    Ok(out)
+}
+
+/// Decrypt `nonce || ciphertext` produced by [`encrypt_with_key`]
+pub fn decrypt_with_key(key: &ContentKey, data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < NONCE_LEN {
+        bail!("Ciphertext is truncated");
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    XChaCha20Poly1305::new(key)
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow::anyhow!("Ciphertext could not be decrypted with this key"))
+}
+
+/// Seal the content key once for each recipient
+///
+/// Slots are shuffled so their order does not reveal which recipient each
+/// belongs to.
+pub fn seal_content_key(key: &ContentKey, recipients: &[PublicKey]) -> Result<Vec<Vec<u8>>> {
+    let mut slots = recipients
+        .iter()
+        .map(|recipient| seal(recipient, key.as_slice()))
+        .collect::<Result<Vec<_>>>()?;
+    slots.shuffle(&mut rand::rngs::OsRng);
+    Ok(slots)
+}
+
+/// Find the slot sealed for `secret` and recover the content key
+pub fn open_content_key(slots: &[Vec<u8>], secret: &SecretKey) -> Option<ContentKey> {
+    slots
+        .iter()
+        .filter_map(|slot| unseal(secret, slot).ok())
+        .find(|key| key.len() == 32)
+        .map(|key| ContentKey::clone_from_slice(&key))
+}
+
+/// Encrypt a subject line under the message's content key
+pub fn encrypt_subject(subject: &str, key: &ContentKey) -> Result<Vec<u8>> {
+    encrypt_with_key(key, subject.as_bytes())
+}
+
+/// Decrypt a subject line, falling back to a placeholder for mail we cannot open
+pub fn decrypt_subject(encrypted: &[u8], recipient_keys: &[Vec<u8>], secret: &SecretKey) -> String {
+    open_content_key(recipient_keys, secret)
+        .and_then(|key| decrypt_with_key(&key, encrypted).ok())
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| UNDECRYPTABLE.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    fn random_signing_key() -> SigningKey {
+        let mut secret = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut secret);
+        SigningKey::from_bytes(&secret)
+    }
+
+    #[test]
+    fn test_subject_roundtrip() {
+        let recipient = random_signing_key();
+        let content_key = generate_content_key();
+        let slots =
+            seal_content_key(&content_key, &[x25519_public(&recipient.verifying_key())]).unwrap();
+
+        let encrypted = encrypt_subject("Quarterly report", &content_key).unwrap();
+        assert!(!encrypted.windows(9).any(|w| w == b"Quarterly"));
+
+        let decrypted = decrypt_subject(&encrypted, &slots, &x25519_secret(&recipient));
+        assert_eq!(decrypted, "Quarterly report");
+    }
+
+    #[test]
+    fn test_every_recipient_can_open() {
+        let recipients: Vec<SigningKey> = (0..3).map(|_| random_signing_key()).collect();
+        let publics: Vec<PublicKey> = recipients
+            .iter()
+            .map(|r| x25519_public(&r.verifying_key()))
+            .collect();
+
+        let content_key = generate_content_key();
+        let slots = seal_content_key(&content_key, &publics).unwrap();
+        assert_eq!(slots.len(), 3);
+
+        for recipient in &recipients {
+            let opened = open_content_key(&slots, &x25519_secret(recipient)).unwrap();
+            assert_eq!(opened, content_key);
+        }
+    }
+
+    #[test]
+    fn test_wrong_key_cannot_decrypt() {
+        let recipient = random_signing_key();
+        let eavesdropper = random_signing_key();
+        let content_key = generate_content_key();
+        let slots =
+            seal_content_key(&content_key, &[x25519_public(&recipient.verifying_key())]).unwrap();
+        let encrypted = encrypt_subject("Secret", &content_key).unwrap();
+
+        let result = decrypt_subject(&encrypted, &slots, &x25519_secret(&eavesdropper));
+        assert_eq!(result, UNDECRYPTABLE);
+    }
+
+    #[test]
+    fn test_legacy_plaintext_subject_is_not_shown() {
+        let key = random_signing_key();
+        assert_eq!(
+            decrypt_subject(b"ENC:Hello", &[], &x25519_secret(&key)),
+            UNDECRYPTABLE
+        );
+    }
+
+    #[test]
+    fn test_symmetric_tamper_detected() {
+        let key = generate_content_key();
+        let mut data = encrypt_with_key(&key, b"body").unwrap();
+        assert_eq!(decrypt_with_key(&key, &data).unwrap(), b"body");
+
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        assert!(decrypt_with_key(&key, &data).is_err());
+        assert!(decrypt_with_key(&key, &data[..10]).is_err());
+    }
+
+    #[test]
+    fn test_verify_did_key() {
+        let key = random_signing_key().verifying_key();
+        let other = random_signing_key().verifying_key();
+        let did = create_did(&key);
+
+        assert!(verify_did_key(&did, &key).is_ok());
+        assert!(verify_did_key(&did, &other).is_err());
+    }
+
+    #[test]
+    fn test_sign_agent_key() {
+        let key = random_signing_key();
+        let agent_key = [0x84, 0x20, 0x24, 7, 7, 7];
+        let signature = ed25519_dalek::Signature::from_bytes(&sign_agent_key(&key, &agent_key));
+
+        assert!(key.verifying_key().verify_strict(&agent_key, &signature).is_ok());
+        assert!(key.verifying_key().verify_strict(&[0x84, 0x20, 0x24], &signature).is_err());
+    }
+
+    #[test]
+    fn test_parse_verifying_key() {
+        let key = random_signing_key().verifying_key();
+        let parsed = parse_verifying_key(&hex::encode(key.to_bytes())).unwrap();
+        assert_eq!(parsed, key);
+
+        assert!(parse_verifying_key("abcd").is_err());
+        assert!(parse_verifying_key("not hex").is_err());
+    }
+}
@@ -1,10 +1,18 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+mod attachments;
 mod commands;
+mod conductor;
 mod config;
+mod contacts;
 mod client;
+mod crypto;
+mod keystore;
+mod resolver;
+mod storage;
 mod types;
+mod wire;
 
 use commands::*;
 
@@ -45,15 +53,27 @@ enum Commands {
         #[arg(short, long)]
         email: Option<String>,
 
-        /// Import existing keys
+        /// Import an existing key (keystore, PKCS#8 PEM, hex or raw 32 bytes)
         #[arg(long)]
         import_keys: Option<String>,
     },
 
     /// Send an email message
     Send {
-        /// Recipient DID or email address
-        to: String,
+        /// Recipients as DIDs, contact names or aliases (same as --to)
+        to: Vec<String>,
+
+        /// Recipient DID, contact name or alias (repeatable)
+        #[arg(long = "to", value_name = "RECIPIENT")]
+        to_flag: Vec<String>,
+
+        /// Carbon-copy recipient (repeatable)
+        #[arg(long, value_name = "RECIPIENT")]
+        cc: Vec<String>,
+
+        /// Blind carbon-copy recipient (repeatable, hidden from other recipients)
+        #[arg(long, value_name = "RECIPIENT")]
+        bcc: Vec<String>,
 
         /// Email subject
         #[arg(short, long)]
@@ -74,6 +94,10 @@ enum Commands {
         /// Epistemic tier (0-4)
         #[arg(long, default_value = "2")]
         tier: u8,
+
+        /// Hide the message after this long (e.g. 30m, 12h, 7d)
+        #[arg(long, value_name = "DURATION")]
+        expires_in: Option<String>,
     },
 
     /// List inbox messages
@@ -82,23 +106,61 @@ enum Commands {
         #[arg(short, long)]
         from: Option<String>,
 
-        /// Filter by minimum trust score
+        /// Minimum sender trust (defaults to preferences.min_trust)
         #[arg(short, long)]
         trust_min: Option<f64>,
 
+        /// Show quarantined low-trust messages instead of the inbox
+        #[arg(long)]
+        quarantine: bool,
+
         /// Show only unread messages
         #[arg(short, long)]
         unread: bool,
 
-        /// Number of messages to display
+        /// Show archived messages instead of the inbox
+        #[arg(long)]
+        archived: bool,
+
+        /// Only messages at or after this date (YYYY-MM-DD, RFC 3339 or Unix seconds)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only messages at or before this date (YYYY-MM-DD, RFC 3339 or Unix seconds)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Number of messages per page
         #[arg(short, long, default_value = "20")]
         limit: usize,
 
+        /// Continue from a previous page (printed below each page)
+        #[arg(long)]
+        cursor: Option<String>,
+
         /// Output format (table, json, raw)
         #[arg(long, default_value = "table")]
         format: String,
     },
 
+    /// Release a quarantined message and whitelist its sender
+    Release {
+        /// Message ID
+        message_id: String,
+    },
+
+    /// Retract a message you sent from every recipient's inbox
+    Retract {
+        /// Message ID
+        message_id: String,
+    },
+
+    /// Remove a received message from your inbox
+    Delete {
+        /// Message ID
+        message_id: String,
+    },
+
     /// Read a specific message
     Read {
         /// Message ID
@@ -107,6 +169,54 @@ enum Commands {
         /// Mark as read
         #[arg(long, default_value = "true")]
         mark_read: bool,
+
+        /// Download attachments into this directory
+        #[arg(long)]
+        save_attachments: Option<String>,
+    },
+
+    /// Change a message's read, starred, archived or label state
+    Mark {
+        /// Message ID
+        message_id: String,
+
+        /// Mark as read
+        #[arg(long, conflicts_with = "unread")]
+        read: bool,
+
+        /// Mark as unread
+        #[arg(long)]
+        unread: bool,
+
+        /// Star the message
+        #[arg(long, conflicts_with = "unstar")]
+        star: bool,
+
+        /// Remove the star
+        #[arg(long)]
+        unstar: bool,
+
+        /// Move the message to the archive
+        #[arg(long, conflicts_with = "unarchive")]
+        archive: bool,
+
+        /// Move the message back to the inbox
+        #[arg(long)]
+        unarchive: bool,
+
+        /// Add a label (repeatable)
+        #[arg(long, value_name = "LABEL")]
+        label: Vec<String>,
+
+        /// Remove a label (repeatable)
+        #[arg(long, value_name = "LABEL")]
+        unlabel: Vec<String>,
+    },
+
+    /// Show the whole conversation a message belongs to
+    Thread {
+        /// ID of any message in the conversation
+        message_id: String,
     },
 
     /// Manage trust scores
@@ -115,12 +225,24 @@ enum Commands {
         command: TrustCommands,
     },
 
-    /// Manage DIDs and contacts
+    /// Manage DIDs
     Did {
         #[command(subcommand)]
         command: DidCommands,
     },
 
+    /// Manage your identity key
+    Identity {
+        #[command(subcommand)]
+        command: IdentityCommands,
+    },
+
+    /// Manage your private address book
+    Contact {
+        #[command(subcommand)]
+        command: ContactCommands,
+    },
+
     /// Search messages
     Search {
         /// Search query
@@ -154,6 +276,13 @@ enum Commands {
         since: Option<String>,
     },
 
+    /// Print new mail as it arrives
+    Watch {
+        /// Shell command to run for each message (MYCELIX_* variables describe it)
+        #[arg(long)]
+        exec: Option<String>,
+    },
+
     /// Show configuration and status
     Status {
         /// Show detailed information
@@ -202,6 +331,31 @@ enum TrustCommands {
         /// Specific DID to sync
         did: Option<String>,
     },
+
+    /// Always accept mail from a DID (end with * for a prefix)
+    Allow {
+        /// DID or DID prefix ending in *
+        pattern: String,
+    },
+
+    /// Always quarantine mail from a DID (end with * for a prefix)
+    Block {
+        /// DID or DID prefix ending in *
+        pattern: String,
+
+        /// Also report their quarantined mail as spam
+        #[arg(long)]
+        report: bool,
+    },
+
+    /// Remove the allow or block rule for a pattern
+    Unblock {
+        /// DID or DID prefix ending in *
+        pattern: String,
+    },
+
+    /// List allow/block rules
+    Rules,
 }
 
 #[derive(Subcommand, Debug)]
@@ -218,8 +372,12 @@ enum DidCommands {
 
     /// Resolve a DID to agent key
     Resolve {
-        /// DID to resolve
+        /// DID to resolve (did:mycelix, did:key or did:web)
         did: String,
+
+        /// Print the W3C DID document as JSON-LD
+        #[arg(long)]
+        document: bool,
     },
 
     /// List all known DIDs
@@ -231,6 +389,96 @@ enum DidCommands {
 
     /// Show your current DID
     Whoami,
+
+    /// Hand your DID over to another agent key (e.g. a new device)
+    Rotate {
+        /// Agent public key of the new holder
+        new_agent_key: String,
+    },
+
+    /// Take over a DID rotated to this agent
+    Accept {
+        /// Rotation ID printed by 'did rotate'
+        rotation_id: String,
+    },
+
+    /// Permanently revoke your DID, e.g. if its key leaked
+    Revoke {
+        /// Reason recorded with the revocation
+        #[arg(long)]
+        reason: Option<String>,
+
+        /// Confirm the revocation
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum IdentityCommands {
+    /// Export your identity key for backup or another device
+    Export {
+        /// Key format (keystore, pem, hex, raw)
+        #[arg(short, long, default_value = "keystore")]
+        format: String,
+
+        /// Output file (created owner-only)
+        #[arg(short, long)]
+        output: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ContactCommands {
+    /// Add a contact
+    Add {
+        /// Display name
+        name: String,
+
+        /// Contact's DID
+        did: String,
+
+        /// Email-style alias usable as a recipient
+        #[arg(long)]
+        alias: Option<String>,
+
+        /// Free-form notes
+        #[arg(long)]
+        notes: Option<String>,
+    },
+
+    /// Update a contact (pass an empty value to clear alias or notes)
+    Update {
+        /// Contact DID, name or alias
+        contact: String,
+
+        /// New display name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// New alias
+        #[arg(long)]
+        alias: Option<String>,
+
+        /// New notes
+        #[arg(long)]
+        notes: Option<String>,
+    },
+
+    /// Remove a contact
+    Remove {
+        /// Contact DID, name or alias
+        contact: String,
+    },
+
+    /// List all contacts
+    List,
+
+    /// Search contacts by name, DID, alias or notes
+    Search {
+        /// Search query
+        query: String,
+    },
 }
 
 #[tokio::main]
@@ -272,16 +520,55 @@ async fn main() -> Result<()> {
             unreachable!()
         }
 
-        Commands::Send { to, subject, body, attach, reply_to, tier } => {
-            send::handle_send(&client, to, subject, body, attach, reply_to, tier).await?;
+        Commands::Send { to, to_flag, cc, bcc, subject, body, attach, reply_to, tier, expires_in } => {
+            let to = to.into_iter().chain(to_flag).collect();
+            send::handle_send(&client, to, cc, bcc, subject, body, attach, reply_to, tier, expires_in).await?;
+        }
+
+        Commands::Inbox { from, trust_min, quarantine, unread, archived, since, until, limit, cursor, format } => {
+            let page = inbox::page_query(since, until, limit, cursor)?;
+            inbox::handle_inbox(&client, from, trust_min, unread, archived, quarantine, page, &format).await?;
+        }
+
+        Commands::Release { message_id } => {
+            quarantine::handle_release(&client, message_id).await?;
+        }
+
+        Commands::Retract { message_id } => {
+            delete::handle_retract(&client, message_id).await?;
         }
 
-        Commands::Inbox { from, trust_min, unread, limit, format } => {
-            inbox::handle_inbox(&client, from, trust_min, unread, limit, &format).await?;
+        Commands::Delete { message_id } => {
+            delete::handle_delete(&client, message_id).await?;
         }
 
-        Commands::Read { message_id, mark_read } => {
-            read::handle_read(&client, message_id, mark_read).await?;
+        Commands::Mark {
+            message_id,
+            read,
+            unread,
+            star,
+            unstar,
+            archive,
+            unarchive,
+            label,
+            unlabel,
+        } => {
+            let update = types::MessageStateUpdate {
+                read: mark::toggle(read, unread),
+                starred: mark::toggle(star, unstar),
+                archived: mark::toggle(archive, unarchive),
+                add_labels: label,
+                remove_labels: unlabel,
+            };
+            mark::handle_mark(&client, message_id, update).await?;
+        }
+
+        Commands::Read { message_id, mark_read, save_attachments } => {
+            read::handle_read(&client, message_id, mark_read, save_attachments).await?;
+        }
+
+        Commands::Thread { message_id } => {
+            thread::handle_thread(&client, message_id).await?;
         }
 
         Commands::Trust { command } => {
@@ -298,6 +585,18 @@ async fn main() -> Result<()> {
                 TrustCommands::Sync { did } => {
                     trust::handle_sync(&client, did).await?;
                 }
+                TrustCommands::Allow { pattern } => {
+                    trust::handle_allow(&client, pattern).await?;
+                }
+                TrustCommands::Block { pattern, report } => {
+                    trust::handle_block(&client, pattern, report).await?;
+                }
+                TrustCommands::Unblock { pattern } => {
+                    trust::handle_unblock(&client, pattern).await?;
+                }
+                TrustCommands::Rules => {
+                    trust::handle_rules(&client).await?;
+                }
             }
         }
 
@@ -306,8 +605,12 @@ async fn main() -> Result<()> {
                 DidCommands::Register { did, agent_key } => {
                     did::handle_register(&client, did, agent_key).await?;
                 }
-                DidCommands::Resolve { did } => {
-                    did::handle_resolve(&client, did).await?;
+                DidCommands::Resolve { did, document } => {
+                    if document {
+                        did::handle_resolve_document(&client, did).await?;
+                    } else {
+                        did::handle_resolve(&client, did).await?;
+                    }
                 }
                 DidCommands::List { filter } => {
                     did::handle_list(&client, filter).await?;
@@ -315,6 +618,43 @@ async fn main() -> Result<()> {
                 DidCommands::Whoami => {
                     did::handle_whoami(&client).await?;
                 }
+                DidCommands::Rotate { new_agent_key } => {
+                    did::handle_rotate(&client, new_agent_key).await?;
+                }
+                DidCommands::Accept { rotation_id } => {
+                    did::handle_accept(&client, rotation_id).await?;
+                }
+                DidCommands::Revoke { reason, yes } => {
+                    did::handle_revoke(&client, reason, yes).await?;
+                }
+            }
+        }
+
+        Commands::Identity { command } => {
+            match command {
+                IdentityCommands::Export { format, output } => {
+                    identity::handle_export(&client, format, output).await?;
+                }
+            }
+        }
+
+        Commands::Contact { command } => {
+            match command {
+                ContactCommands::Add { name, did, alias, notes } => {
+                    contact::handle_add(&client, name, did, alias, notes).await?;
+                }
+                ContactCommands::Update { contact, name, alias, notes } => {
+                    contact::handle_update(&client, contact, name, alias, notes).await?;
+                }
+                ContactCommands::Remove { contact } => {
+                    contact::handle_remove(&client, contact).await?;
+                }
+                ContactCommands::List => {
+                    contact::handle_list(&client).await?;
+                }
+                ContactCommands::Search { query } => {
+                    contact::handle_search(&client, query).await?;
+                }
             }
         }
 
@@ -326,6 +666,10 @@ async fn main() -> Result<()> {
             export::handle_export(&client, &format, output, since).await?;
         }
 
+        Commands::Watch { exec } => {
+            watch::handle_watch(&client, exec).await?;
+        }
+
         Commands::Status { detailed } => {
             status::handle_status(&client, detailed).await?;
         }
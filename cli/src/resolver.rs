@@ -0,0 +1,723 @@
+//! DID documents and the resolvers that produce them
+//!
+//! Every DID method we can send to resolves to a W3C DID document:
+//! `did:mycelix` from the bindings in the mail DNA, `did:key` from the
+//! identifier itself and `did:web` from the domain it names. Mail is
+//! encrypted to the document's key agreement key.
+//!
+//! [`ResolverChain`] asks the DNA first, so rotations and revocations take
+//! effect as soon as they reach the DHT, then falls back to the DID registry
+//! and the other methods. Documents from those slower sources are kept in an
+//! on-disk [`DidCache`] for `preferences.cache_ttl` seconds.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use crypto_box::PublicKey;
+use ed25519_dalek::VerifyingKey;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::client::MAIL_ZOME;
+use crate::conductor::ConductorClient;
+use crate::crypto;
+use crate::types::{
+    DidBinding, DidDocument, DidResolution, Service, VerificationMethod, VerificationRelationship,
+};
+use crate::wire;
+
+const DID_CONTEXT: &str = "https://www.w3.org/ns/did/v1";
+const ED25519_CONTEXT: &str = "https://w3id.org/security/suites/ed25519-2020/v1";
+const X25519_CONTEXT: &str = "https://w3id.org/security/suites/x25519-2020/v1";
+
+/// Multicodec prefixes of multibase-encoded public keys
+const ED25519_CODEC: [u8; 2] = [0xed, 0x01];
+const X25519_CODEC: [u8; 2] = [0xec, 0x01];
+
+/// Service whose endpoint is the Holochain agent key mail is delivered to
+pub const HOLOCHAIN_AGENT_SERVICE: &str = "HolochainAgent";
+
+/// Service whose endpoint is a `mailto:` alias of the DID's owner
+pub const EMAIL_ALIAS_SERVICE: &str = "EmailAlias";
+
+/// Turns DIDs of one or more methods into DID documents
+#[async_trait]
+pub trait DidResolver: Send + Sync {
+    /// Whether this resolver handles `did`'s method
+    fn supports(&self, did: &str) -> bool;
+
+    /// The document for `did`, or `None` if the DID does not exist
+    async fn resolve(&self, did: &str) -> Result<Option<DidDocument>>;
+
+    /// Whether answers may be served from the cache instead of asking again
+    fn cacheable(&self) -> bool {
+        true
+    }
+}
+
+/// A DID whose binding was revoked on the DHT
+///
+/// Resolution stops here rather than falling back to a source that may not
+/// have heard of the revocation yet.
+#[derive(Debug, Error)]
+#[error("DID {did} has been revoked")]
+pub struct DidRevoked {
+    pub did: String,
+}
+
+/// `did:mycelix` identifiers, from the binding the DNA resolves them to
+pub struct MycelixResolver {
+    conductor: Arc<ConductorClient>,
+}
+
+impl MycelixResolver {
+    pub fn new(conductor: Arc<ConductorClient>) -> Self {
+        Self { conductor }
+    }
+}
+
+#[async_trait]
+impl DidResolver for MycelixResolver {
+    fn supports(&self, did: &str) -> bool {
+        did.starts_with("did:mycelix:")
+    }
+
+    async fn resolve(&self, did: &str) -> Result<Option<DidDocument>> {
+        let record: Option<wire::DidRecord> = self
+            .conductor
+            .call_zome(MAIL_ZOME, "resolve_did", did.to_string())
+            .await?;
+
+        match record.map(DidBinding::from) {
+            Some(binding) if binding.revoked.is_some() => Err(DidRevoked { did: did.to_string() }.into()),
+            Some(binding) => binding_document(&binding).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// The DNA is authoritative and follows rotations, so it is always asked
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
+
+/// `did:mycelix` identifiers published to the HTTP DID registry
+///
+/// Covers DIDs registered by `init` but not yet bound on the DHT. The
+/// registry is not trusted: the key it returns must derive the DID.
+#[derive(Clone)]
+pub struct RegistryResolver {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl RegistryResolver {
+    pub fn new(http: reqwest::Client, url: &str) -> Self {
+        Self {
+            http,
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// The registry's record for `did`, or `None` if it has none
+    pub async fn lookup(&self, did: &str) -> Result<Option<DidResolution>> {
+        let url = format!("{}/resolve/{}", self.url, did);
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("DID registry not available at {}", self.url))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            bail!("DID registry returned status {}", response.status());
+        }
+
+        let resolution = response
+            .json()
+            .await
+            .context("Failed to parse DID resolution response")?;
+        Ok(Some(resolution))
+    }
+
+    /// Every DID the registry knows about
+    pub async fn list(&self) -> Result<Vec<DidResolution>> {
+        let url = format!("{}/list", self.url);
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("DID registry not available at {}", self.url))?;
+
+        if !response.status().is_success() {
+            bail!("DID registry returned status {}", response.status());
+        }
+
+        response.json().await.context("Failed to parse DID list response")
+    }
+}
+
+#[async_trait]
+impl DidResolver for RegistryResolver {
+    fn supports(&self, did: &str) -> bool {
+        did.starts_with("did:mycelix:")
+    }
+
+    async fn resolve(&self, did: &str) -> Result<Option<DidDocument>> {
+        match self.lookup(did).await? {
+            Some(resolution) => registry_document(&resolution).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// `did:key` identifiers, which carry their Ed25519 key in the DID itself
+pub struct KeyResolver;
+
+#[async_trait]
+impl DidResolver for KeyResolver {
+    fn supports(&self, did: &str) -> bool {
+        did.starts_with("did:key:")
+    }
+
+    async fn resolve(&self, did: &str) -> Result<Option<DidDocument>> {
+        let key = did_key_public_key(did)?;
+        let signing_id = did.trim_start_matches("did:key:").to_string();
+        let agreement_id = encode_multikey(X25519_CODEC, crypto::x25519_public(&key).as_bytes());
+
+        Ok(Some(ed25519_document(did, &key, &signing_id, &agreement_id)))
+    }
+
+    /// Nothing to fetch; the document is derived from the DID itself
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
+
+/// `did:web` identifiers, fetched from `did.json` on the named domain
+pub struct WebResolver {
+    http: reqwest::Client,
+}
+
+impl WebResolver {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+}
+
+#[async_trait]
+impl DidResolver for WebResolver {
+    fn supports(&self, did: &str) -> bool {
+        did.starts_with("did:web:")
+    }
+
+    async fn resolve(&self, did: &str) -> Result<Option<DidDocument>> {
+        let url = did_web_url(did)?;
+        let response = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", url))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            bail!("{} returned status {}", url, response.status());
+        }
+
+        let document: DidDocument = response
+            .json()
+            .await
+            .with_context(|| format!("{} is not a valid DID document", url))?;
+        if document.id != did {
+            bail!("Document at {} is for {}, not {}", url, document.id, did);
+        }
+
+        Ok(Some(document))
+    }
+}
+
+/// Resolvers tried in order, with a cache in front of the slow ones
+///
+/// The first resolver that finds a document wins. Sources that fail are
+/// skipped; if none finds the DID and any failed, the DID is reported as
+/// unresolvable rather than missing. Revocation on the DHT is final.
+pub struct ResolverChain {
+    resolvers: Vec<Box<dyn DidResolver>>,
+    cache: DidCache,
+}
+
+impl ResolverChain {
+    pub fn new(resolvers: Vec<Box<dyn DidResolver>>, cache: DidCache) -> Self {
+        Self { resolvers, cache }
+    }
+
+    /// Forget any cached document for `did`, e.g. after it was rotated
+    pub fn invalidate(&self, did: &str) {
+        if let Err(err) = self.cache.remove(did) {
+            tracing::warn!("Failed to update DID cache: {:#}", err);
+        }
+    }
+
+    fn store(&self, did: &str, document: &DidDocument) {
+        if let Err(err) = self.cache.put(did, document) {
+            tracing::warn!("Failed to update DID cache: {:#}", err);
+        }
+    }
+}
+
+#[async_trait]
+impl DidResolver for ResolverChain {
+    fn supports(&self, did: &str) -> bool {
+        self.resolvers.iter().any(|resolver| resolver.supports(did))
+    }
+
+    async fn resolve(&self, did: &str) -> Result<Option<DidDocument>> {
+        if !self.supports(did) {
+            bail!("No resolver for the DID method of {}", did);
+        }
+
+        let mut failures = Vec::new();
+        for resolver in self.resolvers.iter().filter(|resolver| resolver.supports(did)) {
+            if resolver.cacheable() {
+                if let Some(document) = self.cache.get(did) {
+                    return Ok(Some(document));
+                }
+            }
+
+            match resolver.resolve(did).await {
+                Ok(Some(document)) => {
+                    if resolver.cacheable() {
+                        self.store(did, &document);
+                    } else {
+                        // A fresh answer supersedes whatever an earlier
+                        // fallback left behind
+                        self.invalidate(did);
+                    }
+                    return Ok(Some(document));
+                }
+                Ok(None) => {}
+                Err(err) if err.is::<DidRevoked>() => {
+                    self.invalidate(did);
+                    return Err(err);
+                }
+                Err(err) => failures.push(format!("{:#}", err)),
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(None)
+        } else {
+            bail!("DID {} is unresolvable:\n  {}", did, failures.join("\n  "))
+        }
+    }
+}
+
+/// A cached DID document and when it was fetched (unix seconds)
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedDocument {
+    document: DidDocument,
+    cached_at: i64,
+}
+
+/// DID documents cached on disk for `ttl` seconds
+///
+/// A missing or unreadable cache file is treated as empty.
+pub struct DidCache {
+    path: PathBuf,
+    ttl: u64,
+}
+
+impl DidCache {
+    pub fn new(path: PathBuf, ttl: u64) -> Self {
+        Self { path, ttl }
+    }
+
+    /// The cached document for `did`, if it has not expired
+    pub fn get(&self, did: &str) -> Option<DidDocument> {
+        let now = chrono::Utc::now().timestamp();
+        self.load()
+            .remove(did)
+            .filter(|entry| now.saturating_sub(entry.cached_at) < self.ttl as i64)
+            .map(|entry| entry.document)
+    }
+
+    /// Cache `document` as of now
+    pub fn put(&self, did: &str, document: &DidDocument) -> Result<()> {
+        let mut entries = self.load();
+        entries.insert(
+            did.to_string(),
+            CachedDocument {
+                document: document.clone(),
+                cached_at: chrono::Utc::now().timestamp(),
+            },
+        );
+        self.save(&entries)
+    }
+
+    /// Drop the cached document for `did`
+    pub fn remove(&self, did: &str) -> Result<()> {
+        let mut entries = self.load();
+        if entries.remove(did).is_some() {
+            self.save(&entries)?;
+        }
+        Ok(())
+    }
+
+    fn load(&self) -> HashMap<String, CachedDocument> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, entries: &HashMap<String, CachedDocument>) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).context("Failed to create cache directory")?;
+        }
+        let contents = serde_json::to_string(entries).context("Failed to serialize DID cache")?;
+        fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write DID cache: {:?}", self.path))
+    }
+}
+
+/// Document for a DID bound in the DNA
+///
+/// Lists the binding's identity key for signing, its X25519 form for
+/// encryption and the agent key mail is delivered to.
+pub fn binding_document(binding: &DidBinding) -> Result<DidDocument> {
+    let key = crypto::parse_verifying_key(&binding.public_key)
+        .with_context(|| format!("Binding for {} carries an invalid key", binding.did))?;
+
+    let mut document = ed25519_document(&binding.did, &key, "key-1", "key-agreement-1");
+    document.service.push(Service {
+        id: format!("{}#agent", binding.did),
+        service_type: HOLOCHAIN_AGENT_SERVICE.to_string(),
+        service_endpoint: binding.agent_pub_key.clone().into(),
+    });
+
+    Ok(document)
+}
+
+/// Document for a DID from its registry record
+///
+/// Only the identity key is used, and only once it is checked against the
+/// DID, so a registry cannot substitute its own key.
+fn registry_document(resolution: &DidResolution) -> Result<DidDocument> {
+    let public_key = resolution
+        .public_key
+        .as_deref()
+        .with_context(|| format!("Registry lists no key for {}", resolution.did))?;
+    let key = crypto::parse_verifying_key(public_key)
+        .with_context(|| format!("Registry lists an invalid key for {}", resolution.did))?;
+    crypto::verify_did_key(&resolution.did, &key)?;
+
+    Ok(ed25519_document(&resolution.did, &key, "key-1", "key-agreement-1"))
+}
+
+/// Document for a DID controlled by a single Ed25519 key
+fn ed25519_document(did: &str, key: &VerifyingKey, signing_id: &str, agreement_id: &str) -> DidDocument {
+    let signing_id = format!("{}#{}", did, signing_id);
+
+    DidDocument {
+        context: serde_json::json!([DID_CONTEXT, ED25519_CONTEXT, X25519_CONTEXT]),
+        id: did.to_string(),
+        also_known_as: vec![],
+        verification_method: vec![VerificationMethod {
+            id: signing_id.clone(),
+            method_type: "Ed25519VerificationKey2020".to_string(),
+            controller: did.to_string(),
+            public_key_multibase: Some(encode_multikey(ED25519_CODEC, key.as_bytes())),
+        }],
+        authentication: vec![VerificationRelationship::Reference(signing_id.clone())],
+        assertion_method: vec![VerificationRelationship::Reference(signing_id)],
+        key_agreement: vec![VerificationRelationship::Embedded(VerificationMethod {
+            id: format!("{}#{}", did, agreement_id),
+            method_type: "X25519KeyAgreementKey2020".to_string(),
+            controller: did.to_string(),
+            public_key_multibase: Some(encode_multikey(
+                X25519_CODEC,
+                crypto::x25519_public(key).as_bytes(),
+            )),
+        })],
+        service: vec![],
+    }
+}
+
+/// The key to encrypt mail for a document's subject to
+///
+/// Prefers a `keyAgreement` X25519 key; documents without one fall back to
+/// their first Ed25519 key, converted the same way our own keys are.
+pub fn encryption_key(document: &DidDocument) -> Result<PublicKey> {
+    for relationship in &document.key_agreement {
+        let key = document
+            .method(relationship)
+            .and_then(|method| method.public_key_multibase.as_deref())
+            .and_then(|multibase| decode_multikey(multibase, X25519_CODEC));
+        if let Some(key) = key {
+            return Ok(PublicKey::from(key));
+        }
+    }
+
+    for method in &document.verification_method {
+        let key = method
+            .public_key_multibase
+            .as_deref()
+            .and_then(|multibase| decode_multikey(multibase, ED25519_CODEC));
+        if let Some(key) = key {
+            let key = VerifyingKey::from_bytes(&key)
+                .with_context(|| format!("{} lists an invalid Ed25519 key", document.id))?;
+            return Ok(crypto::x25519_public(&key));
+        }
+    }
+
+    bail!("{} publishes no key to encrypt mail to", document.id)
+}
+
+/// The DID a `did:web` subject receives mail under on the DHT
+///
+/// The first `did:mycelix` or `did:key` identifier in `alsoKnownAs`.
+pub fn delivery_did(document: &DidDocument) -> Option<&str> {
+    document
+        .also_known_as
+        .iter()
+        .map(String::as_str)
+        .find(|did| did.starts_with("did:mycelix:") || did.starts_with("did:key:"))
+}
+
+/// `did:key` identifier for an Ed25519 key
+pub fn did_key(key: &VerifyingKey) -> String {
+    format!("did:key:{}", encode_multikey(ED25519_CODEC, key.as_bytes()))
+}
+
+/// The Ed25519 key a `did:key` identifier encodes
+fn did_key_public_key(did: &str) -> Result<VerifyingKey> {
+    let multibase = did.strip_prefix("did:key:").context("Not a did:key identifier")?;
+    let key = decode_multikey(multibase, ED25519_CODEC)
+        .with_context(|| format!("{} does not encode an Ed25519 key", did))?;
+    VerifyingKey::from_bytes(&key).with_context(|| format!("{} encodes an invalid key", did))
+}
+
+/// Where a `did:web` identifier's document lives
+///
+/// `did:web:example.com` is `https://example.com/.well-known/did.json`;
+/// further colon-separated parts are path segments, and a port is written
+/// as `%3A`.
+pub fn did_web_url(did: &str) -> Result<String> {
+    let rest = did.strip_prefix("did:web:").context("Not a did:web identifier")?;
+    let mut parts = rest.split(':');
+    let domain = parts.next().unwrap_or_default().replace("%3A", ":");
+    if domain.is_empty() {
+        bail!("{} does not name a domain", did);
+    }
+
+    let path: Vec<&str> = parts.collect();
+    if path.is_empty() {
+        Ok(format!("https://{}/.well-known/did.json", domain))
+    } else {
+        Ok(format!("https://{}/{}/did.json", domain, path.join("/")))
+    }
+}
+
+/// Base58btc multibase (`z...`) of a multicodec-prefixed key
+fn encode_multikey(codec: [u8; 2], key: &[u8]) -> String {
+    let mut bytes = codec.to_vec();
+    bytes.extend_from_slice(key);
+    format!("z{}", bs58::encode(bytes).into_string())
+}
+
+/// The 32-byte key in a multibase string, if it has the expected codec
+fn decode_multikey(multibase: &str, codec: [u8; 2]) -> Option<[u8; 32]> {
+    let bytes = bs58::decode(multibase.strip_prefix('z')?).into_vec().ok()?;
+    bytes.strip_prefix(&codec)?.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+
+    fn key() -> VerifyingKey {
+        SigningKey::from_bytes(&[9u8; 32]).verifying_key()
+    }
+
+    /// Resolver for `did:mycelix` that always gives the same answer
+    struct Fixed {
+        cacheable: bool,
+        answer: fn(&str) -> Result<Option<DidDocument>>,
+    }
+
+    #[async_trait]
+    impl DidResolver for Fixed {
+        fn supports(&self, did: &str) -> bool {
+            did.starts_with("did:mycelix:")
+        }
+
+        async fn resolve(&self, did: &str) -> Result<Option<DidDocument>> {
+            (self.answer)(did)
+        }
+
+        fn cacheable(&self) -> bool {
+            self.cacheable
+        }
+    }
+
+    fn document(did: &str) -> Result<Option<DidDocument>> {
+        Ok(Some(ed25519_document(did, &key(), "key-1", "key-agreement-1")))
+    }
+
+    fn chain(dir: &tempfile::TempDir, dht: fn(&str) -> Result<Option<DidDocument>>) -> ResolverChain {
+        ResolverChain::new(
+            vec![
+                Box::new(Fixed { cacheable: false, answer: dht }),
+                Box::new(Fixed { cacheable: true, answer: document }),
+            ],
+            DidCache::new(dir.path().join("dids.json"), 3600),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_did_key_document() {
+        let did = did_key(&key());
+        assert!(did.starts_with("did:key:z6Mk"));
+
+        let document = KeyResolver.resolve(&did).await.unwrap().unwrap();
+        assert_eq!(document.id, did);
+        assert_eq!(
+            encryption_key(&document).unwrap().as_bytes(),
+            crypto::x25519_public(&key()).as_bytes()
+        );
+
+        assert!(KeyResolver.resolve("did:key:zNotAKey").await.is_err());
+    }
+
+    #[test]
+    fn test_binding_document() {
+        let binding = DidBinding {
+            did: "did:mycelix:abc".to_string(),
+            agent_pub_key: "uhCAkAgent".to_string(),
+            public_key: hex::encode(key().as_bytes()),
+            binding_id: "uhCkkBinding".to_string(),
+            bound_at: 0,
+            rotations: 0,
+            revoked: None,
+        };
+
+        let document = binding_document(&binding).unwrap();
+        assert_eq!(document.service_endpoint(HOLOCHAIN_AGENT_SERVICE), Some("uhCAkAgent"));
+
+        let json = serde_json::to_value(&document).unwrap();
+        assert_eq!(json["@context"][0], DID_CONTEXT);
+        assert_eq!(json["verificationMethod"][0]["id"], "did:mycelix:abc#key-1");
+        assert_eq!(json["authentication"][0], "did:mycelix:abc#key-1");
+        assert_eq!(json["keyAgreement"][0]["type"], "X25519KeyAgreementKey2020");
+    }
+
+    #[test]
+    fn test_encryption_key_falls_back_to_ed25519() {
+        let mut document = ed25519_document("did:web:example.com", &key(), "key-1", "key-2");
+        document.key_agreement.clear();
+        assert_eq!(
+            encryption_key(&document).unwrap().as_bytes(),
+            crypto::x25519_public(&key()).as_bytes()
+        );
+
+        document.verification_method.clear();
+        assert!(encryption_key(&document).is_err());
+    }
+
+    #[test]
+    fn test_delivery_did() {
+        let mut document = ed25519_document("did:web:example.com", &key(), "key-1", "key-2");
+        assert_eq!(delivery_did(&document), None);
+
+        document.also_known_as = vec![
+            "https://example.com/alice".to_string(),
+            "did:mycelix:abc".to_string(),
+        ];
+        assert_eq!(delivery_did(&document), Some("did:mycelix:abc"));
+    }
+
+    #[test]
+    fn test_cache_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dids.json");
+        let cache = DidCache::new(path.clone(), 3600);
+        let doc = document("did:mycelix:abc").unwrap().unwrap();
+
+        assert!(cache.get("did:mycelix:abc").is_none());
+        cache.put("did:mycelix:abc", &doc).unwrap();
+        assert_eq!(cache.get("did:mycelix:abc"), Some(doc));
+        assert!(DidCache::new(path, 0).get("did:mycelix:abc").is_none());
+
+        cache.remove("did:mycelix:abc").unwrap();
+        assert!(cache.get("did:mycelix:abc").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chain_falls_back_and_caches() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = chain(&dir, |_| bail!("conductor unreachable"));
+
+        assert!(chain.resolve("did:mycelix:abc").await.unwrap().is_some());
+        assert!(chain.cache.get("did:mycelix:abc").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_chain_reports_unresolvable() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain = ResolverChain::new(
+            vec![
+                Box::new(Fixed { cacheable: false, answer: |_| Ok(None) }),
+                Box::new(Fixed { cacheable: true, answer: |_| bail!("registry down") }),
+            ],
+            DidCache::new(dir.path().join("dids.json"), 3600),
+        );
+
+        let err = chain.resolve("did:mycelix:abc").await.unwrap_err();
+        assert!(err.to_string().contains("unresolvable"));
+        assert!(err.to_string().contains("registry down"));
+        assert!(chain.resolve("did:example:abc").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dht_answer_replaces_cached_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let stale = document("did:mycelix:abc").unwrap().unwrap();
+
+        let fresh = chain(&dir, document);
+        fresh.cache.put("did:mycelix:abc", &stale).unwrap();
+        fresh.resolve("did:mycelix:abc").await.unwrap();
+        assert!(fresh.cache.get("did:mycelix:abc").is_none());
+
+        let revoked = chain(&dir, |did| Err(DidRevoked { did: did.to_string() }.into()));
+        revoked.cache.put("did:mycelix:abc", &stale).unwrap();
+        let err = revoked.resolve("did:mycelix:abc").await.unwrap_err();
+        assert!(err.is::<DidRevoked>());
+        assert!(revoked.cache.get("did:mycelix:abc").is_none());
+    }
+
+    #[test]
+    fn test_did_web_url() {
+        assert_eq!(
+            did_web_url("did:web:example.com").unwrap(),
+            "https://example.com/.well-known/did.json"
+        );
+        assert_eq!(
+            did_web_url("did:web:example.com%3A8443:users:alice").unwrap(),
+            "https://example.com:8443/users/alice/did.json"
+        );
+        assert!(did_web_url("did:web:").is_err());
+        assert!(did_web_url("did:key:z6Mk").is_err());
+    }
+}
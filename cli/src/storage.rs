@@ -0,0 +1,161 @@
+//! Encrypted content storage
+//!
+//! Message bodies are encrypted under the message's content key (see
+//! [`crate::crypto`]) and stored on the DHT as
+//! `EncryptedBlob` entries. Each blob is addressed by a CIDv1 (raw codec,
+//! sha2-256) over its ciphertext, which the integrity zome re-checks on
+//! write and the CLI re-checks on read, so a tampered or truncated blob is
+//! rejected before decryption is attempted.
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::attachments::AttachmentRef;
+use crate::crypto::{self, ContentKey};
+
+pub use mycelix_mail_cid::compute_cid;
+
+/// Multibase prefix (`b`) + base32 of CIDv1/raw/sha2-256
+const CID_PREFIX: &str = "bafkrei";
+
+/// Plaintext encrypted inside a message's body blob
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MessageContent {
+    /// Message text
+    pub body: String,
+    /// Attachments stored as separate encrypted chunks
+    #[serde(default)]
+    pub attachments: Vec<AttachmentRef>,
+}
+
+impl MessageContent {
+    pub fn text(body: &str) -> Self {
+        Self {
+            body: body.to_string(),
+            attachments: Vec::new(),
+        }
+    }
+}
+
+/// Check that `cid` looks like a CID produced by [`compute_cid`]
+pub fn is_valid_cid(cid: &str) -> bool {
+    cid.starts_with(CID_PREFIX)
+        && cid.len() == 59
+        && cid[1..].chars().all(|c| matches!(c, 'a'..='z' | '2'..='7'))
+}
+
+/// Verify fetched content against the CID it was requested by
+pub fn verify_cid(cid: &str, bytes: &[u8]) -> Result<()> {
+    let actual = compute_cid(bytes);
+    if actual != cid {
+        bail!(
+            "Content integrity check failed: expected {}, got {}",
+            cid,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Encrypt message content under the content key, returning `(cid, ciphertext)`
+pub fn encrypt_body(content: &MessageContent, key: &ContentKey) -> Result<(String, Vec<u8>)> {
+    let plaintext = serde_json::to_vec(content).context("Failed to serialize message body")?;
+    let ciphertext = crypto::encrypt_with_key(key, &plaintext)?;
+    let cid = compute_cid(&ciphertext);
+    Ok((cid, ciphertext))
+}
+
+/// Verify and decrypt message content fetched from the DHT
+///
+/// Bodies that are bare UTF-8 text rather than JSON content are returned
+/// as content without attachments.
+pub fn decrypt_body(cid: &str, ciphertext: &[u8], key: &ContentKey) -> Result<MessageContent> {
+    verify_cid(cid, ciphertext)?;
+    let plaintext = crypto::decrypt_with_key(key, ciphertext)
+        .context("Message body could not be decrypted with this message's key")?;
+
+    if let Ok(content) = serde_json::from_slice::<MessageContent>(&plaintext) {
+        return Ok(content);
+    }
+    let body = String::from_utf8(plaintext).context("Message body is not valid UTF-8")?;
+    Ok(MessageContent::text(&body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_cid_known_vectors() {
+        // Same vectors as mycelix_mail_cid, checked against our own format check
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        let vectors: [(&[u8], &str); 4] = [
+            (
+                b"",
+                "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku",
+            ),
+            (
+                b"hello world",
+                "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e",
+            ),
+            (
+                b"Test message body",
+                "bafkreieuu36btf32w4uhkp4rn42gkppbme5xacdt2n6yayhtl3yblgukwi",
+            ),
+            (
+                all_bytes.as_slice(),
+                "bafkreicav7zotuwysixepl6umshgsz2jofmhqx55dwuhbzyrajtl7fciqa",
+            ),
+        ];
+        for (bytes, expected) in vectors {
+            assert_eq!(compute_cid(bytes), expected);
+            assert!(is_valid_cid(expected));
+        }
+    }
+
+    #[test]
+    fn test_cid_format() {
+        let cid = compute_cid(b"Test message body");
+        assert!(is_valid_cid(&cid));
+        assert_eq!(cid, compute_cid(b"Test message body"));
+        assert_ne!(cid, compute_cid(b"Another body"));
+
+        assert!(!is_valid_cid("invalid-cid-format"));
+        assert!(!is_valid_cid("bafyrei1234567890abcdef"));
+    }
+
+    #[test]
+    fn test_body_roundtrip() {
+        let key = crypto::generate_content_key();
+
+        let content = MessageContent::text("Hello from the mycelium");
+        let (cid, ciphertext) = encrypt_body(&content, &key).unwrap();
+        assert!(is_valid_cid(&cid));
+
+        let decrypted = decrypt_body(&cid, &ciphertext, &key).unwrap();
+        assert_eq!(decrypted, content);
+    }
+
+    #[test]
+    fn test_plain_text_body_still_readable() {
+        let key = crypto::generate_content_key();
+
+        let ciphertext = crypto::encrypt_with_key(&key, b"Legacy body").unwrap();
+        let cid = compute_cid(&ciphertext);
+
+        let decrypted = decrypt_body(&cid, &ciphertext, &key).unwrap();
+        assert_eq!(decrypted.body, "Legacy body");
+        assert!(decrypted.attachments.is_empty());
+    }
+
+    #[test]
+    fn test_tampered_body_rejected() {
+        let key = crypto::generate_content_key();
+
+        let (cid, mut ciphertext) = encrypt_body(&MessageContent::text("Original"), &key).unwrap();
+        ciphertext[0] ^= 0xff;
+
+        let err = decrypt_body(&cid, &ciphertext, &key).unwrap_err();
+        assert!(err.to_string().contains("integrity"));
+    }
+}
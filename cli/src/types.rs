@@ -53,11 +53,17 @@ impl std::fmt::Display for EpistemicTier {
 /// Mail message structure (matches DNA entry type)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MailMessage {
+    /// Message ID (base64 action hash of the DHT entry)
+    #[serde(default)]
+    pub id: String,
     /// Sender's DID
     pub from_did: String,
-    /// Recipient's DID
-    pub to_did: String,
-    /// Encrypted subject (bytes)
+    /// Primary recipients' DIDs
+    pub to_dids: Vec<String>,
+    /// Carbon-copy recipients' DIDs (BCC recipients are never listed)
+    #[serde(default)]
+    pub cc_dids: Vec<String>,
+    /// Subject encrypted under the message's content key
     pub subject_encrypted: Vec<u8>,
     /// IPFS CID for message body
     pub body_cid: String,
@@ -67,6 +73,126 @@ pub struct MailMessage {
     pub thread_id: Option<String>,
     /// Epistemic tier classification
     pub epistemic_tier: EpistemicTier,
+    /// Content key sealed for each recipient and the sender
+    #[serde(default)]
+    pub recipient_keys: Vec<Vec<u8>>,
+    /// Unix timestamp after which the message is hidden and cleaned up
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+    /// Our private read/starred/archived state (not part of the DHT entry)
+    #[serde(default)]
+    pub state: MessageState,
+}
+
+/// New-mail notification pushed by the conductor
+///
+/// Unverified: check it against the message once that can be fetched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewMail {
+    pub message_id: String,
+    /// Sender DID as claimed in the signal
+    pub claimed_from: String,
+    /// Agent key that sent the signal
+    pub provenance: String,
+}
+
+/// Time range and page of a mailbox listing
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageQuery {
+    /// Oldest timestamp to include (Unix seconds)
+    pub since: Option<i64>,
+    /// Newest timestamp to include (Unix seconds)
+    pub until: Option<i64>,
+    /// Page size
+    pub limit: usize,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+}
+
+/// One page of messages, newest first
+#[derive(Debug, Clone)]
+pub struct MessagePage {
+    pub messages: Vec<MailMessage>,
+    /// Cursor for the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// A message held back by the spam filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedMessage {
+    pub message: MailMessage,
+    /// Sender's trust score when it was filtered
+    pub trust_score: f64,
+    /// Why the message was held back
+    pub reason: String,
+    /// Pattern of the block rule that caught the sender, if any
+    pub blocked_by: Option<String>,
+}
+
+/// Private per-user state of a message
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct MessageState {
+    pub read: bool,
+    pub starred: bool,
+    pub archived: bool,
+    pub labels: Vec<String>,
+}
+
+/// Change to a message's state; `None` leaves a flag unchanged
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageStateUpdate {
+    pub read: Option<bool>,
+    pub starred: Option<bool>,
+    pub archived: Option<bool>,
+    pub add_labels: Vec<String>,
+    pub remove_labels: Vec<String>,
+}
+
+impl MessageStateUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.read.is_none()
+            && self.starred.is_none()
+            && self.archived.is_none()
+            && self.add_labels.is_empty()
+            && self.remove_labels.is_empty()
+    }
+}
+
+impl MailMessage {
+    /// Recipients for display, e.g. `did:a, did:b (cc: did:c)`
+    pub fn recipients_display(&self) -> String {
+        let to = self.to_dids.join(", ");
+        if self.cc_dids.is_empty() {
+            to
+        } else {
+            format!("{} (cc: {})", to, self.cc_dids.join(", "))
+        }
+    }
+
+    /// Whether `did` is listed as a to or cc recipient
+    pub fn lists_recipient(&self, did: &str) -> bool {
+        self.to_dids.iter().chain(&self.cc_dids).any(|r| r == did)
+    }
+}
+
+/// Recipient lists for an outgoing message
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Recipients {
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    /// Delivered to, but never written into the message
+    pub bcc: Vec<String>,
+}
+
+impl Recipients {
+    /// Every recipient, to then cc then bcc
+    pub fn all(&self) -> impl Iterator<Item = &String> {
+        self.to.iter().chain(&self.cc).chain(&self.bcc)
+    }
+
+    pub fn len(&self) -> usize {
+        self.to.len() + self.cc.len() + self.bcc.len()
+    }
 }
 
 /// Trust score structure (matches DNA entry type)
@@ -82,6 +208,148 @@ pub struct TrustScore {
     pub source: String,
 }
 
+/// One source's contribution to a DID's trust score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceTrust {
+    /// Source name (matl, manual, local, ...)
+    pub source: String,
+    /// The source's own score (0.0 - 1.0)
+    pub score: f64,
+    /// Configured weight of the source
+    pub weight: f64,
+    /// How far the score can be relied on (0.0 - 1.0)
+    pub confidence: f64,
+    /// Fraction of the final score this source accounts for
+    pub share: f64,
+    /// Last updated timestamp, for published scores
+    pub last_updated: Option<i64>,
+    /// Evidence behind a computed score
+    pub detail: Option<String>,
+}
+
+/// Final trust score for a DID with its per-source breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustBreakdown {
+    pub did: String,
+    pub score: f64,
+    pub sources: Vec<SourceTrust>,
+}
+
+/// Whether a sender rule lets mail through or holds it back
+/// (matches DNA `RuleAction`)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RuleAction {
+    Allow,
+    Block,
+}
+
+/// Private allow/block rule for senders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SenderRule {
+    /// Exact DID or DID prefix ending in `*`
+    pub pattern: String,
+    pub action: RuleAction,
+    /// Creation timestamp
+    pub created_at: i64,
+}
+
+/// Where a DID currently points on the DHT, after any key rotations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DidBinding {
+    pub did: String,
+    pub agent_pub_key: String,
+    /// Hex-encoded Ed25519 identity key that signed the binding
+    pub public_key: String,
+    /// Action ID of the binding in force
+    pub binding_id: String,
+    /// When the current key took the DID over
+    pub bound_at: i64,
+    /// Handovers since the DID was first registered
+    pub rotations: u32,
+    /// Revocation reason, if the DID was revoked
+    pub revoked: Option<String>,
+}
+
+/// W3C DID document (https://www.w3.org/TR/did-core/)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DidDocument {
+    /// JSON-LD context; a string or list of strings
+    #[serde(rename = "@context")]
+    pub context: serde_json::Value,
+    pub id: String,
+    /// Other DIDs for the same subject
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub also_known_as: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub verification_method: Vec<VerificationMethod>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub authentication: Vec<VerificationRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertion_method: Vec<VerificationRelationship>,
+    /// Keys mail for this DID is encrypted to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub key_agreement: Vec<VerificationRelationship>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub service: Vec<Service>,
+}
+
+impl DidDocument {
+    /// The verification method a relationship embeds or refers to
+    ///
+    /// References may be absolute (`did:...#key-1`) or relative (`#key-1`).
+    pub fn method<'a>(&'a self, relationship: &'a VerificationRelationship) -> Option<&'a VerificationMethod> {
+        match relationship {
+            VerificationRelationship::Embedded(method) => Some(method),
+            VerificationRelationship::Reference(id) => self
+                .verification_method
+                .iter()
+                .find(|method| method.id == *id || method.id == format!("{}{}", self.id, id)),
+        }
+    }
+
+    /// Endpoint of the first service of `service_type`, if it is a string
+    pub fn service_endpoint(&self, service_type: &str) -> Option<&str> {
+        self.service
+            .iter()
+            .find(|service| service.service_type == service_type)
+            .and_then(|service| service.service_endpoint.as_str())
+    }
+}
+
+/// Public key listed in a DID document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationMethod {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub method_type: String,
+    pub controller: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key_multibase: Option<String>,
+}
+
+/// Entry of a verification relationship such as `keyAgreement`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VerificationRelationship {
+    /// ID of a method in `verificationMethod`
+    Reference(String),
+    /// Method defined only for this relationship
+    Embedded(VerificationMethod),
+}
+
+/// Service endpoint in a DID document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Service {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub service_type: String,
+    /// A URI, or a map or list of them
+    pub service_endpoint: serde_json::Value,
+}
+
 /// Contact entry structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Contact {
@@ -89,8 +357,8 @@ pub struct Contact {
     pub did: String,
     /// Display name
     pub name: String,
-    /// Optional email
-    pub email: Option<String>,
+    /// Optional email-style alias usable in place of the DID
+    pub email_alias: Option<String>,
     /// Optional notes
     pub notes: Option<String>,
     /// Trust score
@@ -121,7 +389,11 @@ pub struct DidRegistration {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DidResolution {
     pub did: String,
+    #[serde(alias = "agent_pubkey")]
     pub agent_pub_key: String,
+    /// Hex-encoded Ed25519 identity key (used to derive the X25519 encryption key)
+    #[serde(default)]
+    pub public_key: Option<String>,
     pub created_at: i64,
     pub updated_at: i64,
 }
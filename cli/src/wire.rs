@@ -0,0 +1,496 @@
+//! Wire types mirroring the DNA's entry and zome IO structs
+//!
+//! The CLI-facing types in `types.rs` use plain strings and Unix seconds for
+//! display; these structs match the exact shape the zomes (de)serialize so
+//! they can be sent over the conductor websocket unchanged.
+
+use anyhow::{Context, Result};
+use holochain_types::prelude::{ActionHash, ActionHashB64, AgentPubKey, AgentPubKeyB64, Signature, Timestamp};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{self, EpistemicTier, RuleAction};
+
+/// `mycelix_mail_integrity::MailMessage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailMessage {
+    pub from_did: String,
+    pub to_dids: Vec<String>,
+    pub cc_dids: Vec<String>,
+    pub subject_encrypted: Vec<u8>,
+    pub body_cid: String,
+    pub timestamp: Timestamp,
+    pub thread_id: Option<String>,
+    pub epistemic_tier: EpistemicTier,
+    pub recipient_keys: Vec<Vec<u8>>,
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+}
+
+/// `mail_messages::SendMessageInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendMessageInput {
+    pub message: MailMessage,
+    pub bcc_copies: Vec<BccCopy>,
+}
+
+/// `mail_messages::BccCopy`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BccCopy {
+    pub did: String,
+    pub subject_encrypted: Vec<u8>,
+    pub body_cid: String,
+    pub recipient_keys: Vec<Vec<u8>>,
+}
+
+/// `mycelix_mail_integrity::MailItem`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailItem {
+    pub message_hash: ActionHash,
+    pub message: MailMessage,
+}
+
+/// `mail_messages::MailSignal`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MailSignal {
+    NewMail {
+        message_hash: ActionHash,
+        from_did: String,
+    },
+    NewMailClaim {
+        message_hash: ActionHash,
+        from_did: String,
+        provenance: AgentPubKey,
+    },
+}
+
+/// `mycelix_mail_integrity::MailboxQuery`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MailboxQuery {
+    pub since: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+/// `mycelix_mail_integrity::MailPage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailPage {
+    pub items: Vec<MailItem>,
+    pub next_cursor: Option<String>,
+}
+
+/// `trust_filter::FilterInboxPageInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterInboxPageInput {
+    pub min_trust: f64,
+    pub query: MailboxQuery,
+}
+
+/// `trust_filter::ScreenMessageInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenMessageInput {
+    pub message_hash: ActionHash,
+    pub min_trust: f64,
+}
+
+/// `trust_filter::QuarantinedMail`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedMail {
+    pub item: MailItem,
+    pub trust_score: f64,
+    pub reason: String,
+    pub blocked_by: Option<String>,
+}
+
+/// `mycelix_mail_integrity::QuarantineRelease`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantineRelease {
+    pub message_hash: ActionHash,
+    pub sender_did: String,
+    pub released_at: Timestamp,
+}
+
+/// `mycelix_mail_integrity::SenderRule`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SenderRule {
+    pub pattern: String,
+    pub action: RuleAction,
+    pub created_at: Timestamp,
+}
+
+/// `trust_filter::SetSenderRuleInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetSenderRuleInput {
+    pub pattern: String,
+    pub action: RuleAction,
+}
+
+/// `mycelix_mail_integrity::DidRecord`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DidRecord {
+    pub did: String,
+    pub agent_pub_key: AgentPubKey,
+    pub public_key: Vec<u8>,
+    pub binding: ActionHash,
+    pub bound_at: Timestamp,
+    pub rotations: u32,
+    pub revoked: Option<String>,
+}
+
+/// `mail_messages::RegisterDidInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterDidInput {
+    pub did: String,
+    pub public_key: Vec<u8>,
+    pub signature: Signature,
+}
+
+/// `mail_messages::AcceptDidRotationInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptDidRotationInput {
+    pub rotation_hash: ActionHash,
+    pub public_key: Vec<u8>,
+    pub signature: Signature,
+}
+
+/// `mail_messages::RotateDidInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RotateDidInput {
+    pub did: String,
+    pub new_agent_pub_key: AgentPubKey,
+}
+
+/// `mail_messages::RevokeDidInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevokeDidInput {
+    pub did: String,
+    pub reason: String,
+}
+
+/// `mycelix_mail_integrity::EncryptedBlob`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedBlob {
+    pub cid: String,
+    pub ciphertext: Vec<u8>,
+}
+
+/// `mycelix_mail_integrity::MessageState`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageState {
+    pub message_hash: ActionHash,
+    pub read: bool,
+    pub starred: bool,
+    pub archived: bool,
+    pub labels: Vec<String>,
+    pub updated_at: Timestamp,
+}
+
+/// `mail_messages::MessageStateUpdate`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageStateUpdate {
+    pub message_hash: ActionHash,
+    pub read: Option<bool>,
+    pub starred: Option<bool>,
+    pub archived: Option<bool>,
+    pub add_labels: Vec<String>,
+    pub remove_labels: Vec<String>,
+}
+
+/// `mycelix_mail_integrity::Contact`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Contact {
+    pub name: String,
+    pub did: String,
+    pub email_alias: Option<String>,
+    pub notes: Option<String>,
+    pub added_at: Timestamp,
+}
+
+/// `contacts::AddContactInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddContactInput {
+    pub name: String,
+    pub did: String,
+    pub email_alias: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// `contacts::UpdateContactInput`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateContactInput {
+    pub did: String,
+    pub name: Option<String>,
+    pub email_alias: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// `mycelix_mail_integrity::TrustScore`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustScore {
+    pub did: String,
+    pub score: f64,
+    pub last_updated: Timestamp,
+    pub matl_source: String,
+}
+
+/// `trust_filter::SourceTrust`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceTrust {
+    pub source: String,
+    pub score: f64,
+    pub weight: f64,
+    pub confidence: f64,
+    pub share: f64,
+    pub last_updated: Option<Timestamp>,
+    pub detail: Option<String>,
+}
+
+/// `trust_filter::TrustBreakdown`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustBreakdown {
+    pub did: String,
+    pub score: f64,
+    pub sources: Vec<SourceTrust>,
+}
+
+impl MailMessage {
+    /// Convert into the CLI display type, attaching the message ID
+    pub fn into_cli(self, id: String) -> types::MailMessage {
+        types::MailMessage {
+            id,
+            from_did: self.from_did,
+            to_dids: self.to_dids,
+            cc_dids: self.cc_dids,
+            subject_encrypted: self.subject_encrypted,
+            body_cid: self.body_cid,
+            timestamp: to_unix_seconds(self.timestamp),
+            thread_id: self.thread_id,
+            epistemic_tier: self.epistemic_tier,
+            recipient_keys: self.recipient_keys,
+            expires_at: self.expires_at.map(to_unix_seconds),
+            state: types::MessageState::default(),
+        }
+    }
+}
+
+impl From<MailItem> for types::MailMessage {
+    fn from(item: MailItem) -> Self {
+        item.message.into_cli(encode_hash(&item.message_hash))
+    }
+}
+
+impl MailSignal {
+    /// The new-mail claim our zome forwards to clients, if this is one
+    pub fn into_new_mail(self) -> Option<types::NewMail> {
+        match self {
+            MailSignal::NewMailClaim {
+                message_hash,
+                from_did,
+                provenance,
+            } => Some(types::NewMail {
+                message_id: encode_hash(&message_hash),
+                claimed_from: from_did,
+                provenance: encode_agent_key(&provenance),
+            }),
+            MailSignal::NewMail { .. } => None,
+        }
+    }
+}
+
+impl From<&types::PageQuery> for MailboxQuery {
+    fn from(query: &types::PageQuery) -> Self {
+        MailboxQuery {
+            since: query.since.map(from_unix_seconds),
+            until: query.until.map(from_unix_seconds),
+            limit: Some(query.limit),
+            cursor: query.cursor.clone(),
+        }
+    }
+}
+
+impl From<QuarantinedMail> for types::QuarantinedMessage {
+    fn from(quarantined: QuarantinedMail) -> Self {
+        types::QuarantinedMessage {
+            message: quarantined.item.into(),
+            trust_score: quarantined.trust_score,
+            reason: quarantined.reason,
+            blocked_by: quarantined.blocked_by,
+        }
+    }
+}
+
+impl From<SenderRule> for types::SenderRule {
+    fn from(rule: SenderRule) -> Self {
+        types::SenderRule {
+            pattern: rule.pattern,
+            action: rule.action,
+            created_at: to_unix_seconds(rule.created_at),
+        }
+    }
+}
+
+impl From<DidRecord> for types::DidBinding {
+    fn from(record: DidRecord) -> Self {
+        types::DidBinding {
+            did: record.did,
+            agent_pub_key: encode_agent_key(&record.agent_pub_key),
+            public_key: hex::encode(&record.public_key),
+            binding_id: encode_hash(&record.binding),
+            bound_at: to_unix_seconds(record.bound_at),
+            rotations: record.rotations,
+            revoked: record.revoked,
+        }
+    }
+}
+
+impl From<MessageState> for types::MessageState {
+    fn from(state: MessageState) -> Self {
+        types::MessageState {
+            read: state.read,
+            starred: state.starred,
+            archived: state.archived,
+            labels: state.labels,
+        }
+    }
+}
+
+impl From<Contact> for types::Contact {
+    fn from(contact: Contact) -> Self {
+        Self {
+            did: contact.did,
+            name: contact.name,
+            email_alias: contact.email_alias,
+            notes: contact.notes,
+            trust_score: None,
+        }
+    }
+}
+
+impl From<TrustScore> for types::TrustScore {
+    fn from(score: TrustScore) -> Self {
+        types::TrustScore {
+            did: score.did,
+            score: score.score,
+            last_updated: to_unix_seconds(score.last_updated),
+            source: score.matl_source,
+        }
+    }
+}
+
+impl From<TrustBreakdown> for types::TrustBreakdown {
+    fn from(breakdown: TrustBreakdown) -> Self {
+        types::TrustBreakdown {
+            did: breakdown.did,
+            score: breakdown.score,
+            sources: breakdown
+                .sources
+                .into_iter()
+                .map(|source| types::SourceTrust {
+                    source: source.source,
+                    score: source.score,
+                    weight: source.weight,
+                    confidence: source.confidence,
+                    share: source.share,
+                    last_updated: source.last_updated.map(to_unix_seconds),
+                    detail: source.detail,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Holochain timestamp for Unix seconds
+pub fn from_unix_seconds(seconds: i64) -> Timestamp {
+    Timestamp::from_micros(seconds.saturating_mul(1_000_000))
+}
+
+/// Current time as a Holochain timestamp (microseconds)
+pub fn now() -> Timestamp {
+    Timestamp::from_micros(chrono::Utc::now().timestamp_micros())
+}
+
+/// Convert a Holochain timestamp (microseconds) to Unix seconds
+pub fn to_unix_seconds(ts: Timestamp) -> i64 {
+    ts.as_micros() / 1_000_000
+}
+
+/// Encode an action hash as the message ID shown to users
+pub fn encode_hash(hash: &ActionHash) -> String {
+    ActionHashB64::from(hash.clone()).to_string()
+}
+
+/// Parse a user-supplied message ID back into an action hash
+pub fn decode_hash(id: &str) -> Result<ActionHash> {
+    let b64 = ActionHashB64::from_b64_str(id.trim())
+        .map_err(|e| anyhow::anyhow!("{:?}", e))
+        .with_context(|| format!("Invalid message ID: {}", id))?;
+    Ok(b64.into())
+}
+
+/// Encode an agent key the way Holochain tools display it
+pub fn encode_agent_key(key: &AgentPubKey) -> String {
+    AgentPubKeyB64::from(key.clone()).to_string()
+}
+
+/// Parse a user-supplied agent key
+pub fn decode_agent_key(key: &str) -> Result<AgentPubKey> {
+    let b64 = AgentPubKeyB64::from_b64_str(key.trim())
+        .map_err(|e| anyhow::anyhow!("{:?}", e))
+        .with_context(|| format!("Invalid agent key: {}", key))?;
+    Ok(b64.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_unix_seconds() {
+        let ts = Timestamp::from_micros(1_609_459_200_123_456);
+        assert_eq!(to_unix_seconds(ts), 1_609_459_200);
+    }
+
+    #[test]
+    fn test_hash_roundtrip() {
+        let hash = ActionHash::from_raw_36(vec![7u8; 36]);
+        let id = encode_hash(&hash);
+        assert!(id.starts_with("uhCkk"));
+        assert_eq!(decode_hash(&id).unwrap(), hash);
+    }
+
+    #[test]
+    fn test_decode_hash_invalid() {
+        assert!(decode_hash("msg_stub_123").is_err());
+    }
+
+    #[test]
+    fn test_agent_key_roundtrip() {
+        let key = AgentPubKey::from_raw_36(vec![3u8; 36]);
+        let encoded = encode_agent_key(&key);
+        assert!(encoded.starts_with("uhCAk"));
+        assert_eq!(decode_agent_key(&encoded).unwrap(), key);
+        assert!(decode_agent_key(&encode_hash(&ActionHash::from_raw_36(vec![3u8; 36]))).is_err());
+    }
+
+    #[test]
+    fn test_only_forwarded_claims_become_notices() {
+        let message_hash = ActionHash::from_raw_36(vec![7u8; 36]);
+        let provenance = AgentPubKey::from_raw_36(vec![3u8; 36]);
+
+        let claim = MailSignal::NewMailClaim {
+            message_hash: message_hash.clone(),
+            from_did: "did:mycelix:alice".to_string(),
+            provenance: provenance.clone(),
+        };
+        let notice = claim.into_new_mail().unwrap();
+        assert_eq!(notice.message_id, encode_hash(&message_hash));
+        assert_eq!(notice.claimed_from, "did:mycelix:alice");
+        assert_eq!(notice.provenance, encode_agent_key(&provenance));
+
+        let raw = MailSignal::NewMail {
+            message_hash,
+            from_did: "did:mycelix:alice".to_string(),
+        };
+        assert!(raw.into_new_mail().is_none());
+    }
+}
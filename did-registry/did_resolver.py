@@ -72,6 +72,7 @@ class DIDRegistrationRequest(BaseModel):
     """Request model for DID registration"""
     did: str
     agent_pubkey: str
+    public_key: Optional[str] = None
     display_name: Optional[str] = None
     email_alias: Optional[str] = None
 
@@ -90,6 +91,18 @@ class DIDRegistrationRequest(BaseModel):
             raise ValueError('AgentPubKey must start with "uhCAk"')
         return v
 
+    @validator('public_key')
+    def validate_public_key_format(cls, v):
+        if v is None:
+            return v
+        try:
+            raw = bytes.fromhex(v)
+        except ValueError:
+            raise ValueError('public_key must be hex encoded')
+        if len(raw) != 32:
+            raise ValueError('public_key must be a 32-byte Ed25519 key')
+        return v
+
 
 class DIDUpdateRequest(BaseModel):
     """Request model for DID update"""
@@ -107,6 +120,7 @@ class DIDResolutionResponse(BaseModel):
     """Response model for successful DID resolution"""
     did: str
     agent_pubkey: str
+    public_key: Optional[str]
     display_name: Optional[str]
     last_seen: Optional[datetime]
     resolved_at: datetime
@@ -154,7 +168,7 @@ async def resolve_did_from_db(did: str, request_source: Optional[str] = None) ->
     async with pool.acquire() as conn:
         # Resolve DID
         row = await conn.fetchrow("""
-            SELECT did, agent_pubkey, display_name, last_seen
+            SELECT did, agent_pubkey, public_key, display_name, last_seen
             FROM did_registry
             WHERE did = $1 AND is_active = true
         """, did)
@@ -178,13 +192,14 @@ async def resolve_did_from_db(did: str, request_source: Optional[str] = None) ->
         return None
 
 
-async def register_did_in_db(did: str, agent_pubkey: str, display_name: Optional[str] = None, email_alias: Optional[str] = None) -> bool:
+async def register_did_in_db(did: str, agent_pubkey: str, public_key: Optional[str] = None, display_name: Optional[str] = None, email_alias: Optional[str] = None) -> bool:
     """
     Register a new DID in the database.
 
     Args:
         did: The DID to register
         agent_pubkey: The Holochain AgentPubKey
+        public_key: Optional hex-encoded Ed25519 key used for mail encryption
         display_name: Optional display name
         email_alias: Optional email alias
 
@@ -201,9 +216,9 @@ async def register_did_in_db(did: str, agent_pubkey: str, display_name: Optional
     async with pool.acquire() as conn:
         try:
             await conn.execute("""
-                INSERT INTO did_registry (did, agent_pubkey, did_method, did_method_specific_id, display_name, email_alias)
-                VALUES ($1, $2, $3, $4, $5, $6)
-            """, did, agent_pubkey, method, identifier, display_name, email_alias)
+                INSERT INTO did_registry (did, agent_pubkey, public_key, did_method, did_method_specific_id, display_name, email_alias)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+            """, did, agent_pubkey, public_key, method, identifier, display_name, email_alias)
 
             logger.info(f"Registered new DID: {did}")
             return True
@@ -314,6 +329,7 @@ async def resolve_did(did: str, request: Request):
         return DIDResolutionResponse(
             did=result['did'],
             agent_pubkey=result['agent_pubkey'],
+            public_key=result.get('public_key'),
             display_name=result.get('display_name'),
             last_seen=result.get('last_seen'),
             resolved_at=datetime.utcnow()
@@ -345,6 +361,7 @@ async def register_did(registration: DIDRegistrationRequest):
         success = await register_did_in_db(
             registration.did,
             registration.agent_pubkey,
+            registration.public_key,
             registration.display_name,
             registration.email_alias
         )
@@ -10,6 +10,7 @@ CREATE TABLE did_registry (
     id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
     did TEXT NOT NULL UNIQUE,
     agent_pubkey TEXT NOT NULL,
+    public_key TEXT,
     created_at TIMESTAMP NOT NULL DEFAULT NOW(),
     updated_at TIMESTAMP NOT NULL DEFAULT NOW(),
     last_seen TIMESTAMP,
@@ -153,4 +154,5 @@ COMMENT ON TABLE did_resolution_log IS 'Audit log of DID resolution requests';
 COMMENT ON TABLE did_update_history IS 'History of DID-to-AgentPubKey mappings changes';
 COMMENT ON COLUMN did_registry.did IS 'Fully-qualified DID (e.g., did:mycelix:alice)';
 COMMENT ON COLUMN did_registry.agent_pubkey IS 'Base64-encoded Holochain AgentPubKey';
+COMMENT ON COLUMN did_registry.public_key IS 'Hex-encoded Ed25519 identity key (X25519 mail encryption is derived from it)';
 COMMENT ON COLUMN did_registry.last_seen IS 'Last time this DID was successfully used';
@@ -0,0 +1,72 @@
+//! CIDv1 addressing of encrypted blobs
+//!
+//! Both the integrity zome and the CLI depend on this crate, so the
+//! addresses clients compute and the ones the DNA validates cannot drift
+//! apart. Keep it free of HDK types.
+
+use sha2::{Digest, Sha256};
+
+/// Compute the CIDv1 (raw codec, sha2-256 multihash, base32 multibase) of `bytes`
+pub fn compute_cid(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+
+    // version 1, raw codec (0x55), sha2-256 (0x12), 32-byte digest
+    let mut cid_bytes = vec![0x01, 0x55, 0x12, 0x20];
+    cid_bytes.extend_from_slice(&digest);
+
+    format!("b{}", base32_lower(&cid_bytes))
+}
+
+/// RFC 4648 base32, lowercase, no padding (multibase prefix `b`)
+fn base32_lower(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+    let mut out = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_cid_known_vectors() {
+        // CIDv1 (raw, sha2-256) vectors both the DNA and the CLI rely on
+        let all_bytes: Vec<u8> = (0..=255).collect();
+        let vectors: [(&[u8], &str); 4] = [
+            (
+                b"",
+                "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku",
+            ),
+            (
+                b"hello world",
+                "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e",
+            ),
+            (
+                b"Test message body",
+                "bafkreieuu36btf32w4uhkp4rn42gkppbme5xacdt2n6yayhtl3yblgukwi",
+            ),
+            (
+                all_bytes.as_slice(),
+                "bafkreicav7zotuwysixepl6umshgsz2jofmhqx55dwuhbzyrajtl7fciqa",
+            ),
+        ];
+        for (bytes, expected) in vectors {
+            assert_eq!(compute_cid(bytes), expected);
+        }
+    }
+}
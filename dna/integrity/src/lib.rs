@@ -1,17 +1,73 @@
 use hdk::prelude::*;
 use holochain_serialized_bytes::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+
+pub use mycelix_mail_cid::compute_cid;
 
 /// Core mail message entry type
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct MailMessage {
     pub from_did: String,
-    pub to_did: String,
+    pub to_dids: Vec<String>,
+    pub cc_dids: Vec<String>,
+    /// Subject encrypted under the message's content key
     pub subject_encrypted: Vec<u8>,
     pub body_cid: String, // IPFS content ID
     pub timestamp: Timestamp,
+    /// Parent message this replies to, encoded with [`encode_thread_id`]
     pub thread_id: Option<String>,
     pub epistemic_tier: EpistemicTier,
+    /// The content key sealed once per to/cc recipient (and once for the sender)
+    ///
+    /// Slots carry no DID and are shuffled. BCC recipients have no slot here;
+    /// each is delivered a copy sealed for just them and the sender.
+    pub recipient_keys: Vec<Vec<u8>>,
+    /// After this time clients hide the message and clean it up
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+}
+
+impl MailMessage {
+    /// Whether the message has passed its `expires_at`
+    pub fn is_expired(&self, now: &Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= *now)
+    }
+}
+
+/// A mail message paired with the action hash that created it
+///
+/// Returned by the mailbox queries so clients can address individual
+/// messages (read, delete, reply) without re-hashing entries.
+#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct MailItem {
+    pub message_hash: ActionHash,
+    pub message: MailMessage,
+}
+
+/// Time range and page of a mailbox query; every field is optional
+///
+/// `cursor` is the `next_cursor` of the previous page. Messages are
+/// returned newest first with `since <= timestamp <= until`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, SerializedBytes)]
+pub struct MailboxQuery {
+    #[serde(default)]
+    pub since: Option<Timestamp>,
+    #[serde(default)]
+    pub until: Option<Timestamp>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// One page of a mailbox query
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct MailPage {
+    pub items: Vec<MailItem>,
+    /// Pass back as `MailboxQuery::cursor` for the next page; `None` on the
+    /// last page
+    pub next_cursor: Option<String>,
 }
 
 /// Trust score for spam filtering
@@ -35,6 +91,9 @@ pub enum EpistemicTier {
 }
 
 /// Contact entry for address book
+///
+/// Contacts are private: they live only on the owner's source chain and the
+/// latest entry per DID wins.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct Contact {
@@ -46,24 +105,219 @@ pub struct Contact {
 }
 
 /// Mapping between DID and the agent pubkey that owns it
+///
+/// `signature` is the Ed25519 identity key in `public_key` signing the raw
+/// bytes of `agent_pub_key`, proving whoever binds the DID holds that key.
+/// The first binding for a DID has no `rotation` and its DID must be derived
+/// from `public_key` (see [`did_matches_key`]); every later one names the
+/// `DidRotation` through which the previous holder handed the DID over.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct DidBinding {
     pub did: String,
     pub agent_pub_key: AgentPubKey,
+    pub public_key: Vec<u8>,
+    pub signature: Signature,
+    #[serde(default)]
+    pub rotation: Option<ActionHash>,
+}
+
+/// Method prefix of the DIDs this DNA issues
+pub const DID_PREFIX: &str = "did:mycelix:";
+
+/// The `did:mycelix` identifier for an Ed25519 identity key
+///
+/// `did:mycelix:<base58(first 32 bytes of blake2b-512(public_key))>`, the same
+/// derivation `mycelix-mail init` uses.
+pub fn did_from_public_key(public_key: &[u8]) -> ExternResult<String> {
+    let hash = hash_blake2b(public_key.to_vec(), 64)?;
+    Ok(format!("{}{}", DID_PREFIX, bs58::encode(&hash[..32]).into_string()))
+}
+
+/// The `did:key` identifier for an Ed25519 identity key
+pub fn did_key_from_public_key(public_key: &[u8]) -> String {
+    let mut bytes = vec![0xed, 0x01];
+    bytes.extend_from_slice(public_key);
+    format!("did:key:z{}", bs58::encode(bytes).into_string())
+}
+
+/// Whether `did` is the `did:mycelix` or `did:key` identifier of `public_key`
+pub fn did_matches_key(did: &str, public_key: &[u8]) -> ExternResult<bool> {
+    Ok(did == did_from_public_key(public_key)? || did == did_key_from_public_key(public_key))
+}
+
+/// Handover of a DID from the key in `binding` to a new agent key
+///
+/// Only the holder of `binding` can write it, so its action signature is the
+/// current key approving the move. The new agent takes over by publishing a
+/// `DidBinding` whose `rotation` points here.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DidRotation {
+    pub did: String,
+    pub binding: ActionHash,
+    pub new_agent_pub_key: AgentPubKey,
+    pub rotated_at: Timestamp,
+}
+
+/// Permanent withdrawal of a DID binding, e.g. after its key leaked
+///
+/// Written by the binding's own key. A revoked binding can neither send mail
+/// nor hand the DID over, so the DID stops resolving. Validation cannot stop
+/// a leaked identity key binding the DID afresh on another agent; inboxes
+/// drop such mail because that agent is not the one the DID resolves to.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DidRevocation {
+    pub did: String,
+    pub binding: ActionHash,
+    pub reason: String,
+    pub revoked_at: Timestamp,
+}
+
+/// Where a DID currently points, following rotations from its first binding
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct DidRecord {
+    pub did: String,
+    pub agent_pub_key: AgentPubKey,
+    /// Ed25519 identity key of the binding in force
+    pub public_key: Vec<u8>,
+    /// Action of the binding in force
+    pub binding: ActionHash,
+    pub bound_at: Timestamp,
+    /// Handovers since the DID was first registered
+    pub rotations: u32,
+    /// Reason given when the binding in force was revoked
+    pub revoked: Option<String>,
+}
+
+/// One agent's time holding a DID
+///
+/// A tenure starts when the agent's binding was written and ends when the
+/// next holder accepted a handover or the binding was revoked; `until` is
+/// `None` for the holder in force.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct DidTenure {
+    pub agent_pub_key: AgentPubKey,
+    pub from: Timestamp,
+    pub until: Option<Timestamp>,
+}
+
+/// How far a message's `timestamp` may stray from the time it was written
+///
+/// Inboxes judge mail by who held its sender DID at `timestamp`, so a key
+/// that lost the DID must not be able to backdate mail into its tenure.
+pub const MAX_MESSAGE_CLOCK_SKEW_MICROS: i64 = 5 * 60 * 1_000_000;
+
+/// The agent that held a DID at `at`, from its custody history
+pub fn did_holder_at<'a>(history: &'a [DidTenure], at: &Timestamp) -> Option<&'a AgentPubKey> {
+    history
+        .iter()
+        .find(|tenure| tenure.from <= *at && tenure.until.map_or(true, |until| *at < until))
+        .map(|tenure| &tenure.agent_pub_key)
 }
 
 /// Persisted spam report
+///
+/// `inbox_link` is the `ToInbox` link that delivered the message to the
+/// reporter, proving they actually received it.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct SpamReport {
     pub reporter: AgentPubKey,
     pub spammer_did: String,
     pub message_hash: ActionHash,
+    pub inbox_link: ActionHash,
     pub reason: String,
     pub reported_at: Timestamp,
 }
 
+/// Encrypted content (message bodies) addressed by its CID
+///
+/// The CID is a CIDv1 (raw codec, sha2-256) over the ciphertext, so any
+/// agent can verify a fetched blob without being able to decrypt it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EncryptedBlob {
+    pub cid: String,
+    pub ciphertext: Vec<u8>,
+}
+
+/// The owner's private flags for a message (read, starred, archived, labels)
+///
+/// Stored as a private entry on the owner's source chain; each change
+/// writes a new entry and the latest one per message wins.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct MessageState {
+    pub message_hash: ActionHash,
+    pub read: bool,
+    pub starred: bool,
+    pub archived: bool,
+    pub labels: Vec<String>,
+    pub updated_at: Timestamp,
+}
+
+/// Private record that we released a quarantined message
+///
+/// Released messages always reach the inbox, and each release counts in the
+/// sender's favour in our local trust calculation.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct QuarantineRelease {
+    pub message_hash: ActionHash,
+    pub sender_did: String,
+    pub released_at: Timestamp,
+}
+
+/// Private record of the copy of a message sent to one BCC recipient
+///
+/// Nothing public ties a BCC copy to its message; this lets the sender
+/// retract both together.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct BccCopyRecord {
+    pub message_hash: ActionHash,
+    pub copy_hash: ActionHash,
+}
+
+/// Whether a sender rule lets mail through or holds it back
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Allow,
+    Block,
+}
+
+/// Private allow/block rule for senders, consulted before their trust score
+///
+/// `pattern` is either an exact DID or a DID prefix ending in `*`
+/// (e.g. `did:mycelix:acme*`). The latest rule per pattern wins.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct SenderRule {
+    pub pattern: String,
+    pub action: RuleAction,
+    pub created_at: Timestamp,
+}
+
+impl SenderRule {
+    /// Whether the rule applies to mail from `did`
+    pub fn matches(&self, did: &str) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => did.starts_with(prefix),
+            None => did == self.pattern,
+        }
+    }
+
+    /// Exact DIDs beat every wildcard; longer prefixes beat shorter ones
+    pub fn specificity(&self) -> usize {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => prefix.len(),
+            None => usize::MAX,
+        }
+    }
+}
+
 /// Entry types for the DNA
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
@@ -72,12 +326,26 @@ pub enum EntryTypes {
     MailMessage(MailMessage),
     #[entry_type]
     TrustScore(TrustScore),
-    #[entry_type]
+    #[entry_type(visibility = "private")]
     Contact(Contact),
     #[entry_type]
     DidBinding(DidBinding),
     #[entry_type]
     SpamReport(SpamReport),
+    #[entry_type]
+    EncryptedBlob(EncryptedBlob),
+    #[entry_type(visibility = "private")]
+    MessageState(MessageState),
+    #[entry_type(visibility = "private")]
+    QuarantineRelease(QuarantineRelease),
+    #[entry_type(visibility = "private")]
+    SenderRule(SenderRule),
+    #[entry_type]
+    DidRotation(DidRotation),
+    #[entry_type]
+    DidRevocation(DidRevocation),
+    #[entry_type(visibility = "private")]
+    BccCopyRecord(BccCopyRecord),
 }
 
 /// Link types for connecting entries
@@ -91,72 +359,1043 @@ pub enum LinkTypes {
     ContactLink,
     SpamReports,
     DidBindingLink,
+    BlobByCid,
+    /// Reply -> first message of its conversation
+    ThreadRoot,
+    /// First message of a conversation -> every reply in it
+    ThreadMembers,
+    /// Day bucket of a recipient's inbox -> message (see [`mailbox_path`])
+    InboxByTime,
+    /// Day bucket of a sender's outbox -> message
+    OutboxByTime,
+    /// DID binding -> the rotation or revocation that retired it
+    DidCustody,
+}
+
+/// `matl_source` of scores an agent sets by hand for their own filtering
+pub const MANUAL_TRUST_SOURCE: &str = "manual";
+
+/// Source name of scores computed from message and spam report history;
+/// these are derived on demand and never published
+pub const LOCAL_TRUST_SOURCE: &str = "local";
+
+/// DNA properties (the `properties` block in `dna.yaml`)
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct DnaProperties {
+    /// Agents (e.g. the MATL bridge) allowed to publish non-manual trust scores
+    #[serde(default)]
+    pub trust_authorities: Vec<AgentPubKeyB64>,
+    /// Relative weight of each trust source when several have scored a DID;
+    /// sources not listed weigh 1.0
+    #[serde(default)]
+    pub trust_source_weights: BTreeMap<String, f64>,
+    /// Days after which a published (non-manual) trust score counts half
+    #[serde(default = "default_trust_score_half_life_days")]
+    pub trust_score_half_life_days: f64,
+    /// Days after which a spam report counts half
+    #[serde(default = "default_spam_report_half_life_days")]
+    pub spam_report_half_life_days: f64,
+    /// Days after which mail exchanged with a correspondent counts half
+    /// towards local trust
+    #[serde(default = "default_message_half_life_days")]
+    pub message_half_life_days: f64,
+    /// Most spam reports one agent may file in any 24 hours
+    #[serde(default = "default_max_spam_reports_per_day")]
+    pub max_spam_reports_per_day: usize,
+}
+
+fn default_trust_score_half_life_days() -> f64 {
+    90.0
+}
+
+fn default_spam_report_half_life_days() -> f64 {
+    30.0
+}
+
+fn default_message_half_life_days() -> f64 {
+    90.0
+}
+
+fn default_max_spam_reports_per_day() -> usize {
+    20
+}
+
+impl Default for DnaProperties {
+    fn default() -> Self {
+        Self {
+            trust_authorities: Vec::new(),
+            trust_source_weights: BTreeMap::new(),
+            trust_score_half_life_days: default_trust_score_half_life_days(),
+            spam_report_half_life_days: default_spam_report_half_life_days(),
+            message_half_life_days: default_message_half_life_days(),
+            max_spam_reports_per_day: default_max_spam_reports_per_day(),
+        }
+    }
+}
+
+impl DnaProperties {
+    /// Read the properties this DNA was installed with
+    ///
+    /// A DNA installed without properties gets the defaults, which grant no
+    /// agent trust authority. Properties that are present but malformed are
+    /// an error rather than silently replaced by defaults.
+    pub fn get() -> ExternResult<Self> {
+        let properties = dna_info()?.modifiers.properties;
+        // No properties block serializes as nothing or as MessagePack nil
+        if matches!(properties.bytes().as_slice(), [] | [0xc0]) {
+            return Ok(Self::default());
+        }
+        Self::try_from(properties).map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Invalid DNA properties: {:?}",
+                e
+            )))
+        })
+    }
+
+    pub fn is_trust_authority(&self, agent: &AgentPubKey) -> bool {
+        self.trust_authorities
+            .iter()
+            .any(|authority| AgentPubKey::from(authority.clone()) == *agent)
+    }
+
+    pub fn source_weight(&self, source: &str) -> f64 {
+        self.trust_source_weights
+            .get(source)
+            .copied()
+            .unwrap_or(1.0)
+            .max(0.0)
+    }
+}
+
+/// Weight of evidence recorded at `at`, halving every `half_life_days`
+///
+/// A half-life of zero or less disables decay.
+pub fn decay_weight(at: Timestamp, now: Timestamp, half_life_days: f64) -> f64 {
+    if half_life_days <= 0.0 {
+        return 1.0;
+    }
+    let age_days =
+        (now.as_micros() - at.as_micros()).max(0) as f64 / (24.0 * 60.0 * 60.0 * 1_000_000.0);
+    0.5_f64.powf(age_days / half_life_days)
+}
+
+/// Encode a parent message hash as a `thread_id`
+///
+/// This is the same base64 form clients show as the message ID, so a
+/// displayed ID can be passed straight back as `--reply-to`.
+pub fn encode_thread_id(parent: &ActionHash) -> String {
+    ActionHashB64::from(parent.clone()).to_string()
 }
 
-/// Basic validation to guard against malformed data
+/// Decode a `thread_id` back into the parent message hash
+pub fn parse_thread_id(thread_id: &str) -> Option<ActionHash> {
+    ActionHashB64::from_b64_str(thread_id.trim())
+        .ok()
+        .map(ActionHash::from)
+}
+
+/// Mailbox folder indexed by `InboxByTime` links
+pub const INBOX_FOLDER: &str = "inbox";
+
+/// Mailbox folder indexed by `OutboxByTime` links
+pub const OUTBOX_FOLDER: &str = "outbox";
+
+/// Width of one mailbox time bucket
+pub const MAILBOX_BUCKET_MICROS: i64 = 24 * 60 * 60 * 1_000_000;
+
+/// Day bucket a message timestamp falls into
+pub fn mailbox_bucket(timestamp: &Timestamp) -> i64 {
+    timestamp.as_micros().div_euclid(MAILBOX_BUCKET_MICROS)
+}
+
+/// Base of the links to each of an agent's day buckets for `folder`
+pub fn mailbox_root(folder: &str, owner: &AgentPubKey) -> Path {
+    Path::from(format!("mailbox.{}.{}", folder, AgentPubKeyB64::from(owner.clone())))
+}
+
+/// `mailbox.<folder>.<owner>.<bucket>`: one path per agent, folder and day
+///
+/// Queries walk the buckets of the requested time range newest first, so a
+/// page only fetches the messages it returns.
+pub fn mailbox_path(folder: &str, owner: &AgentPubKey, bucket: i64) -> Path {
+    Path::from(format!(
+        "mailbox.{}.{}.{}",
+        folder,
+        AgentPubKeyB64::from(owner.clone()),
+        bucket
+    ))
+}
+
+/// Tag of a mailbox link: message timestamp (big-endian micros) and owner
+///
+/// The timestamp lets queries order and range-filter links without fetching
+/// the messages they point at.
+pub fn mailbox_tag(owner: &AgentPubKey, timestamp: &Timestamp) -> LinkTag {
+    let mut bytes = timestamp.as_micros().to_be_bytes().to_vec();
+    bytes.extend_from_slice(owner.get_raw_39());
+    LinkTag::new(bytes)
+}
+
+/// Decode a tag written by [`mailbox_tag`]
+pub fn parse_mailbox_tag(tag: &LinkTag) -> Option<(AgentPubKey, Timestamp)> {
+    let bytes = tag.as_ref();
+    if bytes.len() != 8 + 39 {
+        return None;
+    }
+    let micros = i64::from_be_bytes(bytes[..8].try_into().ok()?);
+    let owner = AgentPubKey::try_from_raw_39(bytes[8..].to_vec()).ok()?;
+    Some((owner, Timestamp::from_micros(micros)))
+}
+
+/// Tag of a bucket link: the [`mailbox_tag`] of the message that opened the
+/// bucket, followed by that message's action hash
+///
+/// Carrying the message lets validators check a bucket link the same way as
+/// the message link it accompanies.
+pub fn mailbox_bucket_tag(
+    owner: &AgentPubKey,
+    timestamp: &Timestamp,
+    message_hash: &ActionHash,
+) -> LinkTag {
+    let mut bytes = mailbox_tag(owner, timestamp).into_inner();
+    bytes.extend_from_slice(message_hash.get_raw_39());
+    LinkTag::new(bytes)
+}
+
+/// Decode a tag written by [`mailbox_bucket_tag`]
+pub fn parse_mailbox_bucket_tag(tag: &LinkTag) -> Option<(AgentPubKey, Timestamp, ActionHash)> {
+    let bytes = tag.as_ref();
+    if bytes.len() != 8 + 39 + 39 {
+        return None;
+    }
+    let (owner, timestamp) = parse_mailbox_tag(&LinkTag::new(bytes[..8 + 39].to_vec()))?;
+    let message_hash = ActionHash::try_from_raw_39(bytes[8 + 39..].to_vec()).ok()?;
+    Some((owner, timestamp, message_hash))
+}
+
+/// Encode a page cursor: the position of the last message returned
+pub fn encode_mailbox_cursor(timestamp: &Timestamp, message_hash: &ActionHash) -> String {
+    format!("{}.{}", timestamp.as_micros(), encode_thread_id(message_hash))
+}
+
+/// Decode a cursor written by [`encode_mailbox_cursor`]
+pub fn parse_mailbox_cursor(cursor: &str) -> Option<(Timestamp, ActionHash)> {
+    let (micros, hash) = cursor.trim().split_once('.')?;
+    Some((Timestamp::from_micros(micros.parse().ok()?), parse_thread_id(hash)?))
+}
+
+/// Validate DHT operations
 #[hdk_extern]
-pub fn validate(_op: Op) -> ExternResult<ValidateCallbackResult> {
-    match _op {
-        Op::StoreEntry(store_entry) => {
-            match store_entry.entry {
-                EntryTypes::TrustScore(score) => {
-                    if !(0.0..=1.0).contains(&score.score) {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Trust score must be between 0.0 and 1.0".into(),
-                        ));
-                    }
-                    if score.did.trim().is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Trust score DID cannot be empty".into(),
-                        ));
-                    }
-                }
-                EntryTypes::MailMessage(message) => {
-                    if message.from_did.trim().is_empty() || message.to_did.trim().is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Message DIDs cannot be empty".into(),
-                        ));
-                    }
-                    if message.subject_encrypted.is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Subject cannot be empty".into(),
-                        ));
-                    }
-                    if message.body_cid.trim().is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Body CID cannot be empty".into(),
-                        ));
-                    }
-                }
-                EntryTypes::DidBinding(binding) => {
-                    if binding.did.trim().is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "DID cannot be empty".into(),
-                        ));
-                    }
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.flattened::<EntryTypes, LinkTypes>()? {
+        FlatOp::StoreEntry(store_entry) => match store_entry {
+            OpEntry::CreateEntry { app_entry, action } => {
+                validate_entry(app_entry, &action.author, &action.prev_action, &action.timestamp)
+            }
+            OpEntry::UpdateEntry {
+                original_action_hash,
+                app_entry,
+                action,
+                ..
+            } => {
+                let original = must_get_action(original_action_hash)?;
+                if original.action().author() != &action.author {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Only the original author can update an entry".into(),
+                    ));
                 }
-                EntryTypes::SpamReport(report) => {
-                    if report.spammer_did.trim().is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Spam report must include spammer DID".into(),
-                        ));
-                    }
-                    if report.reason.trim().is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Spam report reason cannot be empty".into(),
-                        ));
-                    }
+                if is_custody_record(original.action())? {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "DID bindings, rotations and revocations cannot be changed".into(),
+                    ));
                 }
-                EntryTypes::Contact(contact) => {
-                    if contact.did.trim().is_empty() {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Contact DID cannot be empty".into(),
-                        ));
-                    }
+                validate_entry(app_entry, &action.author, &action.prev_action, &action.timestamp)
+            }
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterCreateLink {
+            link_type,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => validate_create_link(
+            link_type,
+            &base_address,
+            &target_address,
+            &tag,
+            &action.author,
+        ),
+        FlatOp::RegisterDeleteLink {
+            link_type,
+            base_address,
+            original_action,
+            action,
+            ..
+        } => {
+            // Losing these would hide who holds a DID
+            if matches!(link_type, LinkTypes::DidBindingLink | LinkTypes::DidCustody) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "DID custody links cannot be deleted".into(),
+                ));
+            }
+            // Recipients may also clear messages out of their own inbox
+            let inbox_owner = match link_type {
+                LinkTypes::ToInbox => {
+                    base_address.into_agent_pub_key().as_ref() == Some(&action.author)
                 }
+                LinkTypes::InboxByTime => parse_mailbox_tag(&original_action.tag)
+                    .map(|(owner, _)| owner)
+                    .or_else(|| {
+                        parse_mailbox_bucket_tag(&original_action.tag).map(|(owner, _, _)| owner)
+                    })
+                    .is_some_and(|owner| owner == action.author),
+                _ => false,
+            };
+            if original_action.author != action.author && !inbox_owner {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the link author can delete a link".into(),
+                ));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        FlatOp::RegisterDelete(OpDelete { action }) => {
+            let original = must_get_action(action.deletes_address.clone())?;
+            if original.action().author() != &action.author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the original author can delete an entry".into(),
+                ));
+            }
+            if is_custody_record(original.action())? {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "DID bindings, rotations and revocations cannot be deleted".into(),
+                ));
             }
             Ok(ValidateCallbackResult::Valid)
         }
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
+
+/// Whether `action` created one of the entries recording who holds a DID
+///
+/// These stay on the DHT for good so a revoked key cannot undo its revocation.
+fn is_custody_record(action: &Action) -> ExternResult<bool> {
+    let custody_types = [
+        UnitEntryTypes::DidBinding,
+        UnitEntryTypes::DidRotation,
+        UnitEntryTypes::DidRevocation,
+    ];
+    Ok(created_entry_type(action, &custody_types)?.is_some())
+}
+
+/// Base and target rules for each link type
+///
+/// Mailbox and thread links must point at messages; path-indexed links must
+/// hang off a path and point at the entry type that index holds. Links to
+/// entries are only valid when the link author also wrote the entry, so
+/// nobody can index someone else's data under their own paths.
+fn validate_create_link(
+    link_type: LinkTypes,
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    tag: &LinkTag,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    match link_type {
+        LinkTypes::ToInbox => {
+            if base.clone().into_agent_pub_key().is_none() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Inbox links must start from the recipient's agent key".into(),
+                ));
+            }
+            check_link_target::<MailMessage>(target, Some(author), "mail message")
+        }
+        LinkTypes::FromOutbox => {
+            if base.clone().into_agent_pub_key().as_ref() != Some(author) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Outbox links must start from the author's own agent key".into(),
+                ));
+            }
+            check_link_target::<MailMessage>(target, Some(author), "mail message")
+        }
+        LinkTypes::ContactLink => {
+            if base.clone().into_agent_pub_key().as_ref() != Some(author) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Contact links must start from the author's own agent key".into(),
+                ));
+            }
+            check_link_target::<Contact>(target, Some(author), "contact")
+        }
+        LinkTypes::ThreadReply | LinkTypes::ThreadMembers => {
+            let base_check = check_link_target::<MailMessage>(base, None, "mail message")?;
+            if base_check != ValidateCallbackResult::Valid {
+                return Ok(base_check);
+            }
+            check_link_target::<MailMessage>(target, Some(author), "mail message")
+        }
+        LinkTypes::ThreadRoot => {
+            let base_check = check_link_target::<MailMessage>(base, Some(author), "mail message")?;
+            if base_check != ValidateCallbackResult::Valid {
+                return Ok(base_check);
+            }
+            check_link_target::<MailMessage>(target, None, "mail message")
+        }
+        LinkTypes::TrustByDid | LinkTypes::TrustIndex => {
+            check_path_link::<TrustScore>(base, target, author, "trust score")
+        }
+        LinkTypes::SpamReports => check_path_link::<SpamReport>(base, target, author, "spam report"),
+        LinkTypes::DidBindingLink => {
+            check_path_link::<DidBinding>(base, target, author, "DID binding")
+        }
+        LinkTypes::BlobByCid => {
+            check_path_link::<EncryptedBlob>(base, target, author, "encrypted blob")
+        }
+        LinkTypes::InboxByTime => check_mailbox_link(INBOX_FOLDER, base, target, tag, author),
+        LinkTypes::OutboxByTime => check_mailbox_link(OUTBOX_FOLDER, base, target, tag, author),
+        LinkTypes::DidCustody => check_custody_link(base, target, author),
+    }
+}
+
+/// A binding's link to the rotation or revocation that retired it
+fn check_custody_link(
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let base_check = check_link_target::<DidBinding>(base, Some(author), "DID binding")?;
+    if base_check != ValidateCallbackResult::Valid {
+        return Ok(base_check);
+    }
+    let Some(action_hash) = target.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Custody links must address a rotation or revocation action".into(),
+        ));
+    };
+
+    let record = must_get_valid_record(action_hash)?;
+    if record.action().author() != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Custody links can only be created by the binding's holder".into(),
+        ));
+    }
+    let retirement_types = [UnitEntryTypes::DidRotation, UnitEntryTypes::DidRevocation];
+    let retired = match (
+        created_entry_type(record.action(), &retirement_types)?,
+        record.entry().as_option(),
+    ) {
+        (Some(entry_type), Some(entry)) => retired_binding(entry_type, entry),
+        _ => None,
+    };
+    let Some(binding) = retired else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Link address is not a DID rotation or revocation".into(),
+        ));
+    };
+    if AnyLinkableHash::from(binding) != *base {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Custody link must start from the binding it retires".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A time-bucketed mailbox link
+///
+/// Message links hang off the bucket path their tag and the message
+/// timestamp imply; bucket links go from the owner's mailbox root to that
+/// bucket and name the message that opened it. Either way the message must
+/// be real, and senders index their messages into recipients' inboxes and
+/// their own outbox; an owner may also index mail they already hold.
+fn check_mailbox_link(
+    folder: &str,
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    tag: &LinkTag,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(base_hash) = base.clone().into_entry_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mailbox links must start from a mailbox path".into(),
+        ));
+    };
+
+    let (owner, timestamp, action_hash, expected_base) = match target.clone().into_entry_hash() {
+        Some(bucket_hash) => {
+            let Some((owner, timestamp, message_hash)) = parse_mailbox_bucket_tag(tag) else {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Bucket link tag must carry the owner, timestamp and message".into(),
+                ));
+            };
+            if mailbox_path(folder, &owner, mailbox_bucket(&timestamp)).path_entry_hash()?
+                != bucket_hash
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Bucket link does not point at the bucket for its message".into(),
+                ));
+            }
+            let root = mailbox_root(folder, &owner).path_entry_hash()?;
+            (owner, timestamp, message_hash, root)
+        }
+        None => {
+            let Some((owner, timestamp)) = parse_mailbox_tag(tag) else {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Mailbox link tag must carry the owner and message timestamp".into(),
+                ));
+            };
+            let Some(action_hash) = target.clone().into_action_hash() else {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Link must address the action that created a mail message".into(),
+                ));
+            };
+            let bucket = mailbox_path(folder, &owner, mailbox_bucket(&timestamp)).path_entry_hash()?;
+            (owner, timestamp, action_hash, bucket)
+        }
+    };
+
+    let record = must_get_valid_record(action_hash)?;
+    let Ok(Some(message)) = record.entry().to_app_option::<MailMessage>() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Link address is not a mail message".into(),
+        ));
+    };
+    let sender = record.action().author();
+
+    if message.timestamp != timestamp {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mailbox link tag does not match the message timestamp".into(),
+        ));
+    }
+    if folder == OUTBOX_FOLDER && &owner != sender {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Outbox links must index the sender's own outbox".into(),
+        ));
+    }
+    if sender != author && &owner != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mailbox links can only be created by the sender or the mailbox owner".into(),
+        ));
+    }
+    if expected_base != base_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mailbox link is not in the bucket for its message".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A path-indexed link: the base is a path entry, the target either a child
+/// path component or an entry of type `T` written by the link author
+fn check_path_link<T>(
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    author: &AgentPubKey,
+    kind: &str,
+) -> ExternResult<ValidateCallbackResult>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    if base.clone().into_entry_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Links to a {} must start from an index path",
+            kind
+        )));
+    }
+    if target.clone().into_entry_hash().is_some() {
+        // Path tree link to a child component
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    check_link_target::<T>(target, Some(author), kind)
+}
+
+/// Check that `address` is a create action for an entry of type `T`,
+/// optionally written by `author`
+fn check_link_target<T>(
+    address: &AnyLinkableHash,
+    author: Option<&AgentPubKey>,
+    kind: &str,
+) -> ExternResult<ValidateCallbackResult>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let Some(action_hash) = address.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Link must address the action that created a {}",
+            kind
+        )));
+    };
+
+    let record = must_get_valid_record(action_hash)?;
+    if !matches!(record.entry().to_app_option::<T>(), Ok(Some(_))) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Link address is not a {}",
+            kind
+        )));
+    }
+    if let Some(author) = author {
+        if record.action().author() != author {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Links to a {} can only be created by its author",
+                kind
+            )));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Content and authorship checks for a created or updated entry
+///
+/// `chain_top` is the action preceding the write on the author's chain; any
+/// lookups against the author's history stop there so every validator
+/// reaches the same result. `timestamp` is the write's own action time.
+fn validate_entry(
+    entry: EntryTypes,
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    timestamp: &Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    match entry {
+        EntryTypes::TrustScore(score) => {
+            if !(0.0..=1.0).contains(&score.score) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Trust score must be between 0.0 and 1.0".into(),
+                ));
+            }
+            if score.did.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Trust score DID cannot be empty".into(),
+                ));
+            }
+            if score.matl_source == LOCAL_TRUST_SOURCE {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Local trust scores are computed, not published".into(),
+                ));
+            }
+            if score.matl_source != MANUAL_TRUST_SOURCE
+                && !DnaProperties::get()?.is_trust_authority(author)
+            {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Only configured trust authorities may publish '{}' trust scores",
+                    score.matl_source
+                )));
+            }
+            if did_bound_to_author(&score.did, author, chain_top)? {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Agents cannot set a trust score for their own DID".into(),
+                ));
+            }
+        }
+        EntryTypes::MailMessage(message) => {
+            if message.to_dids.is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Message must have at least one recipient".into(),
+                ));
+            }
+            if message.from_did.trim().is_empty()
+                || message
+                    .to_dids
+                    .iter()
+                    .chain(&message.cc_dids)
+                    .any(|did| did.trim().is_empty())
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Message DIDs cannot be empty".into(),
+                ));
+            }
+            if message.recipient_keys.is_empty()
+                || message.recipient_keys.iter().any(|key| key.is_empty())
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Message must carry a sealed content key for each recipient".into(),
+                ));
+            }
+            if message.subject_encrypted.is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Subject cannot be empty".into(),
+                ));
+            }
+            if message.body_cid.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Body CID cannot be empty".into(),
+                ));
+            }
+            if (message.timestamp.as_micros() - timestamp.as_micros()).abs()
+                > MAX_MESSAGE_CLOCK_SKEW_MICROS
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Message timestamp must match the time it was written".into(),
+                ));
+            }
+            if message
+                .expires_at
+                .is_some_and(|expires_at| expires_at <= message.timestamp)
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Message must expire after it was sent".into(),
+                ));
+            }
+            if let Some(thread_id) = &message.thread_id {
+                if parse_thread_id(thread_id).is_none() {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Thread ID must be a base64 message hash".into(),
+                    ));
+                }
+            }
+            if !did_bound_to_author(&message.from_did, author, chain_top)? {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Sender {} is not bound to the author of this message",
+                    message.from_did
+                )));
+            }
+        }
+        EntryTypes::DidBinding(binding) => {
+            if binding.did.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "DID cannot be empty".into(),
+                ));
+            }
+            if &binding.agent_pub_key != author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A DID can only be bound to the key of the agent creating the binding".into(),
+                ));
+            }
+            if binding.public_key.len() != 32 {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "DID binding must carry a 32-byte Ed25519 identity key".into(),
+                ));
+            }
+            let identity_key = AgentPubKey::from_raw_32(binding.public_key.clone());
+            if !verify_signature_raw(
+                identity_key,
+                binding.signature.clone(),
+                binding.agent_pub_key.get_raw_39().to_vec(),
+            )? {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "DID binding signature does not match its identity key".into(),
+                ));
+            }
+            if binding.rotation.is_none() && !did_matches_key(&binding.did, &binding.public_key)? {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A new DID must be derived from the identity key that signs its binding".into(),
+                ));
+            }
+            if let Some(rotation_hash) = binding.rotation {
+                let record = must_get_valid_record(rotation_hash)?;
+                let Ok(Some(rotation)) = record.entry().to_app_option::<DidRotation>() else {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Binding must follow a DID rotation".into(),
+                    ));
+                };
+                if rotation.did != binding.did || &rotation.new_agent_pub_key != author {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "The rotation did not hand this DID over to the binding's key".into(),
+                    ));
+                }
+            }
+        }
+        EntryTypes::DidRotation(rotation) => {
+            if &rotation.new_agent_pub_key == author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A DID must be rotated to a different agent key".into(),
+                ));
+            }
+            return check_binding_holder(&rotation.did, &rotation.binding, author, chain_top);
+        }
+        EntryTypes::DidRevocation(revocation) => {
+            return check_binding_holder(&revocation.did, &revocation.binding, author, chain_top);
+        }
+        EntryTypes::SpamReport(report) => {
+            if report.spammer_did.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Spam report must include spammer DID".into(),
+                ));
+            }
+            if report.reason.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Spam report reason cannot be empty".into(),
+                ));
+            }
+            if &report.reporter != author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Spam reports must be filed by their reporter".into(),
+                ));
+            }
+            return validate_spam_report(&report, author, chain_top, timestamp);
+        }
+        EntryTypes::Contact(contact) => {
+            if contact.did.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Contact DID cannot be empty".into(),
+                ));
+            }
+            if !contact.did.starts_with("did:") {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Contact DID must start with did:".into(),
+                ));
+            }
+            if contact.name.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Contact name cannot be empty".into(),
+                ));
+            }
+        }
+        EntryTypes::EncryptedBlob(blob) => {
+            if blob.ciphertext.is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Blob ciphertext cannot be empty".into(),
+                ));
+            }
+            if blob.cid != compute_cid(&blob.ciphertext) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Blob CID does not match its content".into(),
+                ));
+            }
+        }
+        EntryTypes::MessageState(state) => {
+            if state.labels.iter().any(|label| label.trim().is_empty()) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Message labels cannot be empty".into(),
+                ));
+            }
+        }
+        EntryTypes::QuarantineRelease(release) => {
+            if release.sender_did.trim().is_empty() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Released message must name its sender".into(),
+                ));
+            }
+        }
+        EntryTypes::SenderRule(rule) => {
+            if !rule.pattern.starts_with("did:") {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Sender rule pattern must start with did:".into(),
+                ));
+            }
+            let prefix = rule.pattern.strip_suffix('*').unwrap_or(&rule.pattern);
+            if prefix.contains('*') {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Sender rule wildcard is only allowed at the end".into(),
+                ));
+            }
+        }
+        EntryTypes::BccCopyRecord(record) => {
+            if record.copy_hash == record.message_hash {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A BCC copy must be a different message".into(),
+                ));
+            }
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Delivery, deduplication and rate checks for a spam report
+///
+/// The report must name the real sender of a message delivered to the
+/// reporter's inbox, the reporter may report each message only once, and
+/// may file at most `max_spam_reports_per_day` reports in any 24 hours.
+fn validate_spam_report(
+    report: &SpamReport,
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    timestamp: &Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(report.message_hash.clone())?;
+    let Ok(Some(message)) = record.entry().to_app_option::<MailMessage>() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Spam reports must reference a mail message".into(),
+        ));
+    };
+    if message.from_did != report.spammer_did {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Spam report must name the message's sender".into(),
+        ));
+    }
+
+    let delivered = match must_get_action(report.inbox_link.clone())?.action() {
+        Action::CreateLink(link) => {
+            let is_inbox_link = matches!(
+                LinkTypes::try_from(ScopedLinkType {
+                    zome_index: link.zome_index,
+                    zome_type: link.link_type,
+                }),
+                Ok(LinkTypes::ToInbox)
+            );
+            is_inbox_link
+                && link.base_address.clone().into_agent_pub_key().as_ref() == Some(author)
+                && link.target_address.clone().into_action_hash().as_ref()
+                    == Some(&report.message_hash)
+        }
+        _ => false,
+    };
+    if !delivered {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only messages delivered to your own inbox can be reported".into(),
+        ));
+    }
+
+    let max_per_day = DnaProperties::get()?.max_spam_reports_per_day;
+    let day_ago = Timestamp::from_micros(timestamp.as_micros() - 24 * 60 * 60 * 1_000_000);
+    let mut recent = 0;
+
+    let earlier_reports = app_entries_on_chain(author, chain_top, &[UnitEntryTypes::SpamReport])?;
+    for (_, _, reported_at, entry) in earlier_reports {
+        let Ok(earlier) = SpamReport::try_from(entry) else {
+            continue;
+        };
+        if earlier.message_hash == report.message_hash {
+            return Ok(ValidateCallbackResult::Invalid(
+                "This message has already been reported".into(),
+            ));
+        }
+        if reported_at > day_ago {
+            recent += 1;
+        }
+    }
+
+    if recent >= max_per_day {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Spam report limit reached ({} per day)",
+            max_per_day
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Whether `author` held `did` as of `chain_top`
+///
+/// Bindings can only be created for the author's own key, so a binding on
+/// the author's chain proves they control the DID until they rotate it away
+/// or revoke it, which also happens on their own chain. This only covers
+/// the author's chain: it cannot see that the identity key was retired
+/// elsewhere, so readers also check the sender against the DID's current
+/// binding (see `mail_messages::get_inbox`).
+fn did_bound_to_author(
+    did: &str,
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+) -> ExternResult<bool> {
+    let mut bindings = Vec::new();
+    let mut retired = HashSet::new();
+
+    let custody_types = [
+        UnitEntryTypes::DidBinding,
+        UnitEntryTypes::DidRotation,
+        UnitEntryTypes::DidRevocation,
+    ];
+    let custody = app_entries_on_chain(author, chain_top, &custody_types)?;
+    for (action_hash, entry_type, _, entry) in custody {
+        if entry_type == UnitEntryTypes::DidBinding {
+            if let Ok(binding) = DidBinding::try_from(&entry) {
+                if binding.did == did && &binding.agent_pub_key == author {
+                    bindings.push(action_hash);
+                }
+            }
+        } else if let Some(binding) = retired_binding(entry_type, &entry) {
+            retired.insert(binding);
+        }
+    }
+
+    Ok(bindings.iter().any(|binding| !retired.contains(binding)))
+}
+
+/// Bindings `author` handed over or revoked at or before `chain_top`
+fn retired_bindings(author: &AgentPubKey, chain_top: &ActionHash) -> ExternResult<HashSet<ActionHash>> {
+    let retirement_types = [UnitEntryTypes::DidRotation, UnitEntryTypes::DidRevocation];
+    Ok(app_entries_on_chain(author, chain_top, &retirement_types)?
+        .into_iter()
+        .filter_map(|(_, entry_type, _, entry)| retired_binding(entry_type, &entry))
+        .collect())
+}
+
+/// The binding a rotation or revocation entry of `entry_type` retires
+fn retired_binding(entry_type: UnitEntryTypes, entry: &Entry) -> Option<ActionHash> {
+    match entry_type {
+        UnitEntryTypes::DidRotation => DidRotation::try_from(entry).ok().map(|r| r.binding),
+        UnitEntryTypes::DidRevocation => DidRevocation::try_from(entry).ok().map(|r| r.binding),
+        _ => None,
+    }
+}
+
+/// The app entry type `action` created, if it is one of `entry_types`
+fn created_entry_type(
+    action: &Action,
+    entry_types: &[UnitEntryTypes],
+) -> ExternResult<Option<UnitEntryTypes>> {
+    let Some(created) = action.entry_type() else {
+        return Ok(None);
+    };
+    for unit in entry_types {
+        if *created == EntryType::try_from(*unit)? {
+            return Ok(Some(*unit));
+        }
+    }
+    Ok(None)
+}
+
+/// Every entry of one of `entry_types` that `author` created at or before
+/// `chain_top`, with its type and creation time
+///
+/// Actions are filtered by entry type before anything is fetched, so only
+/// the entries a check needs are pulled from the DHT.
+fn app_entries_on_chain(
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    entry_types: &[UnitEntryTypes],
+) -> ExternResult<Vec<(ActionHash, UnitEntryTypes, Timestamp, Entry)>> {
+    let activity = must_get_agent_activity(author.clone(), ChainFilter::new(chain_top.clone()))?;
+
+    let mut entries = Vec::new();
+    for item in activity {
+        let Action::Create(create) = item.action.action() else {
+            continue;
+        };
+        let Some(entry_type) = created_entry_type(item.action.action(), entry_types)? else {
+            continue;
+        };
+        let entry = must_get_entry(create.entry_hash.clone())?;
+        entries.push((
+            item.action.action_address().clone(),
+            entry_type,
+            create.timestamp,
+            entry.content,
+        ));
+    }
+
+    Ok(entries)
+}
+
+/// `binding` must be `author`'s own binding for `did`, still in force
+///
+/// Each binding is retired once, by either a rotation or a revocation, so a
+/// key that handed its DID over cannot later revoke it from the new holder.
+fn check_binding_holder(
+    did: &str,
+    binding: &ActionHash,
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(binding.clone())?;
+    let Ok(Some(held)) = record.entry().to_app_option::<DidBinding>() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Referenced action is not a DID binding".into(),
+        ));
+    };
+    if held.did != did || &held.agent_pub_key != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the key a DID is bound to can rotate or revoke that binding".into(),
+        ));
+    }
+    if retired_bindings(author, chain_top)?.contains(binding) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "This binding was already rotated or revoked".into(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,159 @@
+use hdk::prelude::*;
+use mycelix_mail_integrity::*;
+use std::collections::{HashMap, HashSet};
+
+/// Input for adding a contact
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddContactInput {
+    pub name: String,
+    pub did: String,
+    pub email_alias: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Change to an existing contact; `None` leaves a field unchanged and an
+/// empty string clears the alias or notes
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateContactInput {
+    pub did: String,
+    pub name: Option<String>,
+    pub email_alias: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Add a contact to the caller's private address book
+#[hdk_extern]
+pub fn add_contact(input: AddContactInput) -> ExternResult<Contact> {
+    let did = input.did.trim().to_string();
+    if latest_contacts()?.contains_key(&did) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Contact {} already exists; update it instead",
+            did
+        ))));
+    }
+
+    let contact = Contact {
+        name: input.name.trim().to_string(),
+        did,
+        email_alias: non_empty(input.email_alias),
+        notes: non_empty(input.notes),
+        added_at: sys_time()?,
+    };
+    create_entry(EntryTypes::Contact(contact.clone()))?;
+    Ok(contact)
+}
+
+/// Update an existing contact's name, alias or notes
+#[hdk_extern]
+pub fn update_contact(input: UpdateContactInput) -> ExternResult<Contact> {
+    let did = input.did.trim().to_string();
+    let (action_hash, mut contact) = latest_contacts()?
+        .remove(&did)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "No contact for {}",
+            did
+        ))))?;
+
+    if let Some(name) = input.name {
+        contact.name = name.trim().to_string();
+    }
+    if let Some(alias) = input.email_alias {
+        contact.email_alias = non_empty(Some(alias));
+    }
+    if let Some(notes) = input.notes {
+        contact.notes = non_empty(Some(notes));
+    }
+
+    update_entry(action_hash, EntryTypes::Contact(contact.clone()))?;
+    Ok(contact)
+}
+
+/// Remove a contact from the address book
+#[hdk_extern]
+pub fn remove_contact(did: String) -> ExternResult<ActionHash> {
+    let did = did.trim().to_string();
+    let (action_hash, _) = latest_contacts()?
+        .remove(&did)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "No contact for {}",
+            did
+        ))))?;
+
+    delete_entry(action_hash)
+}
+
+/// List all contacts, sorted by name
+#[hdk_extern]
+pub fn list_contacts(_: ()) -> ExternResult<Vec<Contact>> {
+    let mut contacts: Vec<Contact> = latest_contacts()?
+        .into_values()
+        .map(|(_, contact)| contact)
+        .collect();
+    contacts.sort_by_key(|c| c.name.to_lowercase());
+    Ok(contacts)
+}
+
+/// Case-insensitive search over name, DID, alias and notes
+#[hdk_extern]
+pub fn search_contacts(query: String) -> ExternResult<Vec<Contact>> {
+    let query = query.trim().to_lowercase();
+    Ok(list_contacts(())?
+        .into_iter()
+        .filter(|c| {
+            [
+                Some(&c.name),
+                Some(&c.did),
+                c.email_alias.as_ref(),
+                c.notes.as_ref(),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|field| field.to_lowercase().contains(&query))
+        })
+        .collect())
+}
+
+/// Latest contact per DID with the action that wrote it, skipping removed ones
+///
+/// Contacts are private entries, so they are read straight from our own
+/// source chain rather than through links.
+fn latest_contacts() -> ExternResult<HashMap<String, (ActionHash, Contact)>> {
+    let deleted: HashSet<ActionHash> = query(
+        ChainQueryFilter::new().action_type(ActionType::Delete),
+    )?
+    .into_iter()
+    .filter_map(|record| match record.action() {
+        Action::Delete(delete) => Some(delete.deletes_address.clone()),
+        _ => None,
+    })
+    .collect();
+
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::Contact.try_into()?)
+        .include_entries(true);
+
+    // Records come back in chain order, so later entries overwrite earlier ones
+    let mut contacts = HashMap::new();
+    for record in query(filter)? {
+        if let Some(contact) = record.entry().to_app_option::<Contact>().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Deserialization error: {:?}",
+                e
+            )))
+        })? {
+            contacts.insert(
+                contact.did.clone(),
+                (record.action_address().clone(), contact),
+            );
+        }
+    }
+
+    contacts.retain(|_, (hash, _)| !deleted.contains(hash));
+    Ok(contacts)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
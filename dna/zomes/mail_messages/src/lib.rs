@@ -1,16 +1,129 @@
 use hdk::prelude::*;
 use mycelix_mail_integrity::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Page size when a mailbox query sets no limit
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page a mailbox query may request
+const MAX_PAGE_SIZE: usize = 500;
+
+/// Input for sending a message
+///
+/// BCC recipients are never written into `message` or given a slot in its
+/// `recipient_keys`; each one is delivered its own [`BccCopy`] instead.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SendMessageInput {
+    pub message: MailMessage,
+    #[serde(default)]
+    pub bcc_copies: Vec<BccCopy>,
+}
+
+/// A blind copy of a message for one BCC recipient
+///
+/// The client encrypts the copy under its own content key, sealed only for
+/// the sender and `did`, so it shares no ciphertext or CID with the message
+/// To and Cc recipients read. The copy and its inbox link are still public
+/// actions on the sender's chain: anyone walking that chain can see that a
+/// copy went to the recipient's agent key, just not what it copies.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BccCopy {
+    pub did: String,
+    pub subject_encrypted: Vec<u8>,
+    pub body_cid: String,
+    pub recipient_keys: Vec<Vec<u8>>,
+}
+
+/// Change to a message's private state; `None` leaves a flag unchanged
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MessageStateUpdate {
+    pub message_hash: ActionHash,
+    pub read: Option<bool>,
+    pub starred: Option<bool>,
+    pub archived: Option<bool>,
+    #[serde(default)]
+    pub add_labels: Vec<String>,
+    #[serde(default)]
+    pub remove_labels: Vec<String>,
+}
+
+/// Signals sent to recipients and on to their clients
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum MailSignal {
+    /// A message was just delivered to the recipient's inbox (sender to
+    /// recipient)
+    NewMail {
+        message_hash: ActionHash,
+        from_did: String,
+    },
+    /// A [`MailSignal::NewMail`] as received, passed on to our clients
+    ///
+    /// Nothing in it is verified: the message is usually not even published
+    /// yet when the signal arrives. `provenance` is the agent that sent the
+    /// signal; clients must check that it wrote the message, under
+    /// `from_did`, once they can fetch it.
+    NewMailClaim {
+        message_hash: ActionHash,
+        from_did: String,
+        provenance: AgentPubKey,
+    },
+}
+
+/// Let any agent deliver new-mail signals to us
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, "recv_remote_signal".into()));
+    create_cap_grant(CapGrantEntry {
+        tag: "recv_remote_signal".into(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Forward a sender's new-mail signal to our connected clients
+///
+/// The sender signals from inside `send_message`, before the message is
+/// published, so nothing can be checked here. The signal is passed on as a
+/// [`MailSignal::NewMailClaim`] naming the calling agent, for clients to
+/// verify against the message once it arrives.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: MailSignal) -> ExternResult<()> {
+    debug!("Received remote signal: {:?}", signal);
+    let MailSignal::NewMail {
+        message_hash,
+        from_did,
+    } = signal
+    else {
+        return Ok(());
+    };
+
+    emit_signal(MailSignal::NewMailClaim {
+        message_hash,
+        from_did,
+        provenance: call_info()?.provenance,
+    })
+}
 
 /// Input required to register the caller's DID inside the DNA
+///
+/// `signature` is the identity key in `public_key` signing our agent key's
+/// raw bytes; see [`DidBinding`].
 #[derive(Serialize, Deserialize, Debug)]
 pub struct RegisterDidInput {
     pub did: String,
+    pub public_key: Vec<u8>,
+    pub signature: Signature,
 }
 
 /// Register the caller's DID so other agents can resolve their AgentPubKey.
+///
+/// Only the first binding for a DID goes through here; later holders take it
+/// over with `rotate_did` and `accept_did_rotation`.
 #[hdk_extern]
 pub fn register_my_did(input: RegisterDidInput) -> ExternResult<ActionHash> {
-    let agent = agent_info()?.agent_initial_pubkey;
     let did = input.did.trim();
     if did.is_empty() {
         return Err(wasm_error!(WasmErrorInner::Guest(
@@ -18,48 +131,174 @@ pub fn register_my_did(input: RegisterDidInput) -> ExternResult<ActionHash> {
         )));
     }
 
-    let path = did_path(did);
-    path.ensure()?;
-    let path_hash = path.path_entry_hash()?;
-
     let existing = get_links(
-        GetLinksInputBuilder::try_new(path_hash.clone(), LinkTypes::DidBindingLink)?.build(),
+        GetLinksInputBuilder::try_new(did_path(did).path_entry_hash()?, LinkTypes::DidBindingLink)?
+            .build(),
     )?;
     if !existing.is_empty() {
         return Err(wasm_error!(WasmErrorInner::Guest(format!(
-            "DID {} already registered",
+            "DID {} already registered; its current holder can rotate it to a new key",
             did
         ))));
     }
 
-    let binding = DidBinding {
-        did: did.to_string(),
-        agent_pub_key: agent.clone(),
+    publish_did_binding(did, input.public_key, input.signature, None)
+}
+
+/// Input for handing our DID over to another agent key
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RotateDidInput {
+    pub did: String,
+    pub new_agent_pub_key: AgentPubKey,
+}
+
+/// Hand a DID we hold over to a new agent key, e.g. a new device
+///
+/// The DID keeps resolving to us until the new agent calls
+/// `accept_did_rotation` with the returned hash. From this point on
+/// validation rejects mail we write as the DID, and once the handover is
+/// accepted recipients' inboxes drop mail our key sends as it afterwards.
+/// Mail we sent while holding the DID stays in their inboxes.
+#[hdk_extern]
+pub fn rotate_did(input: RotateDidInput) -> ExternResult<ActionHash> {
+    let current = held_did_binding(input.did.trim())?;
+
+    let rotation = DidRotation {
+        did: current.did.clone(),
+        binding: current.binding.clone(),
+        new_agent_pub_key: input.new_agent_pub_key,
+        rotated_at: sys_time()?,
     };
-    let binding_hash = create_entry(EntryTypes::DidBinding(binding))?;
+    let rotation_hash = create_entry(EntryTypes::DidRotation(rotation))?;
+    create_link(current.binding, rotation_hash.clone(), LinkTypes::DidCustody, ())?;
 
-    create_link(
-        path_hash,
-        binding_hash.clone(),
-        LinkTypes::DidBindingLink,
-        (),
-    )?;
+    Ok(rotation_hash)
+}
 
-    Ok(binding_hash)
+/// Input for taking over a DID rotated to our key
+///
+/// The identity key may be a new one; it signs our agent key as in
+/// [`RegisterDidInput`].
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AcceptDidRotationInput {
+    pub rotation_hash: ActionHash,
+    pub public_key: Vec<u8>,
+    pub signature: Signature,
+}
+
+/// Take over a DID the previous holder rotated to our key
+#[hdk_extern]
+pub fn accept_did_rotation(input: AcceptDidRotationInput) -> ExternResult<DidRecord> {
+    let AcceptDidRotationInput {
+        rotation_hash,
+        public_key,
+        signature,
+    } = input;
+    let me = agent_info()?.agent_initial_pubkey;
+    let rotation: DidRotation = get(rotation_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "DID rotation not found".into()
+        )))?;
+
+    if rotation.new_agent_pub_key != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This rotation hands the DID to a different agent".into()
+        )));
+    }
+    let Some(current) = current_did_binding(&rotation.did)?
+        .filter(|record| record.binding == rotation.binding)
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Rotation is stale: {} has moved on from the binding it hands over",
+            rotation.did
+        ))));
+    };
+    if current.revoked.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "DID {} has been revoked",
+            rotation.did
+        ))));
+    }
+
+    let binding = publish_did_binding(&rotation.did, public_key.clone(), signature, Some(rotation_hash))?;
+
+    Ok(DidRecord {
+        did: rotation.did,
+        agent_pub_key: me,
+        public_key,
+        binding,
+        bound_at: sys_time()?,
+        rotations: current.rotations + 1,
+        revoked: None,
+    })
+}
+
+/// Input for revoking the binding of a DID we hold
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokeDidInput {
+    pub did: String,
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Permanently revoke our binding for a DID, e.g. after the key leaked
+///
+/// We can no longer send as the DID and nobody can rotate it afterwards; it
+/// no longer resolves, and inboxes drop mail any key sends as it from now
+/// on. Mail we sent before the revocation is unaffected.
+#[hdk_extern]
+pub fn revoke_did(input: RevokeDidInput) -> ExternResult<ActionHash> {
+    let current = held_did_binding(input.did.trim())?;
+
+    let revocation = DidRevocation {
+        did: current.did.clone(),
+        binding: current.binding.clone(),
+        reason: input.reason.trim().to_string(),
+        revoked_at: sys_time()?,
+    };
+    let revocation_hash = create_entry(EntryTypes::DidRevocation(revocation))?;
+    create_link(current.binding, revocation_hash.clone(), LinkTypes::DidCustody, ())?;
+
+    Ok(revocation_hash)
+}
+
+/// Look up the binding a DID currently resolves through
+///
+/// Returns revoked DIDs too, with `revoked` set, so callers can tell them
+/// apart from unknown ones.
+#[hdk_extern]
+pub fn resolve_did(did: String) -> ExternResult<Option<DidRecord>> {
+    current_did_binding(did.trim())
 }
 
 /// Send a mail message
-/// Creates the message entry and links it to both sender's outbox and recipient's inbox
+/// Creates the message entry and links it to the sender's outbox and every
+/// to and cc recipient's inbox; BCC recipients each get their own copy
 #[hdk_extern]
-pub fn send_message(message: MailMessage) -> ExternResult<ActionHash> {
+pub fn send_message(input: SendMessageInput) -> ExternResult<ActionHash> {
+    let SendMessageInput {
+        message,
+        bcc_copies,
+    } = input;
     debug!(
-        "Sending message from {} to {}",
-        message.from_did, message.to_did
+        "Sending message from {} to {} recipient(s)",
+        message.from_did,
+        message.to_dids.len() + message.cc_dids.len() + bcc_copies.len()
     );
 
     // Get the agent info to verify sender
     let agent_info = agent_info()?;
 
+    // Validation rejects messages whose sender DID is not bound to the
+    // author; fail early with a clearer error than the validation receipt
+    if resolve_did_to_pubkey(&message.from_did)? != agent_info.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Sender DID {} is not registered to this agent",
+            message.from_did
+        ))));
+    }
+
     // Create the message entry on the sender's source chain
     let message_hash = create_entry(EntryTypes::MailMessage(message.clone()))?;
 
@@ -70,37 +309,136 @@ pub fn send_message(message: MailMessage) -> ExternResult<ActionHash> {
         LinkTypes::FromOutbox,
         (),
     )?;
+    index_message(
+        OUTBOX_FOLDER,
+        LinkTypes::OutboxByTime,
+        &agent_info.agent_initial_pubkey,
+        &message_hash,
+        &message.timestamp,
+    )?;
+
+    // One inbox link per distinct named recipient, resolved via the DIDs on the DHT
+    let mut delivered: Vec<AgentPubKey> = Vec::new();
+    for did in message.to_dids.iter().chain(&message.cc_dids) {
+        let recipient_pubkey = resolve_did_to_pubkey(did)?;
+        if delivered.contains(&recipient_pubkey) {
+            continue;
+        }
+
+        deliver(&recipient_pubkey, &message_hash, &message.timestamp)?;
+        delivered.push(recipient_pubkey);
+    }
+
+    // Tell online recipients right away; offline ones find it in their inbox
+    let signal = MailSignal::NewMail {
+        message_hash: message_hash.clone(),
+        from_did: message.from_did.clone(),
+    };
+    if let Err(e) = send_remote_signal(signal, delivered.clone()) {
+        debug!("Could not signal recipients: {:?}", e);
+    }
+
+    // Each BCC recipient gets its own copy, so nothing on the message above
+    // (entry, key slots or links) points at them
+    for copy in bcc_copies {
+        let recipient_pubkey = resolve_did_to_pubkey(&copy.did)?;
+        if delivered.contains(&recipient_pubkey) {
+            continue;
+        }
+        let copy_hash = send_bcc_copy(&message, copy, &recipient_pubkey)?;
+        create_entry(EntryTypes::BccCopyRecord(BccCopyRecord {
+            message_hash: message_hash.clone(),
+            copy_hash: copy_hash.clone(),
+        }))?;
+        delivered.push(recipient_pubkey.clone());
 
-    // Resolve DID to AgentPubKey stored on the DHT
-    let recipient_pubkey = resolve_did_to_pubkey(&message.to_did)?;
+        let signal = MailSignal::NewMail {
+            message_hash: copy_hash,
+            from_did: message.from_did.clone(),
+        };
+        if let Err(e) = send_remote_signal(signal, vec![recipient_pubkey]) {
+            debug!("Could not signal BCC recipient: {:?}", e);
+        }
+    }
+
+    // If this is a reply, link it to its parent and to the conversation root
+    if let Some(thread_id) = &message.thread_id {
+        let parent_hash = parse_thread_id(thread_id).ok_or(wasm_error!(
+            WasmErrorInner::Guest(format!("Invalid thread ID: {}", thread_id))
+        ))?;
+        let root_hash = thread_root(parent_hash.clone())?;
+
+        create_link(
+            parent_hash,
+            message_hash.clone(),
+            LinkTypes::ThreadReply,
+            (),
+        )?;
+        create_link(
+            message_hash.clone(),
+            root_hash.clone(),
+            LinkTypes::ThreadRoot,
+            (),
+        )?;
+        create_link(
+            root_hash,
+            message_hash.clone(),
+            LinkTypes::ThreadMembers,
+            (),
+        )?;
+    }
+
+    debug!("Message sent successfully: {:?}", message_hash);
+    Ok(message_hash)
+}
 
+/// Link a message into a recipient's inbox and its time index
+fn deliver(
+    recipient: &AgentPubKey,
+    message_hash: &ActionHash,
+    timestamp: &Timestamp,
+) -> ExternResult<()> {
     create_link(
-        recipient_pubkey,
+        recipient.clone(),
         message_hash.clone(),
         LinkTypes::ToInbox,
         (),
     )?;
+    index_message(INBOX_FOLDER, LinkTypes::InboxByTime, recipient, message_hash, timestamp)
+}
 
-    // If this is a reply, link to parent message
-    if let Some(thread_id) = &message.thread_id {
-        if let Some(parent_hash) = parse_thread_id(thread_id)? {
-            create_link(
-                parent_hash,
-                message_hash.clone(),
-                LinkTypes::ThreadReply,
-                (),
-            )?;
-        }
-    }
+/// Write and deliver one BCC recipient's copy of `message`
+///
+/// The copy keeps the message's headers but carries its own ciphertext and
+/// key slots. It is not linked into the sender's outbox or the thread, both
+/// of which every participant can read, so a reply to it starts a new
+/// conversation with the sender.
+fn send_bcc_copy(
+    message: &MailMessage,
+    copy: BccCopy,
+    recipient: &AgentPubKey,
+) -> ExternResult<ActionHash> {
+    let copy_message = MailMessage {
+        subject_encrypted: copy.subject_encrypted,
+        body_cid: copy.body_cid,
+        recipient_keys: copy.recipient_keys,
+        timestamp: sys_time()?,
+        ..message.clone()
+    };
+    let copy_hash = create_entry(EntryTypes::MailMessage(copy_message.clone()))?;
+    deliver(recipient, &copy_hash, &copy_message.timestamp)?;
 
-    debug!("Message sent successfully: {:?}", message_hash);
-    Ok(message_hash)
+    Ok(copy_hash)
 }
 
 /// Get all messages in the inbox
-/// Returns all messages linked to the current agent's inbox
+/// Returns all messages linked to the current agent's inbox; large inboxes
+/// should be read a page at a time with `get_inbox_page`
+///
+/// Mail is only returned if its author held its `from_did` when it was sent
+/// (see [`get_inbox_item`]).
 #[hdk_extern]
-pub fn get_inbox(_: ()) -> ExternResult<Vec<MailMessage>> {
+pub fn get_inbox(_: ()) -> ExternResult<Vec<MailItem>> {
     let agent_info = agent_info()?;
 
     // Get all links pointing to this agent's inbox
@@ -109,23 +447,25 @@ pub fn get_inbox(_: ()) -> ExternResult<Vec<MailMessage>> {
     )?;
 
     let mut messages = Vec::new();
+    let mut histories = HashMap::new();
 
-    // Fetch each message
+    // Fetch each message, dropping mail from keys that did not hold its DID
     for link in links {
-        if let Some(message) = get_message_from_link(link)? {
+        let message_hash = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+        if let Some(message) = get_inbox_item(message_hash, &mut histories)? {
             messages.push(message);
         }
     }
 
     // Sort by timestamp (newest first)
-    messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    messages.sort_by(|a, b| b.message.timestamp.cmp(&a.message.timestamp));
 
     Ok(messages)
 }
 
 /// Get sent messages from outbox
 #[hdk_extern]
-pub fn get_outbox(_: ()) -> ExternResult<Vec<MailMessage>> {
+pub fn get_outbox(_: ()) -> ExternResult<Vec<MailItem>> {
     let agent_info = agent_info()?;
 
     let links = get_links(
@@ -142,19 +482,83 @@ pub fn get_outbox(_: ()) -> ExternResult<Vec<MailMessage>> {
     }
 
     // Sort by timestamp (newest first)
-    messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    messages.sort_by(|a, b| b.message.timestamp.cmp(&a.message.timestamp));
 
     Ok(messages)
 }
 
-/// Get messages in a specific thread
+/// Get one page of the inbox, newest first, within an optional time range
+///
+/// Only the day buckets overlapping the range are read and only the
+/// returned messages are fetched, so the cost follows the page size rather
+/// than the size of the inbox.
 #[hdk_extern]
-pub fn get_thread(parent_hash: ActionHash) -> ExternResult<Vec<MailMessage>> {
-    let links =
-        get_links(GetLinksInputBuilder::try_new(parent_hash, LinkTypes::ThreadReply)?.build())?;
+pub fn get_inbox_page(query: MailboxQuery) -> ExternResult<MailPage> {
+    mailbox_page(INBOX_FOLDER, LinkTypes::InboxByTime, query)
+}
+
+/// Get one page of sent messages, newest first, within an optional time range
+#[hdk_extern]
+pub fn get_outbox_page(query: MailboxQuery) -> ExternResult<MailPage> {
+    mailbox_page(OUTBOX_FOLDER, LinkTypes::OutboxByTime, query)
+}
+
+/// Add time-index links for mail delivered or sent before the index existed
+///
+/// Idempotent; returns the number of messages indexed.
+#[hdk_extern]
+pub fn index_mailbox(_: ()) -> ExternResult<u32> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut indexed = 0;
+
+    for (folder, link_type, legacy_type) in [
+        (INBOX_FOLDER, LinkTypes::InboxByTime, LinkTypes::ToInbox),
+        (OUTBOX_FOLDER, LinkTypes::OutboxByTime, LinkTypes::FromOutbox),
+    ] {
+        let mut known = HashSet::new();
+        for bucket in mailbox_buckets(folder, &me, link_type)? {
+            let base = mailbox_path(folder, &me, bucket).path_entry_hash()?;
+            for link in get_links(GetLinksInputBuilder::try_new(base, link_type)?.build())? {
+                if let Some(hash) = link.target.into_action_hash() {
+                    known.insert(hash);
+                }
+            }
+        }
+
+        let links =
+            get_links(GetLinksInputBuilder::try_new(me.clone(), legacy_type)?.build())?;
+        for link in links {
+            let message_hash = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+            if known.contains(&message_hash) {
+                continue;
+            }
+            if let Some(item) = get_mail_item(message_hash)? {
+                index_message(folder, link_type, &me, &item.message_hash, &item.message.timestamp)?;
+                known.insert(item.message_hash);
+                indexed += 1;
+            }
+        }
+    }
+
+    debug!("Indexed {} message(s) into the mailbox time index", indexed);
+    Ok(indexed)
+}
+
+/// Get the whole conversation containing a message
+///
+/// Any message in the thread can be passed; the result includes the root
+/// message and every reply beneath it, not just direct replies.
+#[hdk_extern]
+pub fn get_thread(message_hash: ActionHash) -> ExternResult<Vec<MailItem>> {
+    let root_hash = thread_root(message_hash)?;
 
     let mut messages = Vec::new();
+    if let Some(root) = get_mail_item(root_hash.clone())? {
+        messages.push(root);
+    }
 
+    let links =
+        get_links(GetLinksInputBuilder::try_new(root_hash, LinkTypes::ThreadMembers)?.build())?;
     for link in links {
         if let Some(message) = get_message_from_link(link)? {
             messages.push(message);
@@ -162,118 +566,800 @@ pub fn get_thread(parent_hash: ActionHash) -> ExternResult<Vec<MailMessage>> {
     }
 
     // Sort by timestamp (oldest first for threads)
-    messages.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+    messages.sort_by(|a, b| a.message.timestamp.cmp(&b.message.timestamp));
 
     Ok(messages)
 }
 
 /// Get a single message by its hash
+///
+/// Retracted and expired messages are not returned.
 #[hdk_extern]
 pub fn get_message(message_hash: ActionHash) -> ExternResult<Option<MailMessage>> {
-    let record = get(message_hash, GetOptions::default())?;
-
-    match record {
-        Some(record) => {
-            let message: MailMessage = record
-                .entry()
-                .to_app_option()
-                .map_err(|e| {
-                    wasm_error!(WasmErrorInner::Guest(format!(
-                        "Deserialization error: {:?}",
-                        e
-                    )))
-                })?
-                .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid entry".into())))?;
-            Ok(Some(message))
+    Ok(get_mail_item(message_hash)?.map(|item| item.message))
+}
+
+/// The agent that wrote a message, if it can be found
+#[hdk_extern]
+pub fn get_message_author(message_hash: ActionHash) -> ExternResult<Option<AgentPubKey>> {
+    record_author(&message_hash)
+}
+
+/// Store an encrypted blob (message body) addressed by its CID
+///
+/// Blobs are content addressed, so storing the same ciphertext twice returns
+/// the existing entry instead of creating a duplicate.
+#[hdk_extern]
+pub fn store_blob(blob: EncryptedBlob) -> ExternResult<ActionHash> {
+    if blob.cid != compute_cid(&blob.ciphertext) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Blob CID does not match its content".into()
+        )));
+    }
+
+    let path = blob_path(&blob.cid);
+    let path_hash = path.path_entry_hash()?;
+    let existing = get_links(
+        GetLinksInputBuilder::try_new(path_hash.clone(), LinkTypes::BlobByCid)?.build(),
+    )?;
+    if let Some(link) = existing.first() {
+        return Ok(ActionHash::from_raw_39(link.target.get_raw_39().to_vec()));
+    }
+
+    path.ensure()?;
+    let blob_hash = create_entry(EntryTypes::EncryptedBlob(blob))?;
+    create_link(path_hash, blob_hash.clone(), LinkTypes::BlobByCid, ())?;
+
+    Ok(blob_hash)
+}
+
+/// Fetch an encrypted blob by its CID
+#[hdk_extern]
+pub fn get_blob(cid: String) -> ExternResult<Option<EncryptedBlob>> {
+    let path_hash = blob_path(&cid).path_entry_hash()?;
+    let links =
+        get_links(GetLinksInputBuilder::try_new(path_hash, LinkTypes::BlobByCid)?.build())?;
+
+    for link in links {
+        let hash_any_dht: AnyDhtHash =
+            ActionHash::from_raw_39(link.target.get_raw_39().to_vec()).into();
+        if let Some(record) = get(hash_any_dht, GetOptions::default())? {
+            if let Some(blob) = record.entry().to_app_option::<EncryptedBlob>().map_err(|e| {
+                wasm_error!(WasmErrorInner::Guest(format!(
+                    "Deserialization error: {:?}",
+                    e
+                )))
+            })? {
+                return Ok(Some(blob));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Update the caller's private state for a message
+///
+/// Writes a new private `MessageState` entry with the change applied to the
+/// latest state and returns the result.
+#[hdk_extern]
+pub fn update_message_state(update: MessageStateUpdate) -> ExternResult<MessageState> {
+    let now = sys_time()?;
+    let mut state = latest_message_states()?
+        .remove(&update.message_hash)
+        .unwrap_or_else(|| MessageState {
+            message_hash: update.message_hash.clone(),
+            read: false,
+            starred: false,
+            archived: false,
+            labels: Vec::new(),
+            updated_at: now,
+        });
+
+    if let Some(read) = update.read {
+        state.read = read;
+    }
+    if let Some(starred) = update.starred {
+        state.starred = starred;
+    }
+    if let Some(archived) = update.archived {
+        state.archived = archived;
+    }
+    for label in update.add_labels {
+        let label = label.trim().to_string();
+        if !label.is_empty() && !state.labels.contains(&label) {
+            state.labels.push(label);
         }
-        None => Ok(None),
     }
+    state
+        .labels
+        .retain(|label| !update.remove_labels.iter().any(|r| r.trim() == label));
+    state.updated_at = now;
+
+    create_entry(EntryTypes::MessageState(state.clone()))?;
+    Ok(state)
+}
+
+/// Get the caller's private state for every message that has any
+#[hdk_extern]
+pub fn get_message_states(_: ()) -> ExternResult<Vec<MessageState>> {
+    Ok(latest_message_states()?.into_values().collect())
 }
 
-/// Delete a message (creates a delete action)
+/// Delete a message: retract it if we sent it, otherwise remove it from
+/// our inbox
 #[hdk_extern]
 pub fn delete_message(message_hash: ActionHash) -> ExternResult<ActionHash> {
-    delete_entry(message_hash)
+    let me = agent_info()?.agent_initial_pubkey;
+    let record = get(message_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Message not found".into())
+    ))?;
+
+    if record.action().author() == &me {
+        retract_message(message_hash)
+    } else {
+        remove_from_inbox(message_hash)
+    }
+}
+
+/// Retract a message we sent, for every recipient
+///
+/// Deletes every link we created to or from the message (outbox, each
+/// recipient's inbox, time index and thread links) and then the entry
+/// itself, which leaves a delete tombstone on the DHT. BCC copies of the
+/// message are retracted the same way. Returns the hash of the message's
+/// delete.
+#[hdk_extern]
+pub fn retract_message(message_hash: ActionHash) -> ExternResult<ActionHash> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let record = get(message_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Message not found".into())
+    ))?;
+    if record.action().author() != &me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the sender can retract a message; use remove_from_inbox instead".into()
+        )));
+    }
+
+    ChainIndex::read()?.retract(message_hash)
+}
+
+/// The links and BCC copies we wrote, read from our own chain once so that
+/// retracting many messages does not re-query the chain for each
+struct ChainIndex {
+    /// Our undeleted links, keyed by every address they start or end at
+    links: HashMap<AnyLinkableHash, Vec<ActionHash>>,
+    /// Links deleted so far, including during this call
+    deleted: HashSet<ActionHash>,
+    /// BCC copies of each message we sent
+    copies: HashMap<ActionHash, Vec<ActionHash>>,
+}
+
+impl ChainIndex {
+    fn read() -> ExternResult<Self> {
+        let deleted: HashSet<ActionHash> =
+            query(ChainQueryFilter::new().action_type(ActionType::DeleteLink))?
+                .into_iter()
+                .filter_map(|record| match record.action() {
+                    Action::DeleteLink(delete) => Some(delete.link_add_address.clone()),
+                    _ => None,
+                })
+                .collect();
+
+        let mut links: HashMap<AnyLinkableHash, Vec<ActionHash>> = HashMap::new();
+        for record in query(ChainQueryFilter::new().action_type(ActionType::CreateLink))? {
+            let Action::CreateLink(create) = record.action() else {
+                continue;
+            };
+            if deleted.contains(record.action_address()) {
+                continue;
+            }
+            for address in [&create.base_address, &create.target_address] {
+                links
+                    .entry(address.clone())
+                    .or_default()
+                    .push(record.action_address().clone());
+            }
+        }
+
+        let mut copies: HashMap<ActionHash, Vec<ActionHash>> = HashMap::new();
+        let copy_records = query(
+            ChainQueryFilter::new()
+                .entry_type(UnitEntryTypes::BccCopyRecord.try_into()?)
+                .include_entries(true),
+        )?;
+        for record in copy_records {
+            if let Ok(Some(copy)) = record.entry().to_app_option::<BccCopyRecord>() {
+                copies.entry(copy.message_hash).or_default().push(copy.copy_hash);
+            }
+        }
+
+        Ok(Self {
+            links,
+            deleted,
+            copies,
+        })
+    }
+
+    /// Retract a message of ours and its BCC copies
+    fn retract(&mut self, message_hash: ActionHash) -> ExternResult<ActionHash> {
+        for copy_hash in self.copies.remove(&message_hash).unwrap_or_default() {
+            self.retract_one(copy_hash)?;
+        }
+        self.retract_one(message_hash)
+    }
+
+    /// Delete every link we wrote to or from a message, then the message
+    fn retract_one(&mut self, message_hash: ActionHash) -> ExternResult<ActionHash> {
+        let address: AnyLinkableHash = message_hash.clone().into();
+        let mut removed = 0;
+        for link_hash in self.links.remove(&address).unwrap_or_default() {
+            // Links between two of our messages are listed under both
+            if self.deleted.insert(link_hash.clone()) {
+                delete_link(link_hash, GetOptions::default())?;
+                removed += 1;
+            }
+        }
+
+        debug!("Retracted message {:?} ({} link(s) removed)", message_hash, removed);
+        delete_entry(message_hash)
+    }
+}
+
+/// Remove a message from our own inbox without affecting other recipients
+///
+/// Validation only lets authors delete their own entries, so recipients
+/// delete their inbox link instead. Returns the hash of that delete.
+#[hdk_extern]
+pub fn remove_from_inbox(message_hash: ActionHash) -> ExternResult<ActionHash> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(GetLinksInputBuilder::try_new(me.clone(), LinkTypes::ToInbox)?.build())?;
+    let inbox_link = links
+        .into_iter()
+        .find(|link| ActionHash::from_raw_39(link.target.get_raw_39().to_vec()) == message_hash)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Message is not in your inbox".into()
+        )))?;
+
+    // Drop it from the time index too, so pages no longer return it
+    if let Some(item) = fetch_mail_item(message_hash.clone())? {
+        let bucket = mailbox_bucket(&item.message.timestamp);
+        let base = mailbox_path(INBOX_FOLDER, &me, bucket).path_entry_hash()?;
+        let indexed = get_links(
+            GetLinksInputBuilder::try_new(base, LinkTypes::InboxByTime)?.build(),
+        )?;
+        for link in indexed {
+            if link.target.clone().into_action_hash().as_ref() == Some(&message_hash) {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+    }
+
+    delete_link(inbox_link.create_link_hash, GetOptions::default())
+}
+
+/// Clean up expired mail
+///
+/// Expired messages we received are removed from our inbox and expired
+/// messages we sent are retracted. Our inbox links, each affected index
+/// bucket and our own chain are read once, however much mail has expired.
+/// Returns the number of messages cleaned up.
+#[hdk_extern]
+pub fn purge_expired(_: ()) -> ExternResult<u32> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let mut purged = 0;
+
+    // Received mail: drop the inbox links now, the time index bucket by bucket
+    let mut expired_by_bucket: HashMap<i64, HashSet<ActionHash>> = HashMap::new();
+    let inbox = get_links(GetLinksInputBuilder::try_new(me.clone(), LinkTypes::ToInbox)?.build())?;
+    for link in inbox {
+        let message_hash = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+        let Some((item, author)) = fetch_mail_record(message_hash.clone())? else {
+            continue;
+        };
+        if !item.message.is_expired(&now) || author == me {
+            continue;
+        }
+        delete_link(link.create_link_hash, GetOptions::default())?;
+        if expired_by_bucket
+            .entry(mailbox_bucket(&item.message.timestamp))
+            .or_default()
+            .insert(message_hash)
+        {
+            purged += 1;
+        }
+    }
+    for (bucket, expired) in expired_by_bucket {
+        let base = mailbox_path(INBOX_FOLDER, &me, bucket).path_entry_hash()?;
+        let indexed = get_links(GetLinksInputBuilder::try_new(base, LinkTypes::InboxByTime)?.build())?;
+        for link in indexed {
+            if link
+                .target
+                .clone()
+                .into_action_hash()
+                .is_some_and(|hash| expired.contains(&hash))
+            {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+    }
+
+    // Sent mail: retract, reading our chain only if something has expired
+    let mut chain: Option<ChainIndex> = None;
+    let mut seen = HashSet::new();
+    let outbox = get_links(GetLinksInputBuilder::try_new(me, LinkTypes::FromOutbox)?.build())?;
+    for link in outbox {
+        let message_hash = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+        if !seen.insert(message_hash.clone()) {
+            continue;
+        }
+        let Some(item) = fetch_mail_item(message_hash.clone())? else {
+            continue;
+        };
+        if item.message.is_expired(&now) {
+            if chain.is_none() {
+                chain = Some(ChainIndex::read()?);
+            }
+            if let Some(chain) = chain.as_mut() {
+                chain.retract(message_hash)?;
+            }
+            purged += 1;
+        }
+    }
+
+    debug!("Purged {} expired message(s)", purged);
+    Ok(purged)
 }
 
 // === Helper Functions ===
 
 /// Helper function to get a message from a link
-fn get_message_from_link(link: Link) -> ExternResult<Option<MailMessage>> {
-    // The link target is an AnyLinkableHash which we convert to ActionHash then to AnyDhtHash
-    let hash_any_dht: AnyDhtHash =
-        ActionHash::from_raw_39(link.target.get_raw_39().to_vec()).into();
-    let record = get(hash_any_dht, GetOptions::default())?;
-
-    match record {
-        Some(record) => {
-            let message: MailMessage = record
-                .entry()
-                .to_app_option()
-                .map_err(|e| {
-                    wasm_error!(WasmErrorInner::Guest(format!(
-                        "Deserialization error: {:?}",
-                        e
-                    )))
-                })?
-                .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid entry".into())))?;
-            Ok(Some(message))
+fn get_message_from_link(link: Link) -> ExternResult<Option<MailItem>> {
+    // The link target is an AnyLinkableHash which we convert to ActionHash
+    get_mail_item(ActionHash::from_raw_39(link.target.get_raw_39().to_vec()))
+}
+
+/// Fetch a live message together with its action hash
+///
+/// Retracted and expired messages count as missing.
+fn get_mail_item(message_hash: ActionHash) -> ExternResult<Option<MailItem>> {
+    let now = sys_time()?;
+    Ok(fetch_mail_item(message_hash)?.filter(|item| !item.message.is_expired(&now)))
+}
+
+/// Fetch a message unless it has been retracted; expired ones are included
+fn fetch_mail_item(message_hash: ActionHash) -> ExternResult<Option<MailItem>> {
+    Ok(fetch_mail_record(message_hash)?.map(|(item, _)| item))
+}
+
+/// Like [`fetch_mail_item`], together with the agent that wrote the message
+fn fetch_mail_record(message_hash: ActionHash) -> ExternResult<Option<(MailItem, AgentPubKey)>> {
+    let Some(Details::Record(details)) = get_details(message_hash.clone(), GetOptions::default())?
+    else {
+        return Ok(None);
+    };
+    if !details.deletes.is_empty() {
+        return Ok(None);
+    }
+
+    let message: MailMessage = details
+        .record
+        .entry()
+        .to_app_option()
+        .map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Deserialization error: {:?}",
+                e
+            )))
+        })?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid entry".into())))?;
+    let author = details.record.action().author().clone();
+
+    Ok(Some((
+        MailItem {
+            message_hash,
+            message,
+        },
+        author,
+    )))
+}
+
+/// Fetch a live message for the inbox, if its sender DID vouches for it
+///
+/// Validation only sees the author's own chain, so it cannot stop a retired
+/// or leaked identity key from binding the DID afresh on a new agent and
+/// sending from there. The inbox therefore only accepts mail written by the
+/// agent that held `from_did` in its custody chain at the message's
+/// timestamp: mail sent before a rotation or revocation stays, mail a key
+/// sent after losing the DID does not. `histories` caches custody histories
+/// across one query.
+fn get_inbox_item(
+    message_hash: ActionHash,
+    histories: &mut HashMap<String, Vec<DidTenure>>,
+) -> ExternResult<Option<MailItem>> {
+    let now = sys_time()?;
+    let Some((item, author)) = fetch_mail_record(message_hash)? else {
+        return Ok(None);
+    };
+    if item.message.is_expired(&now) {
+        return Ok(None);
+    }
+
+    let from_did = &item.message.from_did;
+    if !histories.contains_key(from_did) {
+        histories.insert(from_did.clone(), did_custody_history(from_did.clone())?);
+    }
+    if did_holder_at(&histories[from_did], &item.message.timestamp) != Some(&author) {
+        debug!(
+            "Skipping {:?}: its author did not hold {} when it was sent",
+            item.message_hash, from_did
+        );
+        return Ok(None);
+    }
+
+    Ok(Some(item))
+}
+
+/// Author of the action at `hash`, if it can be found
+fn record_author(hash: &ActionHash) -> ExternResult<Option<AgentPubKey>> {
+    Ok(get(hash.clone(), GetOptions::default())?.map(|record| record.action().author().clone()))
+}
+
+/// Link a message into `owner`'s bucket of `folder` for its timestamp
+///
+/// The first message in a bucket also links the bucket from the owner's
+/// mailbox root, naming itself in the tag so the link can be validated.
+fn index_message(
+    folder: &str,
+    link_type: LinkTypes,
+    owner: &AgentPubKey,
+    message_hash: &ActionHash,
+    timestamp: &Timestamp,
+) -> ExternResult<()> {
+    let bucket = mailbox_path(folder, owner, mailbox_bucket(timestamp)).path_entry_hash()?;
+    let root = mailbox_root(folder, owner).path_entry_hash()?;
+    let bucket_links = get_links(GetLinksInputBuilder::try_new(root.clone(), link_type)?.build())?;
+    if !bucket_links
+        .iter()
+        .any(|link| link.target.clone().into_entry_hash().as_ref() == Some(&bucket))
+    {
+        create_link(
+            root,
+            bucket.clone(),
+            link_type,
+            mailbox_bucket_tag(owner, timestamp, message_hash),
+        )?;
+    }
+
+    create_link(bucket, message_hash.clone(), link_type, mailbox_tag(owner, timestamp))?;
+    Ok(())
+}
+
+/// Day buckets that hold any of `owner`'s mail in `folder`
+fn mailbox_buckets(
+    folder: &str,
+    owner: &AgentPubKey,
+    link_type: LinkTypes,
+) -> ExternResult<Vec<i64>> {
+    let root = mailbox_root(folder, owner).path_entry_hash()?;
+    let links = get_links(GetLinksInputBuilder::try_new(root, link_type)?.build())?;
+    let buckets: BTreeSet<i64> = links
+        .iter()
+        .filter_map(|link| parse_mailbox_bucket_tag(&link.tag))
+        .filter(|(tag_owner, _, _)| tag_owner == owner)
+        .map(|(_, timestamp, _)| mailbox_bucket(&timestamp))
+        .collect();
+    Ok(buckets.into_iter().collect())
+}
+
+/// Read one page of a time-indexed mailbox, newest first
+///
+/// Messages are ordered by (timestamp, hash); the cursor is the position of
+/// the last message on the previous page, and the next page starts strictly
+/// below it. Inbox pages skip mail whose author did not hold its sender DID
+/// when it was sent, as `get_inbox` does.
+fn mailbox_page(folder: &str, link_type: LinkTypes, query: MailboxQuery) -> ExternResult<MailPage> {
+    let owner = agent_info()?.agent_initial_pubkey;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let after = match query.cursor {
+        Some(cursor) => Some(parse_mailbox_cursor(&cursor).ok_or(wasm_error!(
+            WasmErrorInner::Guest(format!("Invalid page cursor: {}", cursor))
+        ))?),
+        None => None,
+    };
+
+    let in_range = |timestamp: &Timestamp| {
+        query.since.map_or(true, |since| *timestamp >= since)
+            && query.until.map_or(true, |until| *timestamp <= until)
+    };
+
+    let mut buckets = mailbox_buckets(folder, &owner, link_type)?;
+    buckets.retain(|bucket| {
+        query.since.map_or(true, |since| *bucket >= mailbox_bucket(&since))
+            && query.until.map_or(true, |until| *bucket <= mailbox_bucket(&until))
+            && after
+                .as_ref()
+                .map_or(true, |(timestamp, _)| *bucket <= mailbox_bucket(timestamp))
+    });
+    buckets.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut items: Vec<MailItem> = Vec::new();
+    let mut histories = HashMap::new();
+    for bucket in buckets {
+        let base = mailbox_path(folder, &owner, bucket).path_entry_hash()?;
+        let links = get_links(GetLinksInputBuilder::try_new(base, link_type)?.build())?;
+
+        // Order by the timestamp in the tag so only returned messages are fetched
+        let mut positions: Vec<(Timestamp, ActionHash)> = links
+            .into_iter()
+            .filter_map(|link| {
+                let (_, timestamp) = parse_mailbox_tag(&link.tag)?;
+                Some((timestamp, link.target.into_action_hash()?))
+            })
+            .filter(|(timestamp, _)| in_range(timestamp))
+            .filter(|position| after.as_ref().map_or(true, |after| position < after))
+            .collect();
+        positions.sort_unstable_by(|a, b| b.cmp(a));
+        positions.dedup();
+
+        for (_, message_hash) in positions {
+            if items.len() == limit {
+                let next_cursor = items
+                    .last()
+                    .map(|last| encode_mailbox_cursor(&last.message.timestamp, &last.message_hash));
+                return Ok(MailPage { items, next_cursor });
+            }
+            let item = if folder == INBOX_FOLDER {
+                get_inbox_item(message_hash, &mut histories)?
+            } else {
+                get_mail_item(message_hash)?
+            };
+            if let Some(item) = item {
+                items.push(item);
+            }
         }
-        None => Ok(None),
     }
+
+    Ok(MailPage {
+        items,
+        next_cursor: None,
+    })
 }
 
-/// Resolve a DID to an AgentPubKey
-/// In production, this would query the DHT for DID -> PubKey mappings
-/// For MVP, we'll use a simplified mock
+/// Resolve a DID to the AgentPubKey of its current, unrevoked binding
 fn resolve_did_to_pubkey(did: &str) -> ExternResult<AgentPubKey> {
-    let path = did_path(did);
-    let path_hash = path.path_entry_hash()?;
+    match current_did_binding(did)? {
+        Some(record) if record.revoked.is_none() => Ok(record.agent_pub_key),
+        Some(_) => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "DID {} has been revoked",
+            did
+        )))),
+        None => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "DID {} is not registered in this DNA",
+            did
+        )))),
+    }
+}
 
-    let links =
-        get_links(GetLinksInputBuilder::try_new(path_hash, LinkTypes::DidBindingLink)?.build())?;
+/// Follow a DID from its first binding through every claimed rotation
+///
+/// A rotation the new agent has not accepted yet leaves the DID with its
+/// previous holder. A revocation ends the walk at the revoked binding.
+fn current_did_binding(did: &str) -> ExternResult<Option<DidRecord>> {
+    Ok(did_custody(did)?.map(|(record, _)| record))
+}
 
-    if let Some(link) = links.first() {
-        let hash_any_dht: AnyDhtHash =
-            ActionHash::from_raw_39(link.target.get_raw_39().to_vec()).into();
-        if let Some(record) = get(hash_any_dht, GetOptions::default())? {
-            let binding: DidBinding = record
-                .entry()
-                .to_app_option()
-                .map_err(|e| {
-                    wasm_error!(WasmErrorInner::Guest(format!(
-                        "Deserialization error: {:?}",
-                        e
-                    )))
-                })?
-                .ok_or(wasm_error!(WasmErrorInner::Guest(
-                    "Invalid DID binding entry".into()
-                )))?;
-
-            return Ok(binding.agent_pub_key);
+/// Every agent that has held a DID and when, oldest first
+///
+/// Mail is judged against whoever held its `from_did` when it was sent, so
+/// a rotation or revocation only affects mail sent after it.
+#[hdk_extern]
+pub fn did_custody_history(did: String) -> ExternResult<Vec<DidTenure>> {
+    Ok(did_custody(did.trim())?
+        .map(|(_, history)| history)
+        .unwrap_or_default())
+}
+
+/// Walk a DID's custody chain: where it points now, and who held it when
+fn did_custody(did: &str) -> ExternResult<Option<(DidRecord, Vec<DidTenure>)>> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(did_path(did).path_entry_hash()?, LinkTypes::DidBindingLink)?
+            .build(),
+    )?;
+
+    let mut bindings = Vec::new();
+    for link in links {
+        let binding_hash = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+        let Some(record) = get(binding_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Ok(Some(binding)) = record.entry().to_app_option::<DidBinding>() {
+            if binding.did == did {
+                bindings.push((binding_hash, binding, record.action().timestamp()));
+            }
+        }
+    }
+
+    // Only the identity key's holder can publish a first binding; if they
+    // published several, the earliest counts
+    let Some((mut binding_hash, mut binding, mut bound_at)) = bindings
+        .iter()
+        .filter(|(_, binding, _)| binding.rotation.is_none())
+        .min_by_key(|(_, _, bound_at)| *bound_at)
+        .cloned()
+    else {
+        return Ok(None);
+    };
+
+    let mut history = Vec::new();
+    let mut rotations = 0;
+    loop {
+        let custody = get_links(
+            GetLinksInputBuilder::try_new(binding_hash.clone(), LinkTypes::DidCustody)?.build(),
+        )?;
+
+        let mut handover = None;
+        for link in custody {
+            let action_hash = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+            let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+                continue;
+            };
+            if let Ok(Some(revocation)) = record.entry().to_app_option::<DidRevocation>() {
+                history.push(DidTenure {
+                    agent_pub_key: binding.agent_pub_key.clone(),
+                    from: bound_at,
+                    until: Some(record.action().timestamp()),
+                });
+                let current = DidRecord {
+                    did: did.to_string(),
+                    agent_pub_key: binding.agent_pub_key,
+                    public_key: binding.public_key,
+                    binding: binding_hash,
+                    bound_at,
+                    rotations,
+                    revoked: Some(revocation.reason),
+                };
+                return Ok(Some((current, history)));
+            }
+            if matches!(record.entry().to_app_option::<DidRotation>(), Ok(Some(_))) {
+                handover = Some(action_hash);
+            }
         }
+
+        let next = handover.and_then(|rotation_hash| {
+            bindings
+                .iter()
+                .filter(|(_, next, _)| next.rotation.as_ref() == Some(&rotation_hash))
+                .min_by_key(|(_, _, bound_at)| *bound_at)
+                .cloned()
+        });
+        let Some(next) = next else {
+            break;
+        };
+        // The previous holder keeps the DID until the handover is accepted
+        history.push(DidTenure {
+            agent_pub_key: binding.agent_pub_key.clone(),
+            from: bound_at,
+            until: Some(next.2),
+        });
+        (binding_hash, binding, bound_at) = next;
+        rotations += 1;
     }
 
-    Err(wasm_error!(WasmErrorInner::Guest(format!(
-        "DID {} is not registered in this DNA",
-        did
-    ))))
+    history.push(DidTenure {
+        agent_pub_key: binding.agent_pub_key.clone(),
+        from: bound_at,
+        until: None,
+    });
+    let current = DidRecord {
+        did: did.to_string(),
+        agent_pub_key: binding.agent_pub_key,
+        public_key: binding.public_key,
+        binding: binding_hash,
+        bound_at,
+        rotations,
+        revoked: None,
+    };
+    Ok(Some((current, history)))
 }
 
-/// Parse a thread ID into an ActionHash
-fn parse_thread_id(thread_id: &str) -> ExternResult<Option<ActionHash>> {
-    // Thread ID format: "msg_<base64_encoded_hash>"
-    if let Some(_hash_str) = thread_id.strip_prefix("msg_") {
-        // Decode base64 and convert to ActionHash
-        // For MVP, we'll return None
-        // In production, implement proper parsing
-        debug!("Parsing thread ID: {}", thread_id);
-        Ok(None)
-    } else {
-        Ok(None)
+/// The caller's binding for `did`, which must be the one in force
+fn held_did_binding(did: &str) -> ExternResult<DidRecord> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let current = current_did_binding(did)?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        format!("DID {} is not registered in this DNA", did)
+    )))?;
+
+    if current.agent_pub_key != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "DID {} is held by another agent key",
+            did
+        ))));
     }
+    if current.revoked.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "DID {} has been revoked",
+            did
+        ))));
+    }
+
+    Ok(current)
+}
+
+/// Bind `did` to our key and index the binding under the DID's path
+fn publish_did_binding(
+    did: &str,
+    public_key: Vec<u8>,
+    signature: Signature,
+    rotation: Option<ActionHash>,
+) -> ExternResult<ActionHash> {
+    let binding = DidBinding {
+        did: did.to_string(),
+        agent_pub_key: agent_info()?.agent_initial_pubkey,
+        public_key,
+        signature,
+        rotation,
+    };
+    let binding_hash = create_entry(EntryTypes::DidBinding(binding))?;
+
+    let path = did_path(did);
+    path.ensure()?;
+    create_link(
+        path.path_entry_hash()?,
+        binding_hash.clone(),
+        LinkTypes::DidBindingLink,
+        (),
+    )?;
+
+    Ok(binding_hash)
+}
+
+/// Find the first message of the conversation containing `message_hash`
+///
+/// Every reply carries a `ThreadRoot` link written when it was sent, so this
+/// is a single lookup however deep the reply is. A message without one is
+/// the root of its own thread.
+fn thread_root(message_hash: ActionHash) -> ExternResult<ActionHash> {
+    let links = get_links(
+        GetLinksInputBuilder::try_new(message_hash.clone(), LinkTypes::ThreadRoot)?.build(),
+    )?;
+
+    Ok(links
+        .first()
+        .map(|link| ActionHash::from_raw_39(link.target.get_raw_39().to_vec()))
+        .unwrap_or(message_hash))
+}
+
+/// Latest private state per message, read from our own source chain
+fn latest_message_states() -> ExternResult<HashMap<ActionHash, MessageState>> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::MessageState.try_into()?)
+        .include_entries(true);
+
+    // Records come back in chain order, so later entries overwrite earlier ones
+    let mut states = HashMap::new();
+    for record in query(filter)? {
+        if let Some(state) = record.entry().to_app_option::<MessageState>().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Deserialization error: {:?}",
+                e
+            )))
+        })? {
+            states.insert(state.message_hash.clone(), state);
+        }
+    }
+
+    Ok(states)
 }
 
 fn did_path(did: &str) -> Path {
     Path::from(format!("did_index.{}", did))
 }
+
+fn blob_path(cid: &str) -> Path {
+    Path::from(format!("blob.{}", cid))
+}
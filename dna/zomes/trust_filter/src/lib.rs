@@ -1,5 +1,6 @@
 use hdk::prelude::*;
 use mycelix_mail_integrity::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Input for spam reporting
 #[derive(Serialize, Deserialize, Debug)]
@@ -14,54 +15,139 @@ pub struct GetSpamReportsInput {
     pub since: Timestamp,
 }
 
+/// Input for filtering one page of the inbox
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FilterInboxPageInput {
+    pub min_trust: f64,
+    #[serde(default)]
+    pub query: MailboxQuery,
+}
+
+/// Input for checking a single delivered message against the spam filter
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScreenMessageInput {
+    pub message_hash: ActionHash,
+    pub min_trust: f64,
+}
+
+/// Input for adding or changing an allow/block rule
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetSenderRuleInput {
+    /// Exact DID or DID prefix ending in `*`
+    pub pattern: String,
+    pub action: RuleAction,
+}
+
+/// One source's contribution to a DID's trust score
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SourceTrust {
+    pub source: String,
+    pub score: f64,
+    /// Configured weight of the source (`trust_source_weights`)
+    pub weight: f64,
+    /// How far the score can be relied on, from 0.0 to 1.0
+    pub confidence: f64,
+    /// Fraction of the final score this source accounts for
+    pub share: f64,
+    /// When the score was last updated; `None` for computed scores
+    pub last_updated: Option<Timestamp>,
+    /// Evidence behind a computed score
+    pub detail: Option<String>,
+}
+
+/// A DID's final trust score with the per-source scores behind it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrustBreakdown {
+    pub did: String,
+    pub score: f64,
+    pub sources: Vec<SourceTrust>,
+}
+
 /// Check the trust score for a specific DID
-/// This queries the local MATL trust scores stored on the DHT
+/// This is the aggregate of every source, see `get_trust_breakdown`
 #[hdk_extern]
 pub fn check_sender_trust(did: String) -> ExternResult<f64> {
     debug!("Checking trust for DID: {}", did);
+    Ok(get_trust_breakdown(did)?.score)
+}
 
-    // Use a Path for DID-based lookup
-    let path = Path::from(format!("trust.{}", did));
-    let path_hash = path.path_entry_hash()?;
+/// Combine every trust source for a DID and explain the result
+///
+/// Sources are the newest published score per `matl_source` (MATL, manual
+/// overrides, ...) plus the locally computed score. Each counts in
+/// proportion to its configured weight times its confidence: published
+/// scores lose confidence with `trust_score_half_life_days` (manual ones
+/// never do), and the local score gains it with the amount of history
+/// behind it. With nothing to go on, the local prior is returned.
+#[hdk_extern]
+pub fn get_trust_breakdown(did: String) -> ExternResult<TrustBreakdown> {
+    let properties = DnaProperties::get()?;
+    let now = sys_time()?;
 
-    // Query trust scores linked to this DID path
-    let links =
-        get_links(GetLinksInputBuilder::try_new(path_hash, LinkTypes::TrustByDid)?.build())?;
+    let mut sources = Vec::new();
+    for (source, published) in latest_scores_by_source(&did)? {
+        let confidence = if source == MANUAL_TRUST_SOURCE {
+            1.0
+        } else {
+            decay_weight(
+                published.score.last_updated,
+                now,
+                properties.trust_score_half_life_days,
+            )
+        };
+        sources.push(SourceTrust {
+            weight: properties.source_weight(&source),
+            source,
+            score: published.score.score,
+            confidence,
+            share: 0.0,
+            last_updated: Some(published.score.last_updated),
+            detail: None,
+        });
+    }
 
-    // If we have a trust score, return it
-    if let Some(link) = links.first() {
-        // Convert the link target to the appropriate hash type
-        let hash_any_dht: AnyDhtHash =
-            ActionHash::from_raw_39(link.target.get_raw_39().to_vec()).into();
-        let record = get(hash_any_dht, GetOptions::default())?;
-
-        if let Some(record) = record {
-            let trust_score: TrustScore = record
-                .entry()
-                .to_app_option()
-                .map_err(|e| {
-                    wasm_error!(WasmErrorInner::Guest(format!(
-                        "Deserialization error: {:?}",
-                        e
-                    )))
-                })?
-                .ok_or(wasm_error!(WasmErrorInner::Guest(
-                    "Invalid trust score".into()
-                )))?;
-
-            debug!("Found trust score for {}: {}", did, trust_score.score);
-            return Ok(trust_score.score);
+    let local = calculate_local_trust(&did, &properties)?;
+    sources.push(SourceTrust {
+        source: LOCAL_TRUST_SOURCE.to_string(),
+        score: local.score,
+        weight: properties.source_weight(LOCAL_TRUST_SOURCE),
+        confidence: local.confidence,
+        share: 0.0,
+        last_updated: None,
+        detail: Some(format!(
+            "{} correspondent(s), {} spam report(s), {} released",
+            local.correspondents, local.reports, local.releases
+        )),
+    });
+
+    let total: f64 = sources.iter().map(|s| s.weight * s.confidence).sum();
+    let score = if total > 0.0 {
+        for source in &mut sources {
+            source.share = source.weight * source.confidence / total;
         }
-    }
+        sources.iter().map(|s| s.share * s.score).sum()
+    } else {
+        local.score
+    };
 
-    // Default: neutral score for new/unknown users (0.5)
-    // This allows new users to send mail, but they start with low reputation
-    debug!("No trust score found for {}, returning default 0.5", did);
-    Ok(0.5)
+    debug!(
+        "Trust for {}: {:.3} from {} source(s)",
+        did,
+        score,
+        sources.len()
+    );
+    Ok(TrustBreakdown {
+        did,
+        score,
+        sources,
+    })
 }
 
 /// Update or create a trust score for a DID
 /// This is called by the MATL bridge to sync trust scores from the MATL system
+///
+/// Links to the caller's previous scores for the same DID and source are
+/// removed, so lookups only ever see the newest one.
 #[hdk_extern]
 pub fn update_trust_score(trust_score: TrustScore) -> ExternResult<ActionHash> {
     debug!(
@@ -69,11 +155,13 @@ pub fn update_trust_score(trust_score: TrustScore) -> ExternResult<ActionHash> {
         trust_score.did, trust_score.score
     );
 
+    let me = agent_info()?.agent_initial_pubkey;
+
     // Create the trust score entry
     let score_hash = create_entry(EntryTypes::TrustScore(trust_score.clone()))?;
 
     // Use a Path for DID-based lookup
-    let path = Path::from(format!("trust.{}", trust_score.did));
+    let path = trust_path(&trust_score.did);
     path.ensure()?;
     let path_hash = path.path_entry_hash()?;
 
@@ -84,65 +172,252 @@ pub fn update_trust_score(trust_score: TrustScore) -> ExternResult<ActionHash> {
     let index_path = Path::from("trust_index");
     index_path.ensure()?;
     let index_hash = index_path.path_entry_hash()?;
-    create_link(index_hash, score_hash.clone(), LinkTypes::TrustIndex, ())?;
+    create_link(index_hash.clone(), score_hash.clone(), LinkTypes::TrustIndex, ())?;
+
+    // Remove links to the scores this one supersedes
+    let mut superseded = Vec::new();
+    for (link, published) in published_scores(&trust_score.did)? {
+        if link.author == me
+            && published.action_hash != score_hash
+            && published.score.matl_source == trust_score.matl_source
+            && published.score.last_updated <= trust_score.last_updated
+        {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+            superseded.push(published.action_hash);
+        }
+    }
+
+    if !superseded.is_empty() {
+        let index_links =
+            get_links(GetLinksInputBuilder::try_new(index_hash, LinkTypes::TrustIndex)?.build())?;
+        for link in index_links {
+            let target = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+            if link.author == me && superseded.contains(&target) {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+        debug!("Removed {} superseded trust score(s)", superseded.len());
+    }
 
     Ok(score_hash)
 }
 
+/// A message held back by the spam filter, with the reason why
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuarantinedMail {
+    pub item: MailItem,
+    /// Sender's trust score; 0.0 for blocked senders
+    pub trust_score: f64,
+    pub reason: String,
+    /// Pattern of the block rule that caught the sender, if any
+    pub blocked_by: Option<String>,
+}
+
 /// Get filtered inbox messages based on minimum trust threshold
 /// This is the KEY SPAM FILTER - uses MATL scores to filter messages
+///
+/// Messages below the threshold are not dropped; they are listed by
+/// `get_quarantine` until released.
 #[hdk_extern]
-pub fn filter_inbox(min_trust: f64) -> ExternResult<Vec<MailMessage>> {
+pub fn filter_inbox(min_trust: f64) -> ExternResult<Vec<MailItem>> {
     debug!("Filtering inbox with min_trust: {}", min_trust);
+    Ok(partition_inbox(min_trust)?.0)
+}
 
-    // Call the mail_messages zome to get all inbox messages
-    let response: ZomeCallResponse = call(
-        CallTargetCell::Local,
-        "mail_messages",
-        "get_inbox".into(),
-        None,
-        (),
-    )?;
+/// Filter one page of the inbox (see `mail_messages::get_inbox_page`)
+///
+/// Quarantined messages are left out, so a page can hold fewer than
+/// `limit` messages; `next_cursor` still continues after the whole page.
+#[hdk_extern]
+pub fn filter_inbox_page(input: FilterInboxPageInput) -> ExternResult<MailPage> {
+    let page: MailPage = call_mail_zome("get_inbox_page", input.query)?;
+    Ok(MailPage {
+        items: partition_items(page.items, input.min_trust)?.0,
+        next_cursor: page.next_cursor,
+    })
+}
 
-    // Decode the response
-    let all_messages: Vec<MailMessage> = match response {
-        ZomeCallResponse::Ok(result) => decode(&result.into_vec()).map_err(|e| {
+/// Messages the spam filter holds back at `min_trust`, with score and reason
+#[hdk_extern]
+pub fn get_quarantine(min_trust: f64) -> ExternResult<Vec<QuarantinedMail>> {
+    Ok(partition_inbox(min_trust)?.1)
+}
+
+/// Run one message through the same filter as `filter_inbox`
+///
+/// Returns `None` if it would reach the inbox at `min_trust`, otherwise
+/// why it would be quarantined. Used to screen new-mail notifications.
+#[hdk_extern]
+pub fn screen_message(input: ScreenMessageInput) -> ExternResult<Option<QuarantinedMail>> {
+    let message: Option<MailMessage> = call_mail_zome("get_message", input.message_hash.clone())?;
+    let message = message.ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Message not found".into()
+    )))?;
+
+    let item = MailItem {
+        message_hash: input.message_hash,
+        message,
+    };
+    Ok(partition_items(vec![item], input.min_trust)?.1.pop())
+}
+
+/// Release a quarantined message and whitelist its sender
+///
+/// The message reaches the inbox from now on, and the release counts as
+/// strong evidence in the sender's favour in our local trust calculation.
+/// Only mail delivered to our own inbox can be released, so a sender cannot
+/// be vouched for with a message that never reached us.
+#[hdk_extern]
+pub fn release_from_quarantine(message_hash: ActionHash) -> ExternResult<QuarantineRelease> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let inbox = get_links(GetLinksInputBuilder::try_new(me, LinkTypes::ToInbox)?.build())?;
+    let delivered = inbox
+        .iter()
+        .any(|link| link.target.clone().into_action_hash().as_ref() == Some(&message_hash));
+    if !delivered {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only messages delivered to your inbox can be released".into()
+        )));
+    }
+
+    let record = get(message_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
+        WasmErrorInner::Guest("Message not found".into())
+    ))?;
+
+    let message: MailMessage = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| {
             wasm_error!(WasmErrorInner::Guest(format!(
-                "Failed to decode response: {:?}",
+                "Deserialization error: {:?}",
                 e
             )))
-        })?,
-        _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Zome call failed".into()
-            )))
-        }
+        })?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Invalid message entry".into()
+        )))?;
+
+    if let Some(existing) = my_releases()?
+        .into_iter()
+        .find(|release| release.message_hash == message_hash)
+    {
+        return Ok(existing);
+    }
+
+    let release = QuarantineRelease {
+        message_hash,
+        sender_did: message.from_did,
+        released_at: sys_time()?,
     };
+    create_entry(EntryTypes::QuarantineRelease(release.clone()))?;
 
-    debug!("Got {} total messages", all_messages.len());
+    debug!("Released message from {}", release.sender_did);
+    Ok(release)
+}
 
-    // Filter by trust score
-    let mut trusted_messages = Vec::new();
+/// Always accept or always reject senders matching a DID or DID prefix
+///
+/// Rules are private and checked before any trust score. Setting a rule for
+/// an existing pattern replaces it.
+#[hdk_extern]
+pub fn set_sender_rule(input: SetSenderRuleInput) -> ExternResult<SenderRule> {
+    let rule = SenderRule {
+        pattern: input.pattern.trim().to_string(),
+        action: input.action,
+        created_at: sys_time()?,
+    };
 
-    for message in all_messages {
-        // Check sender's trust score
-        let trust_score = check_sender_trust(message.from_did.clone())?;
+    match latest_sender_rules()?.remove(&rule.pattern) {
+        Some((action_hash, existing)) => {
+            if existing.action == rule.action {
+                return Ok(existing);
+            }
+            update_entry(action_hash, EntryTypes::SenderRule(rule.clone()))?;
+        }
+        None => {
+            create_entry(EntryTypes::SenderRule(rule.clone()))?;
+        }
+    }
 
-        if trust_score >= min_trust {
-            trusted_messages.push(message);
-        } else {
-            debug!(
-                "Filtered out message from {} (trust: {} < {})",
-                message.from_did, trust_score, min_trust
-            );
+    debug!("Sender rule {:?} for {}", rule.action, rule.pattern);
+    Ok(rule)
+}
+
+/// Remove the allow or block rule for a pattern
+#[hdk_extern]
+pub fn remove_sender_rule(pattern: String) -> ExternResult<ActionHash> {
+    let pattern = pattern.trim().to_string();
+    let (action_hash, _) = latest_sender_rules()?
+        .remove(&pattern)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "No sender rule for {}",
+            pattern
+        ))))?;
+
+    delete_entry(action_hash)
+}
+
+/// All allow/block rules, most specific first
+#[hdk_extern]
+pub fn list_sender_rules(_: ()) -> ExternResult<Vec<SenderRule>> {
+    let mut rules: Vec<SenderRule> = latest_sender_rules()?
+        .into_values()
+        .map(|(_, rule)| rule)
+        .collect();
+    rules.sort_by(|a, b| {
+        b.specificity()
+            .cmp(&a.specificity())
+            .then_with(|| a.pattern.cmp(&b.pattern))
+    });
+    Ok(rules)
+}
+
+/// File a spam report for every quarantined message from a blocked sender
+///
+/// Messages we already reported are skipped, and reporting stops at the
+/// daily report limit. Returns how many reports were filed.
+#[hdk_extern]
+pub fn report_blocked_senders(_: ()) -> ExternResult<u32> {
+    let my_reports = my_spam_reports()?;
+    let reported: HashSet<ActionHash> = my_reports
+        .iter()
+        .map(|report| report.message_hash.clone())
+        .collect();
+
+    let now = sys_time()?;
+    let day_ago = Timestamp::from_micros(now.as_micros() - 24 * 60 * 60 * 1_000_000);
+    let max_per_day = DnaProperties::get()?.max_spam_reports_per_day;
+    let mut remaining = max_per_day
+        .saturating_sub(my_reports.iter().filter(|r| r.reported_at > day_ago).count());
+
+    // A zero threshold quarantines nothing but blocked senders
+    let mut filed = 0;
+    for quarantined in partition_inbox(0.0)?.1 {
+        let Some(pattern) = quarantined.blocked_by else {
+            continue;
+        };
+        if reported.contains(&quarantined.item.message_hash) {
+            continue;
+        }
+        if remaining == 0 {
+            debug!("Spam report limit reached; remaining blocked mail left unreported");
+            break;
         }
+
+        report_spam(SpamReportInput {
+            message_hash: quarantined.item.message_hash,
+            reason: format!("Sender blocked by rule {}", pattern),
+        })?;
+        remaining -= 1;
+        filed += 1;
     }
 
-    debug!("Returning {} trusted messages", trusted_messages.len());
-    Ok(trusted_messages)
+    Ok(filed)
 }
 
 /// Get all trust scores (for admin/debugging)
+///
+/// Returns the newest score per DID and source.
 #[hdk_extern]
 pub fn get_all_trust_scores(_: ()) -> ExternResult<Vec<TrustScore>> {
     let index_path = Path::from("trust_index");
@@ -154,23 +429,22 @@ pub fn get_all_trust_scores(_: ()) -> ExternResult<Vec<TrustScore>> {
     let links =
         get_links(GetLinksInputBuilder::try_new(index_hash, LinkTypes::TrustIndex)?.build())?;
 
-    let mut scores = Vec::new();
+    let me = agent_info()?.agent_initial_pubkey;
+    let properties = DnaProperties::get()?;
+
+    let mut latest: BTreeMap<(String, String), PublishedScore> = BTreeMap::new();
     for link in links {
-        let hash_any_dht: AnyDhtHash =
-            ActionHash::from_raw_39(link.target.get_raw_39().to_vec()).into();
-        if let Some(record) = get(hash_any_dht, GetOptions::default())? {
-            if let Some(score) = record.entry().to_app_option::<TrustScore>().map_err(|e| {
-                wasm_error!(WasmErrorInner::Guest(format!(
-                    "Deserialization error: {:?}",
-                    e
-                )))
-            })? {
-                scores.push(score);
-            }
+        let Some(published) = fetch_published_score(&link)? else {
+            continue;
+        };
+        if !published.is_trusted(&me, &properties) {
+            continue;
         }
+        let key = (published.score.did.clone(), published.score.matl_source.clone());
+        keep_newest(&mut latest, key, published);
     }
 
-    Ok(scores)
+    Ok(latest.into_values().map(|p| p.score).collect())
 }
 
 /// Report spam/malicious message
@@ -183,7 +457,7 @@ pub fn report_spam(input: SpamReportInput) -> ExternResult<()> {
     );
 
     // Get the message to identify the sender
-    let record = get(input.message_hash, GetOptions::default())?.ok_or(wasm_error!(
+    let record = get(input.message_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
         WasmErrorInner::Guest("Message not found".into())
     ))?;
 
@@ -201,12 +475,48 @@ pub fn report_spam(input: SpamReportInput) -> ExternResult<()> {
         )))?;
 
     let reporter = agent_info()?.agent_initial_pubkey;
+
+    // Only mail delivered to us can be reported; the inbox link proves it
+    let inbox_links = get_links(
+        GetLinksInputBuilder::try_new(reporter.clone(), LinkTypes::ToInbox)?.build(),
+    )?;
+    let inbox_link = inbox_links
+        .into_iter()
+        .find(|link| {
+            ActionHash::from_raw_39(link.target.get_raw_39().to_vec()) == input.message_hash
+        })
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Only messages delivered to your inbox can be reported".into()
+        )))?;
+
+    // Mirror the integrity zome's limits so callers get a clear error
+    let my_reports = my_spam_reports()?;
+    if my_reports
+        .iter()
+        .any(|report| report.message_hash == input.message_hash)
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You have already reported this message".into()
+        )));
+    }
+
+    let now = sys_time()?;
+    let day_ago = Timestamp::from_micros(now.as_micros() - 24 * 60 * 60 * 1_000_000);
+    let max_per_day = DnaProperties::get()?.max_spam_reports_per_day;
+    if my_reports.iter().filter(|r| r.reported_at > day_ago).count() >= max_per_day {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Spam report limit reached ({} per day); try again later",
+            max_per_day
+        ))));
+    }
+
     let report = SpamReport {
         reporter,
         spammer_did: message.from_did.clone(),
         message_hash: input.message_hash.clone(),
+        inbox_link: inbox_link.create_link_hash,
         reason: input.reason,
-        reported_at: sys_time()?,
+        reported_at: now,
     };
 
     let report_hash = create_entry(EntryTypes::SpamReport(report))?;
@@ -214,7 +524,17 @@ pub fn report_spam(input: SpamReportInput) -> ExternResult<()> {
     let path = Path::from("spam_reports");
     path.ensure()?;
     let path_hash = path.path_entry_hash()?;
-    create_link(path_hash, report_hash, LinkTypes::SpamReports, ())?;
+    create_link(path_hash, report_hash.clone(), LinkTypes::SpamReports, ())?;
+
+    // Per-sender index used by the local trust calculation
+    let sender_path = spam_reports_path(&message.from_did);
+    sender_path.ensure()?;
+    create_link(
+        sender_path.path_entry_hash()?,
+        report_hash,
+        LinkTypes::SpamReports,
+        (),
+    )?;
 
     debug!("Spam report registered for sender: {}", message.from_did);
     Ok(())
@@ -256,15 +576,527 @@ pub fn get_spam_reports(input: GetSpamReportsInput) -> ExternResult<Vec<SpamRepo
 
 // === Helper Functions ===
 
-/// Calculate a simple local trust score based on message history
-/// This is a fallback when MATL scores are not available
-fn calculate_local_trust(did: &str) -> ExternResult<f64> {
-    // In production, this would:
-    // 1. Count total messages sent by this DID
-    // 2. Count spam reports
-    // 3. Calculate score = 1.0 - (spam_reports / total_messages)
-
-    // For MVP, return neutral
-    debug!("Calculating local trust for {} (MVP: returning 0.5)", did);
-    Ok(0.5)
+/// Split the inbox into messages that pass `min_trust` and quarantined ones
+fn partition_inbox(min_trust: f64) -> ExternResult<(Vec<MailItem>, Vec<QuarantinedMail>)> {
+    let all_messages: Vec<MailItem> = call_mail_zome("get_inbox", ())?;
+    partition_items(all_messages, min_trust)
+}
+
+/// Call a `mail_messages` function on our own cell
+fn call_mail_zome<I, O>(function: &str, payload: I) -> ExternResult<O>
+where
+    I: Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response: ZomeCallResponse = call(
+        CallTargetCell::Local,
+        "mail_messages",
+        function.into(),
+        None,
+        payload,
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => decode(&result.into_vec()).map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to decode response: {:?}",
+                e
+            )))
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Guest(
+            "Zome call failed".into()
+        ))),
+    }
+}
+
+/// Split messages into those that pass `min_trust` and quarantined ones
+///
+/// Mail whose author did not hold its `from_did` when it was sent is always
+/// quarantined: a retired or leaked key can bind the DID afresh on a new
+/// agent, and neither a release nor an allow rule for the DID should let
+/// that through. Mail sent before a rotation or revocation is judged
+/// against the holder at the time, so it is not affected. Otherwise released messages always pass, then the most
+/// specific sender rule decides, and only senders without a rule are
+/// compared against `min_trust`. Each sender's trust is computed once.
+fn partition_items(
+    all_messages: Vec<MailItem>,
+    min_trust: f64,
+) -> ExternResult<(Vec<MailItem>, Vec<QuarantinedMail>)> {
+    debug!("Got {} total messages", all_messages.len());
+
+    let released: HashSet<ActionHash> = my_releases()?
+        .into_iter()
+        .map(|release| release.message_hash)
+        .collect();
+    let rules = list_sender_rules(())?;
+
+    let mut trusted_messages = Vec::new();
+    let mut quarantined = Vec::new();
+    let mut sender_trust: HashMap<String, TrustBreakdown> = HashMap::new();
+    let mut histories: HashMap<String, Vec<DidTenure>> = HashMap::new();
+
+    for item in all_messages {
+        let from_did = &item.message.from_did;
+        if !histories.contains_key(from_did) {
+            let history = call_mail_zome("did_custody_history", from_did.clone())?;
+            histories.insert(from_did.clone(), history);
+        }
+        let author = get(item.message_hash.clone(), GetOptions::default())?
+            .map(|record| record.action().author().clone());
+        let holder = did_holder_at(&histories[from_did], &item.message.timestamp);
+        if author.is_none() || holder != author.as_ref() {
+            let reason = format!("Sent by a key that did not hold {} at the time", from_did);
+            quarantined.push(QuarantinedMail {
+                item,
+                trust_score: 0.0,
+                reason,
+                blocked_by: None,
+            });
+            continue;
+        }
+
+        if released.contains(&item.message_hash) {
+            trusted_messages.push(item);
+            continue;
+        }
+
+        // Rules are sorted most specific first, so the first match decides
+        if let Some(rule) = rules.iter().find(|rule| rule.matches(&item.message.from_did)) {
+            match rule.action {
+                RuleAction::Allow => trusted_messages.push(item),
+                RuleAction::Block => quarantined.push(QuarantinedMail {
+                    item,
+                    trust_score: 0.0,
+                    reason: format!("Sender is blocked by rule {}", rule.pattern),
+                    blocked_by: Some(rule.pattern.clone()),
+                }),
+            }
+            continue;
+        }
+
+        // A zero threshold lets everyone through without computing trust
+        if min_trust <= 0.0 {
+            trusted_messages.push(item);
+            continue;
+        }
+
+        // Check sender's trust score
+        let from_did = item.message.from_did.clone();
+        let breakdown = match sender_trust.get(&from_did) {
+            Some(breakdown) => breakdown.clone(),
+            None => {
+                let breakdown = get_trust_breakdown(from_did.clone())?;
+                sender_trust.insert(from_did, breakdown.clone());
+                breakdown
+            }
+        };
+
+        if breakdown.score >= min_trust {
+            trusted_messages.push(item);
+        } else {
+            debug!(
+                "Quarantined message from {} (trust: {} < {})",
+                item.message.from_did, breakdown.score, min_trust
+            );
+            quarantined.push(QuarantinedMail {
+                item,
+                trust_score: breakdown.score,
+                reason: quarantine_reason(&breakdown, min_trust),
+                blocked_by: None,
+            });
+        }
+    }
+
+    debug!(
+        "{} trusted, {} quarantined message(s)",
+        trusted_messages.len(),
+        quarantined.len()
+    );
+    Ok((trusted_messages, quarantined))
+}
+
+/// Why a sender fell below the threshold, naming the dominant source
+fn quarantine_reason(breakdown: &TrustBreakdown, min_trust: f64) -> String {
+    let dominant = breakdown
+        .sources
+        .iter()
+        .max_by(|a, b| a.share.total_cmp(&b.share))
+        .filter(|source| source.share > 0.0);
+
+    let basis = match dominant {
+        Some(source) => match &source.detail {
+            Some(detail) => format!("{} {:.2}: {}", source.source, source.score, detail),
+            None => format!("{} score {:.2}", source.source, source.score),
+        },
+        None => "unknown sender with no history".to_string(),
+    };
+
+    format!(
+        "Sender trust {:.2} is below {:.2} ({})",
+        breakdown.score, min_trust, basis
+    )
+}
+
+/// Latest sender rule per pattern with the action that wrote it, skipping
+/// removed ones
+///
+/// Rules are private entries, so they are read from our own source chain.
+fn latest_sender_rules() -> ExternResult<HashMap<String, (ActionHash, SenderRule)>> {
+    let deleted: HashSet<ActionHash> = query(
+        ChainQueryFilter::new().action_type(ActionType::Delete),
+    )?
+    .into_iter()
+    .filter_map(|record| match record.action() {
+        Action::Delete(delete) => Some(delete.deletes_address.clone()),
+        _ => None,
+    })
+    .collect();
+
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::SenderRule.try_into()?)
+        .include_entries(true);
+
+    // Records come back in chain order, so later entries overwrite earlier ones
+    let mut rules = HashMap::new();
+    for record in query(filter)? {
+        if let Some(rule) = record.entry().to_app_option::<SenderRule>().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Deserialization error: {:?}",
+                e
+            )))
+        })? {
+            rules.insert(rule.pattern.clone(), (record.action_address().clone(), rule));
+        }
+    }
+
+    rules.retain(|_, (hash, _)| !deleted.contains(hash));
+    Ok(rules)
+}
+
+/// Messages we have released from quarantine, read from our own source chain
+fn my_releases() -> ExternResult<Vec<QuarantineRelease>> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::QuarantineRelease.try_into()?)
+        .include_entries(true);
+
+    let mut releases = Vec::new();
+    for record in query(filter)? {
+        if let Some(release) = record.entry().to_app_option::<QuarantineRelease>().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Deserialization error: {:?}",
+                e
+            )))
+        })? {
+            releases.push(release);
+        }
+    }
+    Ok(releases)
+}
+
+/// A trust score together with the action that published it
+struct PublishedScore {
+    score: TrustScore,
+    author: AgentPubKey,
+    action_hash: ActionHash,
+    published_at: Timestamp,
+}
+
+impl PublishedScore {
+    /// Ordering key: the score's own timestamp, then when it was published
+    fn published_key(&self) -> (Timestamp, Timestamp) {
+        (self.score.last_updated, self.published_at)
+    }
+
+    /// Whether we accept this score: authorities for published sources, and
+    /// only our own hand-set scores for the manual source
+    fn is_trusted(&self, me: &AgentPubKey, properties: &DnaProperties) -> bool {
+        if self.score.matl_source == MANUAL_TRUST_SOURCE {
+            &self.author == me
+        } else {
+            properties.is_trust_authority(&self.author)
+        }
+    }
+}
+
+/// Every score linked from a DID's trust path, with the link pointing to it
+fn published_scores(did: &str) -> ExternResult<Vec<(Link, PublishedScore)>> {
+    let path_hash = trust_path(did).path_entry_hash()?;
+    let links =
+        get_links(GetLinksInputBuilder::try_new(path_hash, LinkTypes::TrustByDid)?.build())?;
+
+    let mut scores = Vec::new();
+    for link in links {
+        if let Some(published) = fetch_published_score(&link)? {
+            if published.score.did == did {
+                scores.push((link, published));
+            }
+        }
+    }
+    Ok(scores)
+}
+
+/// The newest accepted score per source for a DID
+fn latest_scores_by_source(did: &str) -> ExternResult<BTreeMap<String, PublishedScore>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let properties = DnaProperties::get()?;
+
+    let mut latest = BTreeMap::new();
+    for (_, published) in published_scores(did)? {
+        if published.is_trusted(&me, &properties) {
+            let source = published.score.matl_source.clone();
+            keep_newest(&mut latest, source, published);
+        }
+    }
+    Ok(latest)
+}
+
+fn keep_newest<K: Ord>(
+    latest: &mut BTreeMap<K, PublishedScore>,
+    key: K,
+    published: PublishedScore,
+) {
+    match latest.get(&key) {
+        Some(current) if current.published_key() >= published.published_key() => {}
+        _ => {
+            latest.insert(key, published);
+        }
+    }
+}
+
+fn fetch_published_score(link: &Link) -> ExternResult<Option<PublishedScore>> {
+    let action_hash = ActionHash::from_raw_39(link.target.get_raw_39().to_vec());
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+
+    let score: Option<TrustScore> = record.entry().to_app_option().map_err(|e| {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "Deserialization error: {:?}",
+            e
+        )))
+    })?;
+
+    Ok(score.map(|score| PublishedScore {
+        score,
+        author: record.action().author().clone(),
+        action_hash,
+        published_at: record.action().timestamp(),
+    }))
+}
+
+fn trust_path(did: &str) -> Path {
+    Path::from(format!("trust.{}", did))
+}
+
+/// Prior pseudo-counts for a DID with no history: a fresh DID starts at
+/// 1.5 / (1.5 + 3.5) = 0.3, below the default inbox threshold
+const PRIOR_GOOD: f64 = 1.5;
+const PRIOR_BAD: f64 = 3.5;
+
+/// Evidence contributed by each agent the sender has exchanged mail with,
+/// and the most that correspondence alone can earn: at most
+/// (1.5 + 4) / (5 + 4) ~= 0.61, so it never outweighs a couple of reports
+const MESSAGE_WEIGHT: f64 = 0.25;
+const MAX_MESSAGE_EVIDENCE: f64 = 4.0;
+
+/// Most recent inbound senders checked for a reply when counting
+/// correspondents, which bounds the lookups per trust calculation
+const MAX_CORRESPONDENTS_CHECKED: usize = 32;
+
+/// Evidence contributed by one spam report from a fully established reporter
+const REPORT_WEIGHT: f64 = 2.0;
+
+/// Evidence contributed by each message we released from quarantine; one
+/// release lifts an unknown sender above the default threshold
+const RELEASE_WEIGHT: f64 = 3.0;
+
+/// Most reports from a single reporter that count against one DID
+const MAX_REPORTS_PER_REPORTER: f64 = 2.0;
+
+/// Trust computed from a sender's history
+struct LocalTrust {
+    score: f64,
+    /// Share of the evidence that is real history rather than the prior
+    confidence: f64,
+    /// Distinct agents the sender has exchanged mail with
+    correspondents: usize,
+    reports: usize,
+    releases: usize,
+}
+
+/// Calculate a local trust score from the sender's history on the DHT
+///
+/// Two-way correspondence and our own quarantine releases count in the
+/// sender's favour and spam reports against them. Only agents who both sent
+/// mail to the sender and got mail back count, once each: delivering to
+/// made-up agent keys or mailing one accomplice over and over earns nothing
+/// extra. Correspondence and reports decay by
+/// their half-lives in the DNA properties so reformed senders recover;
+/// releases are deliberate and never decay. A report counts in proportion
+/// to its reporter's standing and each reporter's total is capped, so a
+/// handful of agents or freshly created accounts cannot sink a sender on
+/// their own.
+fn calculate_local_trust(did: &str, properties: &DnaProperties) -> ExternResult<LocalTrust> {
+    let now = sys_time()?;
+
+    let mut good = 0.0;
+    let mut correspondents = 0;
+    if let Some(agent) = did_agent(did)? {
+        for delivered_at in correspondents_of(&agent)?.into_values() {
+            good += MESSAGE_WEIGHT
+                * decay_weight(delivered_at, now, properties.message_half_life_days);
+            correspondents += 1;
+        }
+    }
+    let mut good = good.min(MAX_MESSAGE_EVIDENCE);
+
+    // Messages we released from quarantine vouch for the sender
+    let releases = my_releases()?
+        .into_iter()
+        .filter(|release| release.sender_did == did)
+        .count();
+    good += RELEASE_WEIGHT * releases as f64;
+
+    // Decayed report count per reporter, capped so one agent's reports only
+    // ever count like a couple of independent ones
+    let reports = spam_reports_against(did)?;
+    let mut by_reporter: HashMap<AgentPubKey, f64> = HashMap::new();
+    for report in &reports {
+        *by_reporter.entry(report.reporter.clone()).or_default() += decay_weight(
+            report.reported_at,
+            now,
+            properties.spam_report_half_life_days,
+        );
+    }
+
+    let mut bad = 0.0;
+    for (reporter, count) in by_reporter {
+        bad += REPORT_WEIGHT
+            * reporter_weight(&reporter)?
+            * count.min(MAX_REPORTS_PER_REPORTER);
+    }
+
+    let prior = PRIOR_GOOD + PRIOR_BAD;
+    let score = (PRIOR_GOOD + good) / (prior + good + bad);
+    debug!(
+        "Local trust for {}: {:.3} (good {:.2}, bad {:.2})",
+        did, score, good, bad
+    );
+    Ok(LocalTrust {
+        score,
+        confidence: (good + bad) / (prior + good + bad),
+        correspondents,
+        reports: reports.len(),
+        releases,
+    })
+}
+
+/// How much a reporter's spam reports count, from 0.25 for an agent that
+/// has never exchanged mail with anyone up to 1.0 for an established
+/// correspondent
+fn reporter_weight(reporter: &AgentPubKey) -> ExternResult<f64> {
+    let reached = correspondents_of(reporter)?.len() as f64;
+    Ok(0.25 + 0.75 * (1.0 - (-reached / 5.0).exp()))
+}
+
+/// Agents `agent` has exchanged mail with in both directions, each with the
+/// time of `agent`'s latest delivery to them
+///
+/// Candidates are the authors of `agent`'s own inbox links: validation only
+/// accepts those from an agent that wrote the message under a DID bound to
+/// it, so made-up agent keys never appear. The most recent
+/// [`MAX_CORRESPONDENTS_CHECKED`] of them are kept if `agent` has an
+/// undeleted inbox link of its own in theirs.
+fn correspondents_of(agent: &AgentPubKey) -> ExternResult<HashMap<AgentPubKey, Timestamp>> {
+    let inbound =
+        get_links(GetLinksInputBuilder::try_new(agent.clone(), LinkTypes::ToInbox)?.build())?;
+
+    let mut latest_inbound: HashMap<AgentPubKey, Timestamp> = HashMap::new();
+    for link in inbound {
+        if &link.author == agent {
+            continue;
+        }
+        let latest = latest_inbound.entry(link.author).or_insert(link.timestamp);
+        if link.timestamp > *latest {
+            *latest = link.timestamp;
+        }
+    }
+    let mut candidates: Vec<(AgentPubKey, Timestamp)> = latest_inbound.into_iter().collect();
+    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+    candidates.truncate(MAX_CORRESPONDENTS_CHECKED);
+
+    let mut correspondents = HashMap::new();
+    for (peer, _) in candidates {
+        let peer_inbox =
+            get_links(GetLinksInputBuilder::try_new(peer.clone(), LinkTypes::ToInbox)?.build())?;
+        let replied_at = peer_inbox
+            .iter()
+            .filter(|link| &link.author == agent)
+            .map(|link| link.timestamp)
+            .max();
+        if let Some(delivered_at) = replied_at {
+            correspondents.insert(peer, delivered_at);
+        }
+    }
+    Ok(correspondents)
+}
+
+/// Spam reports filed against a DID
+fn spam_reports_against(did: &str) -> ExternResult<Vec<SpamReport>> {
+    let path_hash = spam_reports_path(did).path_entry_hash()?;
+    let links =
+        get_links(GetLinksInputBuilder::try_new(path_hash, LinkTypes::SpamReports)?.build())?;
+
+    let mut reports = Vec::new();
+    for link in links {
+        let hash_any_dht: AnyDhtHash =
+            ActionHash::from_raw_39(link.target.get_raw_39().to_vec()).into();
+        if let Some(record) = get(hash_any_dht, GetOptions::default())? {
+            if let Some(report) = record.entry().to_app_option::<SpamReport>().map_err(|e| {
+                wasm_error!(WasmErrorInner::Guest(format!(
+                    "Deserialization error: {:?}",
+                    e
+                )))
+            })? {
+                if report.spammer_did == did {
+                    reports.push(report);
+                }
+            }
+        }
+    }
+
+    Ok(reports)
+}
+
+/// The agent currently holding a DID on the DHT, if any
+///
+/// Follows key rotations, so a DID's mail history counts from its current key.
+fn did_agent(did: &str) -> ExternResult<Option<AgentPubKey>> {
+    let record: Option<DidRecord> = call_mail_zome("resolve_did", did.to_string())?;
+    Ok(record.map(|record| record.agent_pub_key))
+}
+
+/// Spam reports we have filed, read from our own source chain
+fn my_spam_reports() -> ExternResult<Vec<SpamReport>> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::SpamReport.try_into()?)
+        .include_entries(true);
+
+    let mut reports = Vec::new();
+    for record in query(filter)? {
+        if let Some(report) = record.entry().to_app_option::<SpamReport>().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Deserialization error: {:?}",
+                e
+            )))
+        })? {
+            reports.push(report);
+        }
+    }
+    Ok(reports)
+}
+
+/// Per-DID index of spam reports
+fn spam_reports_path(did: &str) -> Path {
+    Path::from(format!("spam_reports.{}", did))
 }
@@ -52,11 +52,19 @@ nix develop --command bash -c '
     cd ../..
     echo ""
 
+    # Build contacts zome
+    echo "📦 Building contacts zome..."
+    cd zomes/contacts
+    cargo build --release --target wasm32-unknown-unknown 2>&1 | head -50
+    cd ../..
+    echo ""
+
     # Copy WASM files
     echo "📋 Copying WASM files..."
     cp integrity/target/wasm32-unknown-unknown/release/mycelix_mail_integrity.wasm integrity.wasm
     cp zomes/mail_messages/target/wasm32-unknown-unknown/release/mail_messages.wasm mail_messages.wasm
     cp zomes/trust_filter/target/wasm32-unknown-unknown/release/trust_filter.wasm trust_filter.wasm
+    cp zomes/contacts/target/wasm32-unknown-unknown/release/contacts.wasm contacts.wasm
 
     # Check files exist and show sizes
     echo ""